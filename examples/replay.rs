@@ -0,0 +1,108 @@
+//! 回放录制的检测会话
+//!
+//! cargo run --example replay -- <session.jsonl> <model.onnx> [speed]
+//!
+//! speed: `real` 按原速，数字为加速倍数，缺省为不等待
+//!
+//! 广播同时按录制时间驱动在场状态机，锁屏交给只记录的 `FakeLocker`，
+//! 输出回放中会在何时锁屏，并与录制时的锁屏决策比对
+
+use std::error::Error;
+
+use dioxus_demo::{
+    ble::{
+        model::Model,
+        presence_detector::PresenceDetector,
+        presence_state::TransitionReason,
+        session::{ReplaySpeed, SessionEntry, SessionReplayer},
+    },
+    domain::value_objects::ModelResult,
+    dto::detection::AlgoConfig,
+};
+use tokio::sync::mpsc;
+
+/// 只记录不锁屏
+#[derive(Default)]
+struct FakeLocker {
+    locks: Vec<(i64, TransitionReason)>,
+}
+
+impl FakeLocker {
+    fn lock(&mut self, ts: i64, reason: TransitionReason) {
+        println!("would lock at {}: {}", ts, reason);
+        self.locks.push((ts, reason));
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut args = std::env::args().skip(1);
+    let session_path = args.next().ok_or("missing session file")?;
+    let model_path = args.next().ok_or("missing model file")?;
+    let speed = match args.next().as_deref() {
+        None => ReplaySpeed::Instant,
+        Some("real") => ReplaySpeed::RealTime,
+        Some(n) => ReplaySpeed::Accelerated(n.parse()?),
+    };
+
+    let replayer = SessionReplayer::load(&session_path).await?;
+    let model = Model::new(&std::fs::read(&model_path)?).map_err(|e| e.to_string())?;
+    let config = AlgoConfig::default();
+
+    // 在场状态机只依赖录制时间，与回放速度无关
+    let mut locker = FakeLocker::default();
+    for transition in replayer.replay_presence(&config) {
+        println!(
+            "{} {} -> {}: {}",
+            transition.ts, transition.from, transition.to, transition.reason
+        );
+        if transition.is_departure() {
+            locker.lock(transition.ts, transition.reason);
+        }
+    }
+
+    let (sample_tx, mut sample_rx) = mpsc::channel::<Vec<f32>>(100);
+    let detector = PresenceDetector::new(config, sample_tx).await?;
+
+    let replay = tokio::spawn(async move {
+        let res = replayer.replay(&detector, speed).await;
+        (replayer, res)
+    });
+
+    let mut predictions = Vec::new();
+    while let Some(window) = sample_rx.recv().await {
+        let res = model
+            .inference(window.clone())
+            .unwrap_or(ModelResult::Unknown);
+        println!("{:?} => {}", window, res);
+        predictions.push(res);
+    }
+
+    let (replayer, res) = replay.await?;
+    res?;
+
+    // 与录制时的预测比对
+    let recorded = replayer.predictions();
+    let mismatched = recorded
+        .iter()
+        .zip(predictions.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    println!(
+        "replayed {} windows, recorded {} predictions, {} mismatched",
+        predictions.len(),
+        recorded.len(),
+        mismatched
+    );
+    let recorded_locks = replayer
+        .entries()
+        .iter()
+        .filter(|e| matches!(e, SessionEntry::Lock { locked: true, .. }))
+        .count();
+    println!(
+        "replay would lock {} times, recorded {} locks",
+        locker.locks.len(),
+        recorded_locks
+    );
+    Ok(())
+}
@@ -0,0 +1,257 @@
+app-name = BLE Unlock
+
+## Menu
+menu-home = Home
+menu-label = Label
+menu-stats = Stats
+menu-device = Devices
+menu-log = Log
+menu-settings = Settings
+menu-about = About
+
+## Common
+common-back = ← Back
+common-none = None
+common-loading = Loading ...
+common-error = Error: { $error }
+
+startup-failed = Failed to start
+startup-failed-hint = Fix the configuration or check Bluetooth, then restart the app. See the log for details.
+
+## Errors
+error-adapter-unavailable = No Bluetooth adapter found. Plug in an adapter or turn Bluetooth on
+error-adapter = Cannot access Bluetooth. Check the Bluetooth service and permissions
+error-device-not-found = Device { $id } is not nearby
+error-gatt = Failed to connect to the device. Move closer and try again
+error-unsupported-platform = Auto lock is not supported on this system
+error-ble = Bluetooth error: { $error }
+error-model-read = Cannot read model file { $path }
+error-model-load = Failed to load the model. Check that the model file is complete
+error-model = Inference error: { $error }
+error-config-read = Cannot read config file { $path }
+error-config-parse = Config file { $path } is malformed: { $error }
+error-config-invalid = Invalid setting { $field }: { $reason }
+error-db = Database access failed. Check db_url
+error-other = { $error }
+
+config-reason-model-input = must equal the model input length { $len }
+config-reason-between = must be between { $min } and { $max }
+config-reason-at-least = must be at least { $min }
+config-reason-at-most = must be at most { $max }
+config-reason-not-above = must not exceed { $field }
+config-reason-quantiles = quantiles must satisfy 0 < absent_quantile < present_quantile < 1
+config-reason-required = is required by the selected unlock policy
+config-reason-min-length = must be at least { $min } characters
+config-reason-sqlite-only = only sqlite: URLs are supported
+config-reason-file-missing = { $path } does not exist
+config-reason-no-config-dir = no config directory available
+
+## Model predictions
+model-stationary = Stationary
+model-moving-away = Moving away
+model-moving-closer = Moving closer
+model-unknown = Unknown
+model-unlabeled = Unlabeled
+
+## Signal quality
+signal-excellent = Excellent
+signal-good = Good
+signal-fair = Fair
+signal-weak = Weak
+signal-lost = No signal
+
+## Bluetooth adapter
+adapter-missing = No Bluetooth adapter found
+adapter-powered-off = Bluetooth is off
+adapter-ready = Bluetooth ready
+adapter-recover-hint = Scanning resumes automatically once it is back
+adapter-picker-title = Bluetooth adapter
+adapter-picker-auto = Automatic
+adapter-picker-off = { $name } (off)
+adapter-picker-multi = Multiple adapters
+
+## Snooze
+snooze-disabled = Auto-lock is on
+snooze-until = Paused, resumes in about { $minutes ->
+    [one] 1 minute
+   *[other] { $minutes } minutes
+}
+snooze-until-unlock = Paused until the next unlock
+snooze-indefinite = Paused
+snooze-quiet-hours = Quiet hours { $start }-{ $end }
+snooze-app = { $name } is running
+snooze-fullscreen = Fullscreen
+snooze-15-minutes = 15 min
+snooze-1-hour = 1 hour
+snooze-until-unlock-button = Until unlock
+snooze-cancel = Cancel
+
+## Presence
+
+presence-unknown = Checking
+presence-present = At desk
+presence-leaving = Leaving
+presence-absent = Away
+presence-returning = Returning
+
+## Transition and lock reasons
+
+transition-strong = Signal { $rssi } dBm is at or above the presence threshold { $threshold } dBm
+transition-weak = Signal { $rssi } dBm is below the away threshold { $threshold } dBm
+transition-quiet = No signal at or above the away threshold { $threshold } dBm for { $secs } s
+transition-sustained = Signal stayed at or above the away threshold for { $secs } s
+lock-reason-adapter = { $adapter }; { $reason }
+lock-reason-connected = Target came close and passed the connection check
+lock-reason-challenge = Target came close and passed the challenge-response check
+
+## Chart
+chart-threshold = Threshold { $threshold } dBm
+chart-lock = Lock
+chart-absent = Absent { $threshold } dBm
+
+## Devices
+device-list-title = Nearby Bluetooth devices
+device-list-select = Select
+device-list-signal = Signal { $quality } { $percent }% { $trend }
+device-unknown = Unknown device
+device-out-of-range = Device is out of range or expired
+device-address = Address
+device-fingerprint = Fingerprint
+device-signal-quality = Signal quality
+device-distance = Estimated distance
+device-tx-power = TX power
+device-seen = First / last seen
+device-manufacturer-data = Manufacturer data
+device-unknown-vendor = Unknown vendor
+device-services = Advertised service UUIDs
+device-service-data = Service data
+device-gatt = GATT services
+device-connect = Connect and read
+device-connecting = Connecting...
+device-gatt-hint = Connect to the device to list its services and characteristics
+device-connect-failed = Connection failed: { $error }
+
+## Labeling
+label-title = Batch sample labeling
+label-empty = No samples to label
+label-device = Device { $device }
+label-created = Created: { $created }
+label-selected = Labeled: { $label }
+label-fetch-failed = Failed to fetch: { $error }
+label-save-failed = Failed to label: { $error }
+
+## Stats
+stats-title = Model accuracy
+stats-all-devices = All devices
+stats-all-models = All models
+stats-model-unrecorded = (not recorded)
+stats-failed = Failed to compute stats: { $error }
+stats-accuracy = Accuracy
+stats-labeled = Labeled samples
+stats-correct = Correct predictions
+stats-confusion = Confusion matrix (rows: label, columns: prediction)
+stats-per-class = Per-class metrics
+stats-class = Class
+stats-support = Labeled
+stats-predicted = Predicted
+stats-precision = Precision
+stats-recall = Recall
+stats-daily = Daily accuracy
+stats-no-labels = No labeled samples yet
+
+## Log
+log-title = Log
+log-search = Search
+log-pause = Pause
+log-resume = Resume
+log-clear = Clear
+log-export = Export
+log-exported = Exported to { $path }
+log-export-failed = Export failed: { $error }
+log-total = { $count ->
+    [one] 1 entry
+   *[other] { $count } entries
+}
+
+## Settings
+settings-title = Settings
+settings-general = General
+settings-language = Language
+settings-detection = Detection
+settings-window-size = Window size
+settings-window-size-hint = Fixed by the model input length
+settings-threshold = Threshold (dBm)
+settings-timeout = Lock timeout (s)
+settings-leave-dwell = Signal gap before leaving (s)
+settings-return-dwell = Return confirmation (s)
+settings-batch-size = Write batch size
+settings-lock = Lock policy
+settings-auto-lock = Lock on timeout
+settings-notifications = Desktop notifications
+settings-adapter-lost = When Bluetooth is unavailable
+settings-adapter-lost-closed = Lock on timeout as usual
+settings-adapter-lost-open = Pause the timer
+settings-unlock = When the target returns
+settings-unlock-lock-only = Lock only, never unlock
+settings-unlock-connected = Unlock while the device stays connected
+settings-unlock-challenge = Unlock after companion app verification
+settings-unlock-hint = Requires a sustained approach and a strong signal; stays locked if verification fails or is uncertain
+settings-model = Model
+settings-model-path = Model file
+settings-model-builtin = Leave empty for the built-in model
+settings-model-version = Current version: { $version }
+settings-storage = Storage
+settings-db-url = Database
+settings-db-url-hint = Changing the database location takes effect after a restart
+settings-retention-days = Keep raw records (days)
+settings-max-rows = Max raw records
+settings-save = Save and apply
+settings-saved = Saved and applied
+settings-restart-required = Saved; { $fields } takes effect after a restart
+settings-save-failed = Save failed: { $error }
+settings-trusted = Trusted devices
+settings-trusted-empty = No trusted devices yet
+settings-trusted-current = Current target
+settings-trusted-set-target = Set as target
+settings-trusted-remove = Remove
+settings-trusted-fingerprint = Fingerprint
+settings-trusted-name = Name
+settings-trusted-add = Add
+settings-trusted-unbound = Not bound
+settings-trusted-unbound-hint = The device was not in range when added, so proximity unlock is off; add it again while it is nearby to bind it
+settings-threshold-present = Present
+settings-threshold-absent = Absent
+settings-threshold-apply = Override
+settings-threshold-clear = Auto
+settings-threshold-hourly = Learned from { $samples } readings at this hour
+settings-threshold-daily = Learned from { $samples } readings across the day
+settings-threshold-default = Not enough history, using the default threshold
+settings-threshold-override = Set manually
+
+## About
+about-description = Unlock your devices conveniently and efficiently over Bluetooth.
+about-version = Version: v1.0.0
+about-author = Author: Xiao Ming / Ming
+about-homepage = GitHub project page
+
+## Notifications
+notify-lost-title = Target device left
+notify-lost-body = No advertisement from the target device before the timeout
+notify-found-title = Target device is back
+notify-found-body = Receiving advertisements from the target device again
+notify-locked-title = Screen locked
+notify-low-confidence-title = Low detection confidence
+notify-low-confidence-body = The target state has been unclear for { $minutes } minutes; check the signal or the model
+notify-adapter-title = Bluetooth unavailable
+notify-adapter-body = { $state }; the target cannot be detected until it recovers
+notify-task-failed-title = Background task failed
+notify-task-failed-body = The { $task } task exited unexpectedly and is restarting; check the log
+
+## Tray
+tray-status = { $state } · { $rssi } dBm
+tray-snooze-15-minutes = Pause for 15 minutes
+tray-snooze-1-hour = Pause for 1 hour
+tray-snooze-until-unlock = Pause until next unlock
+tray-resume = Resume auto lock
+tray-show = Show window
+tray-quit = Quit
@@ -0,0 +1,251 @@
+app-name = BLE Unlock
+
+## 菜单
+menu-home = 首页
+menu-label = 标注
+menu-stats = 统计
+menu-device = 设备
+menu-log = 日志
+menu-settings = 设置
+menu-about = 关于
+
+## 通用
+common-back = ← 返回
+common-none = 无
+common-loading = 加载/处理中 ...
+common-error = 出错：{ $error }
+
+startup-failed = 启动失败
+startup-failed-hint = 修改配置或检查蓝牙后重新启动应用，详细信息见日志。
+
+## Errors
+error-adapter-unavailable = 未找到可用的蓝牙适配器，请插入适配器或开启蓝牙
+error-adapter = 无法访问蓝牙，请检查蓝牙服务及权限
+error-device-not-found = 设备 { $id } 不在附近
+error-gatt = 连接设备失败，请靠近后重试
+error-unsupported-platform = 当前系统不支持自动锁屏
+error-ble = 蓝牙异常：{ $error }
+error-model-read = 无法读取模型文件 { $path }
+error-model-load = 模型加载失败，请检查模型文件是否完整
+error-model = 推理出错：{ $error }
+error-config-read = 无法读取配置文件 { $path }
+error-config-parse = 配置文件 { $path } 格式有误：{ $error }
+error-config-invalid = 配置项 { $field } 无效：{ $reason }
+error-db = 数据库访问失败，请检查 db_url
+error-other = { $error }
+
+config-reason-model-input = 应等于模型输入长度 { $len }
+config-reason-between = 应在 { $min } 到 { $max } 之间
+config-reason-at-least = 不能小于 { $min }
+config-reason-at-most = 不能大于 { $max }
+config-reason-not-above = 不能超过 { $field }
+config-reason-quantiles = 分位数需满足 0 < absent_quantile < present_quantile < 1
+config-reason-required = 所选解锁策略需要填写
+config-reason-min-length = 至少 { $min } 个字符
+config-reason-sqlite-only = 仅支持 sqlite: 地址
+config-reason-file-missing = { $path } 不存在
+config-reason-no-config-dir = 找不到可写配置文件的目录
+
+## 模型预测
+model-stationary = 静止
+model-moving-away = 远离
+model-moving-closer = 靠近
+model-unknown = 未知
+model-unlabeled = 未标注
+
+## 信号质量
+signal-excellent = 极好
+signal-good = 良好
+signal-fair = 一般
+signal-weak = 较弱
+signal-lost = 无信号
+
+## 蓝牙适配器
+adapter-missing = 未找到蓝牙适配器
+adapter-powered-off = 蓝牙已关闭
+adapter-ready = 蓝牙正常
+adapter-recover-hint = 恢复后自动重新扫描
+adapter-picker-title = 蓝牙适配器
+adapter-picker-auto = 自动
+adapter-picker-off = { $name }（已关闭）
+adapter-picker-multi = 多适配器
+
+## 暂停
+snooze-disabled = 自动锁屏已启用
+snooze-until = 已暂停，约 { $minutes } 分钟后恢复
+snooze-until-unlock = 已暂停，下次解锁后恢复
+snooze-indefinite = 已暂停
+snooze-quiet-hours = 免打扰时段 { $start }-{ $end }
+snooze-app = { $name } 运行中
+snooze-fullscreen = 全屏中
+snooze-15-minutes = 15 分钟
+snooze-1-hour = 1 小时
+snooze-until-unlock-button = 直到解锁
+snooze-cancel = 取消
+
+## 在场状态
+
+presence-unknown = 判断中
+presence-present = 在座
+presence-leaving = 正在离开
+presence-absent = 已离开
+presence-returning = 正在回来
+
+## 转换与锁屏原因
+
+transition-strong = 信号 { $rssi } dBm 不低于在场阈值 { $threshold } dBm
+transition-weak = 信号 { $rssi } dBm 低于离开阈值 { $threshold } dBm
+transition-quiet = { $secs } 秒未收到不低于离开阈值 { $threshold } dBm 的信号
+transition-sustained = 信号持续 { $secs } 秒未低于离开阈值
+lock-reason-adapter = { $adapter }，{ $reason }
+lock-reason-connected = 目标设备靠近并通过设备连接验证
+lock-reason-challenge = 目标设备靠近并通过质询应答验证
+
+## 曲线
+chart-threshold = 阈值 { $threshold } dBm
+chart-lock = 锁屏
+chart-absent = 离开阈值 { $threshold } dBm
+
+## 设备
+device-list-title = 附近的蓝牙设备
+device-list-select = 选择
+device-list-signal = 信号{ $quality } { $percent }% { $trend }
+device-unknown = 未知设备
+device-out-of-range = 设备不在范围内或已过期
+device-address = 地址
+device-fingerprint = 指纹
+device-signal-quality = 信号质量
+device-distance = 估算距离
+device-tx-power = 发射功率
+device-seen = 首次/最近发现
+device-manufacturer-data = 厂商数据
+device-unknown-vendor = 未知厂商
+device-services = 广播服务 UUID
+device-service-data = 服务数据
+device-gatt = GATT 服务
+device-connect = 连接并读取
+device-connecting = 连接中...
+device-gatt-hint = 连接设备后枚举服务与特征
+device-connect-failed = 连接失败：{ $error }
+
+## 标注
+label-title = 批量样本标注
+label-empty = 没有可标注样本
+label-device = 设备 { $device }
+label-created = 创建: { $created }
+label-selected = 人工标注: { $label }
+label-fetch-failed = 获取失败: { $error }
+label-save-failed = 标注失败: { $error }
+
+## 统计
+stats-title = 模型准确率
+stats-all-devices = 全部设备
+stats-all-models = 全部模型
+stats-model-unrecorded = (未记录)
+stats-failed = 统计失败: { $error }
+stats-accuracy = 准确率
+stats-labeled = 已标注样本
+stats-correct = 预测正确
+stats-confusion = 混淆矩阵（行: 人工标注，列: 预测）
+stats-per-class = 各类别指标
+stats-class = 类别
+stats-support = 标注数
+stats-predicted = 预测数
+stats-precision = 精确率
+stats-recall = 召回率
+stats-daily = 每日准确率
+stats-no-labels = 暂无人工标注样本
+
+## 日志
+log-title = 日志
+log-search = 搜索
+log-pause = 暂停
+log-resume = 继续
+log-clear = 清空
+log-export = 导出
+log-exported = 已导出到 { $path }
+log-export-failed = 导出失败: { $error }
+log-total = { $count } 条
+
+## 设置
+settings-title = 设置
+settings-general = 通用
+settings-language = 界面语言
+settings-detection = 检测参数
+settings-window-size = 窗口大小
+settings-window-size-hint = 由模型输入长度决定
+settings-threshold = 阈值 (dBm)
+settings-timeout = 超时锁屏 (秒)
+settings-leave-dwell = 信号中断多久视为正在离开 (秒)
+settings-return-dwell = 回来后需持续 (秒)
+settings-batch-size = 批量写入条数
+settings-lock = 锁屏策略
+settings-auto-lock = 超时自动锁屏
+settings-notifications = 桌面通知
+settings-adapter-lost = 蓝牙不可用时
+settings-adapter-lost-closed = 照常超时锁屏
+settings-adapter-lost-open = 暂停计时
+settings-unlock = 目标回到附近时
+settings-unlock-lock-only = 只锁屏不解锁
+settings-unlock-connected = 设备保持连接时解锁
+settings-unlock-challenge = 配套应用验证后解锁
+settings-unlock-hint = 需先检测到持续靠近且信号足够强，验证失败或无法判断时保持锁定
+settings-model = 模型
+settings-model-path = 模型文件
+settings-model-builtin = 留空使用内置模型
+settings-model-version = 当前版本：{ $version }
+settings-storage = 数据存储
+settings-db-url = 数据库
+settings-db-url-hint = 修改数据库位置需重启后生效
+settings-retention-days = 原始记录保留 (天)
+settings-max-rows = 原始记录上限 (条)
+settings-save = 保存并应用
+settings-saved = 已保存，设置已生效
+settings-restart-required = 已保存，{ $fields } 需重启后生效
+settings-save-failed = 保存失败：{ $error }
+settings-trusted = 受信任设备
+settings-trusted-empty = 还没有受信任设备
+settings-trusted-current = 当前目标
+settings-trusted-set-target = 设为目标
+settings-trusted-remove = 移除
+settings-trusted-fingerprint = 设备指纹
+settings-trusted-name = 名称
+settings-trusted-add = 添加
+settings-trusted-unbound = 未绑定
+settings-trusted-unbound-hint = 添加时未扫描到该设备，靠近解锁不可用；设备在附近时重新添加即可绑定
+settings-threshold-present = 在场
+settings-threshold-absent = 离开
+settings-threshold-apply = 覆盖
+settings-threshold-clear = 自动
+settings-threshold-hourly = 按本时段 { $samples } 条记录学习
+settings-threshold-daily = 按全天 { $samples } 条记录学习
+settings-threshold-default = 记录不足，使用默认阈值
+settings-threshold-override = 手动指定
+
+## 关于
+about-description = 通过蓝牙技术，实现设备解锁的便捷与高效。
+about-version = 版本: v1.0.0
+about-author = 作者: 小明 / Ming
+about-homepage = GitHub 项目主页
+
+## 通知
+notify-lost-title = 目标设备已离开
+notify-lost-body = 超时未收到目标设备的广播
+notify-found-title = 目标设备已回来
+notify-found-body = 重新收到目标设备的广播
+notify-locked-title = 已锁屏
+notify-low-confidence-title = 检测置信度低
+notify-low-confidence-body = 已有 { $minutes } 分钟无法判断目标设备的状态，请检查信号或模型
+notify-adapter-title = 蓝牙不可用
+notify-adapter-body = { $state }，恢复前无法检测目标设备
+notify-task-failed-title = 后台任务异常
+notify-task-failed-body = { $task } 任务异常退出，正在重启；请查看日志
+
+## 托盘
+tray-status = { $state } · { $rssi } dBm
+tray-snooze-15-minutes = 暂停 15 分钟
+tray-snooze-1-hour = 暂停 1 小时
+tray-snooze-until-unlock = 暂停到下次解锁
+tray-resume = 恢复自动锁屏
+tray-show = 显示窗口
+tray-quit = 退出
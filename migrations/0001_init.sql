@@ -1,6 +1,6 @@
 -- t_sample definition
 
-CREATE TABLE t_sample (
+CREATE TABLE IF NOT EXISTS t_sample (
 	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
 	device TEXT NOT NULL,
 	created_at REAL DEFAULT (CURRENT_TIMESTAMP)
@@ -0,0 +1,3 @@
+-- 记录产生预测的模型版本，用于按模型统计准确率
+
+ALTER TABLE t_sample ADD COLUMN model TEXT DEFAULT ('') NOT NULL;
@@ -0,0 +1,7 @@
+-- 统一 predict/actual 编码：-1 未标注，0 静止，1 远离，2 靠近，3 未知
+-- 旧版本在创建样本时把 actual 写成 ModelResult::Unknown(3)，实际含义是"未标注"，
+-- 人工标注只会写入 0/1/2，因此这些行全部修正为 -1
+-- 旧版本把 DetectionState::Unknown 作为 predict 写成 -1，预测不存在"未标注"，修正为 3
+
+UPDATE t_sample SET actual = -1 WHERE actual NOT IN (0, 1, 2);
+UPDATE t_sample SET predict = 3 WHERE predict NOT IN (0, 1, 2, 3);
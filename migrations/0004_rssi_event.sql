@@ -0,0 +1,11 @@
+-- t_rssi_event definition：原始广播 RSSI 流
+
+CREATE TABLE IF NOT EXISTS t_rssi_event (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	device TEXT NOT NULL,
+	name TEXT NOT NULL,
+	rssi INTEGER NOT NULL,
+	ts INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rssi_event_device_ts ON t_rssi_event (device, ts);
@@ -0,0 +1,7 @@
+-- t_trusted_device definition：受信任的目标设备
+
+CREATE TABLE IF NOT EXISTS t_trusted_device (
+	fingerprint TEXT NOT NULL PRIMARY KEY,
+	name TEXT DEFAULT ('') NOT NULL,
+	created_at TEXT DEFAULT (CURRENT_TIMESTAMP) NOT NULL
+);
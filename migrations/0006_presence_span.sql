@@ -0,0 +1,11 @@
+-- t_presence_span definition：目标设备处于 Present 状态的时段，学习阈值时只统计这些时段内的读数
+-- end_ts 为空表示仍在场
+
+CREATE TABLE IF NOT EXISTS t_presence_span (
+	id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+	device TEXT NOT NULL,
+	start_ts INTEGER NOT NULL,
+	end_ts INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_presence_span_device ON t_presence_span (device, start_ts);
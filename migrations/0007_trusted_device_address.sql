@@ -0,0 +1,4 @@
+-- 受信任设备绑定的地址（没有地址的平台为外设 ID），靠近解锁按它查找设备而不是名称指纹
+-- 为空表示尚未绑定，此时不会解锁
+
+ALTER TABLE t_trusted_device ADD COLUMN address TEXT DEFAULT ('') NOT NULL;
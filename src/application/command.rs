@@ -3,6 +3,13 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateSampleCommand{
     pub device:String,
+    pub model:String,
     pub sample:Vec<f32>,
     pub predict:i32
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelSampleCommand{
+    pub id:i32,
+    pub actual:i32
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
+pub mod rssi_event_service;
 pub mod sample_service;
+pub mod stats_service;
 pub mod command;
 pub mod query;
 pub mod view;
\ No newline at end of file
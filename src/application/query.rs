@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// 从原始 RSSI 记录重建模型窗口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebuildWindowsQuery {
+    pub device: String,
+    /// unix 毫秒
+    pub from: i64,
+    pub to: i64,
+    pub window_size: usize,
+    /// 相邻窗口的起点间隔（条数）
+    pub step: usize,
+}
+
+/// 样本列表过滤条件，`None` 表示不过滤
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListSamplesQuery {
+    pub device: Option<String>,
+    /// `true` 只返回已标注，`false` 只返回未标注
+    pub labeled: Option<bool>,
+    pub limit: Option<u32>,
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use shaku::{Component, Interface};
+
+use crate::{domain::repo::rssi_event_repo::IRssiEventRepo, errors::AppResult};
+
+use super::query::RebuildWindowsQuery;
+
+#[async_trait::async_trait]
+pub trait IRssiEventService: Interface {
+    /// 按新的窗口大小从原始记录重建样本窗口，便于离线实验
+    async fn rebuild_windows(&self, query: RebuildWindowsQuery) -> AppResult<Vec<Vec<f32>>>;
+}
+
+#[derive(Component)]
+#[shaku(interface = IRssiEventService)]
+pub struct RssiEventService {
+    #[shaku(inject)]
+    repo: Arc<dyn IRssiEventRepo>,
+}
+
+#[async_trait::async_trait]
+impl IRssiEventService for RssiEventService {
+    async fn rebuild_windows(&self, query: RebuildWindowsQuery) -> AppResult<Vec<Vec<f32>>> {
+        let events = self
+            .repo
+            .load_range(&query.device, query.from, query.to)
+            .await?;
+        let rssi: Vec<f32> = events.iter().map(|e| e.rssi as f32).collect();
+        Ok(rebuild_windows(&rssi, query.window_size, query.step))
+    }
+}
+
+/// 只输出完整窗口，顺序为 old->new
+pub fn rebuild_windows(rssi: &[f32], window_size: usize, step: usize) -> Vec<Vec<f32>> {
+    if window_size == 0 {
+        return Vec::new();
+    }
+    rssi.windows(window_size)
+        .step_by(step.max(1))
+        .map(|w| w.to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebuild_windows() {
+        let rssi: Vec<f32> = (0..10).map(|i| -(i as f32)).collect();
+
+        let w = rebuild_windows(&rssi, 9, 1);
+        assert_eq!(w.len(), 2);
+        assert_eq!(w[0], rssi[0..9].to_vec());
+        assert_eq!(w[1], rssi[1..10].to_vec());
+
+        let w = rebuild_windows(&rssi, 3, 4);
+        assert_eq!(w, vec![vec![0.0, -1.0, -2.0], vec![-4.0, -5.0, -6.0]]);
+
+        assert!(rebuild_windows(&rssi, 11, 1).is_empty());
+        assert!(rebuild_windows(&rssi, 0, 1).is_empty());
+    }
+}
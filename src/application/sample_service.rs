@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use sea_orm::{DatabaseBackend, FromQueryResult, QueryResult, Statement};
+use sea_orm::{DatabaseBackend, FromQueryResult, QueryResult, Statement, Value};
 use shaku::{Component, Interface};
 
 use crate::{
@@ -14,13 +14,20 @@ use crate::{
 };
 
 use super::view::SampleViewRow;
-use super::{command::CreateSampleCommand, view::SampleView};
+use super::{
+    command::{CreateSampleCommand, LabelSampleCommand},
+    query::ListSamplesQuery,
+    view::SampleView,
+};
 
 #[async_trait::async_trait]
 pub trait ISampleService: Interface {
     async fn get_sample(&self, id: u32) -> AppResult<SampleView>;
     async fn create_sample(&self, cmd: CreateSampleCommand) -> AppResult<()>;
     async fn fetch_latest(&self, count: i32) -> AppResult<Vec<SampleView>>; // 新增
+    async fn label_sample(&self, cmd: LabelSampleCommand) -> AppResult<()>;
+    /// 按创建时间倒序列出样本
+    async fn list_samples(&self, query: ListSamplesQuery) -> AppResult<Vec<SampleView>>;
 }
 
 #[derive(Component)]
@@ -41,6 +48,7 @@ impl ISampleService for SampleService {
         Ok(SampleView {
             id: r.id,
             device: r.device,
+            model: r.model,
             created_at: r.created_at,
             sample: r.data,
             predict: r.predict.into(),
@@ -53,21 +61,20 @@ impl ISampleService for SampleService {
             .save(SampleAggregate::new(
                 0,
                 cmd.device,
+                cmd.model,
                 cmd.sample,
                 ModelResult::from(cmd.predict),
-                ModelResult::Unknown,
+                ModelResult::Unlabeled,
                 "".to_string(),
             ))
             .await
-            .unwrap();
-        Ok(())
     }
     async fn fetch_latest(&self, count: i32) -> AppResult<Vec<SampleView>> {
         let db = self.db.get_connection();
         let stmt = Statement::from_sql_and_values(
             DB_BACKEND,
             r#"
-                SELECT id, device, created_at, sample, predict, actual
+                SELECT id, device, model, created_at, sample, predict, actual
                 FROM t_sample
                 ORDER BY created_at DESC
                 LIMIT $1
@@ -79,17 +86,41 @@ impl ISampleService for SampleService {
             .await
             .map_err(|e| AppError::DbError { source: e })?;
 
-        let result: Vec<SampleView> = rows
-            .into_iter()
-            .map(|row| SampleView {
-                id: row.id,
-                device: row.device,
-                created_at: row.created_at,
-                sample: serde_json::from_str(&row.sample).unwrap_or_default(),
-                predict: row.predict,
-                actual: row.actual,
-            })
-            .collect();
-        Ok(result)
+        Ok(rows.into_iter().map(SampleView::from).collect())
+    }
+
+    async fn label_sample(&self, cmd: LabelSampleCommand) -> AppResult<()> {
+        let mut sample = self.repo.load(cmd.id as SampleID).await?;
+        sample.change_actual(ModelResult::from(cmd.actual));
+        self.repo.save(sample).await
+    }
+
+    async fn list_samples(&self, query: ListSamplesQuery) -> AppResult<Vec<SampleView>> {
+        let db = self.db.get_connection();
+        let mut sql = String::from(
+            "SELECT id, device, model, created_at, sample, predict, actual FROM t_sample WHERE 1 = 1",
+        );
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(device) = query.device {
+            values.push(device.into());
+            sql.push_str(&format!(" AND device = ${}", values.len()));
+        }
+        match query.labeled {
+            Some(true) => sql.push_str(" AND actual IN (0, 1, 2)"),
+            Some(false) => sql.push_str(" AND actual NOT IN (0, 1, 2)"),
+            None => {}
+        }
+        sql.push_str(" ORDER BY created_at DESC, id DESC");
+        if let Some(limit) = query.limit {
+            values.push(limit.into());
+            sql.push_str(&format!(" LIMIT ${}", values.len()));
+        }
+
+        let stmt = Statement::from_sql_and_values(DB_BACKEND, sql, values);
+        let rows: Vec<SampleViewRow> = SampleViewRow::find_by_statement(stmt)
+            .all(db.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(rows.into_iter().map(SampleView::from).collect())
     }
 }
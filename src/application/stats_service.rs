@@ -0,0 +1,138 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use sea_orm::{DatabaseBackend, FromQueryResult, Statement};
+use serde::{Deserialize, Serialize};
+use shaku::{Component, Interface};
+
+use crate::{
+    domain::value_objects::{confusion_matrix::ConfusionMatrix, ModelResult},
+    errors::{AppError, AppResult},
+    infrastructure::IDbProvider,
+};
+
+use super::view::{AccuracyPointView, AccuracyReportView, ClassMetricsView, LabeledSampleRow};
+
+/// 统计过滤条件，`None` 表示不过滤
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsFilter {
+    pub device: Option<String>,
+    /// `Some("")` 只统计未记录模型版本的旧样本
+    pub model: Option<String>,
+}
+
+#[async_trait::async_trait]
+pub trait IStatsService: Interface {
+    /// 基于人工标注样本计算混淆矩阵、各类别精确率/召回率及每日准确率
+    async fn accuracy_report(&self, filter: StatsFilter) -> AppResult<AccuracyReportView>;
+    async fn list_devices(&self) -> AppResult<Vec<String>>;
+    async fn list_models(&self) -> AppResult<Vec<String>>;
+}
+
+#[derive(Component)]
+#[shaku(interface = IStatsService)]
+pub struct StatsService {
+    #[shaku(inject)]
+    db: Arc<dyn IDbProvider>,
+}
+
+const DB_BACKEND: DatabaseBackend = DatabaseBackend::Sqlite;
+
+#[derive(Debug, FromQueryResult)]
+struct DistinctRow {
+    value: String,
+}
+
+impl StatsService {
+    async fn distinct(&self, column: &str) -> AppResult<Vec<String>> {
+        let db = self.db.get_connection();
+        let stmt = Statement::from_string(
+            DB_BACKEND,
+            format!(
+                "SELECT DISTINCT {column} AS value FROM t_sample WHERE actual IN (0, 1, 2) ORDER BY {column}"
+            ),
+        );
+        let rows = DistinctRow::find_by_statement(stmt)
+            .all(db.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(rows.into_iter().map(|r| r.value).collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl IStatsService for StatsService {
+    async fn accuracy_report(&self, filter: StatsFilter) -> AppResult<AccuracyReportView> {
+        let db = self.db.get_connection();
+        // 只统计人工标注过的样本
+        let stmt = Statement::from_sql_and_values(
+            DB_BACKEND,
+            r#"
+                SELECT predict, actual, date(created_at) AS day
+                FROM t_sample
+                WHERE actual IN (0, 1, 2)
+                  AND ($1 IS NULL OR device = $1)
+                  AND ($2 IS NULL OR model = $2)
+                ORDER BY created_at
+            "#,
+            vec![filter.device.into(), filter.model.into()],
+        );
+        let rows = LabeledSampleRow::find_by_statement(stmt)
+            .all(db.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+
+        Ok(build_report(&rows))
+    }
+
+    async fn list_devices(&self) -> AppResult<Vec<String>> {
+        self.distinct("device").await
+    }
+
+    async fn list_models(&self) -> AppResult<Vec<String>> {
+        self.distinct("model").await
+    }
+}
+
+fn build_report(rows: &[LabeledSampleRow]) -> AccuracyReportView {
+    let mut matrix = ConfusionMatrix::default();
+    let mut days: BTreeMap<String, ConfusionMatrix> = BTreeMap::new();
+
+    for row in rows {
+        let (actual, predict) = (ModelResult::from(row.actual), ModelResult::from(row.predict));
+        matrix.record(actual, predict);
+        days.entry(row.day.clone().unwrap_or_default())
+            .or_default()
+            .record(actual, predict);
+    }
+
+    let classes = ConfusionMatrix::CLASSES;
+    AccuracyReportView {
+        labels: classes.iter().map(|c| (*c).into()).collect(),
+        matrix: classes
+            .iter()
+            .map(|a| classes.iter().map(|p| matrix.count(*a, *p)).collect())
+            .collect(),
+        classes: classes
+            .iter()
+            .map(|c| ClassMetricsView {
+                label: (*c).into(),
+                support: matrix.support(*c),
+                predicted: matrix.predicted(*c),
+                precision: matrix.precision(*c),
+                recall: matrix.recall(*c),
+            })
+            .collect(),
+        total: matrix.total(),
+        correct: matrix.correct(),
+        accuracy: matrix.accuracy(),
+        timeline: days
+            .into_iter()
+            .map(|(day, m)| AccuracyPointView {
+                day,
+                total: m.total(),
+                correct: m.correct(),
+                accuracy: m.accuracy(),
+            })
+            .collect(),
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 pub struct SampleView{
     pub id:i32,
     pub device:String,
+    pub model:String,
     pub sample:Vec<f32>,
     pub predict:i32,
     pub actual:i32,
@@ -15,8 +16,61 @@ pub struct SampleView{
 pub struct SampleViewRow {
     pub id: i32,
     pub device: String,
+    pub model: String,
     pub created_at: String,
     pub sample: String,
     pub predict: i32,
     pub actual: i32,
-}
\ No newline at end of file
+}
+
+impl From<SampleViewRow> for SampleView {
+    fn from(row: SampleViewRow) -> Self {
+        Self {
+            id: row.id,
+            device: row.device,
+            model: row.model,
+            created_at: row.created_at,
+            sample: serde_json::from_str(&row.sample).unwrap_or_default(),
+            predict: row.predict,
+            actual: row.actual,
+        }
+    }
+}
+
+/// 单个类别的统计指标
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+pub struct ClassMetricsView{
+    pub label:i32,
+    pub support:u32,
+    pub predicted:u32,
+    pub precision:Option<f32>,
+    pub recall:Option<f32>,
+}
+
+/// 按天统计的准确率
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+pub struct AccuracyPointView{
+    pub day:String,
+    pub total:u32,
+    pub correct:u32,
+    pub accuracy:Option<f32>,
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone,Default,PartialEq)]
+pub struct AccuracyReportView{
+    /// 行列顺序同 `ConfusionMatrix::CLASSES`，行为人工标注，列为预测
+    pub labels:Vec<i32>,
+    pub matrix:Vec<Vec<u32>>,
+    pub classes:Vec<ClassMetricsView>,
+    pub total:u32,
+    pub correct:u32,
+    pub accuracy:Option<f32>,
+    pub timeline:Vec<AccuracyPointView>,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct LabeledSampleRow {
+    pub predict: i32,
+    pub actual: i32,
+    pub day: Option<String>,
+}
@@ -0,0 +1,629 @@
+//! blue-lock 命令行工具
+//!
+//! 不依赖桌面界面，便于通过 SSH 脚本化配置与调试：
+//!
+//! ```sh
+//! blue-lock scan
+//! blue-lock trust 5d964bc66dbc1093 --name "iPhone" --address AA:BB:CC:DD:EE:FF
+//! blue-lock watch
+//! blue-lock thresholds 5d964bc66dbc1093 --present -62
+//! blue-lock samples list --unlabeled
+//! blue-lock model eval ai/hybrid_model.onnx
+//! blue-lock ctl pause
+//! ```
+
+use std::{error::Error, io::Write as _, path::PathBuf, sync::Arc, time::Duration};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use dioxus_demo::{
+    application::{
+        command::LabelSampleCommand, query::ListSamplesQuery, sample_service::ISampleService,
+        view::SampleView,
+    },
+    ble::{
+        adapter::{AdapterManager, AdapterState},
+        baseline::{BaselineManager, ThresholdOverride, ThresholdSource},
+        presence_detector::PresenceDetector,
+        scanner::DeviceScanner,
+        service::to_device_list,
+    },
+    di::Deps,
+    domain::{
+        entity::trusted_device::TrustedDevice,
+        repo::trusted_device_repo::ITrustedDeviceRepo,
+        value_objects::{confusion_matrix::ConfusionMatrix, ModelResult},
+    },
+    dto::detection::SignalConfig,
+    errors::AppError,
+    runtime::{connect, load_model, RuntimeConfig},
+    supervisor::Supervisor,
+};
+use shaku::HasComponent;
+use tokio::sync::{mpsc, watch};
+use tracing_subscriber::EnvFilter;
+
+#[cfg(unix)]
+use dioxus_demo::ipc::{client::IpcClient, default_socket_path};
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "blue-lock", version, about = "通过蓝牙设备锁定")]
+struct Cli {
+    /// 配置文件，缺省同桌面端
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// 数据库地址，覆盖配置文件中的 db_url
+    #[arg(long, global = true)]
+    db: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 扫描附近设备，列出名称指纹与信号
+    Scan {
+        /// 扫描时长（秒）
+        #[arg(short, long, default_value_t = 5)]
+        secs: u64,
+    },
+    /// 将设备加入受信任列表
+    Trust {
+        fingerprint: String,
+        #[arg(long, default_value = "")]
+        name: String,
+        /// 设备地址（没有地址的平台为外设 ID），见 `scan` 输出；靠近解锁只验证这台设备
+        #[arg(long, default_value = "")]
+        address: String,
+    },
+    /// 将设备移出受信任列表
+    Untrust { fingerprint: String },
+    /// 列出受信任设备
+    Trusted,
+    /// 查看从历史学习的在场/离开阈值，或手动指定
+    Thresholds {
+        /// 缺省列出所有受信任设备
+        fingerprint: Option<String>,
+        /// 在场阈值（dBm）
+        #[arg(long, allow_hyphen_values = true)]
+        present: Option<f32>,
+        /// 离开阈值（dBm）
+        #[arg(long, allow_hyphen_values = true)]
+        absent: Option<f32>,
+        /// 清除手动阈值，恢复自动学习
+        #[arg(long, conflicts_with_all = ["present", "absent"])]
+        clear: bool,
+    },
+    /// 实时输出目标设备的 RSSI 与模型预测
+    Watch {
+        /// 缺省为配置中的目标或第一个受信任设备
+        fingerprint: Option<String>,
+        /// 模型文件，缺省使用内置模型
+        #[arg(long)]
+        model: Option<PathBuf>,
+    },
+    /// 样本管理
+    Samples {
+        #[command(subcommand)]
+        command: SamplesCommand,
+    },
+    /// 模型工具
+    Model {
+        #[command(subcommand)]
+        command: ModelCommand,
+    },
+    /// 控制运行中的检测器
+    #[cfg(unix)]
+    Ctl {
+        /// 控制接口 socket，缺省同运行时
+        #[arg(long)]
+        socket: Option<PathBuf>,
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+}
+
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum CtlCommand {
+    /// 当前目标、暂停原因及最近的广播
+    Status,
+    /// 暂停自动锁屏
+    Pause,
+    /// 恢复自动锁屏
+    Resume,
+    /// 临时暂停自动锁屏，不指定时长时暂停到下次解锁
+    Snooze {
+        /// 暂停分钟数
+        #[arg(long, short)]
+        minutes: Option<u64>,
+    },
+    /// 切换目标设备
+    Target { fingerprint: String },
+    /// 列出蓝牙适配器，带参数时修改偏好
+    Adapters {
+        /// 优先使用的适配器，传空字符串清除
+        #[arg(long)]
+        prefer: Option<String>,
+        /// 同时在所有已开启的适配器上扫描
+        #[arg(long, conflicts_with = "single")]
+        multi: bool,
+        /// 只使用一个适配器
+        #[arg(long)]
+        single: bool,
+    },
+    /// 标注样本，缺省为目标设备最新的一条
+    Label {
+        label: ModelResult,
+        #[arg(long)]
+        id: Option<i32>,
+    },
+    /// 持续输出运行时事件（JSON Lines）
+    Events,
+}
+
+#[derive(Subcommand)]
+enum SamplesCommand {
+    /// 按时间倒序列出样本
+    List {
+        #[arg(long)]
+        device: Option<String>,
+        /// 只列出未标注样本
+        #[arg(long, conflicts_with = "labeled")]
+        unlabeled: bool,
+        /// 只列出已标注样本
+        #[arg(long)]
+        labeled: bool,
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: u32,
+    },
+    /// 人工标注：stationary / away / closer（或 0 / 1 / 2）
+    Label { id: i32, label: ModelResult },
+    /// 导出样本
+    Export {
+        /// 输出文件，缺省为标准输出
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+        format: ExportFormat,
+        /// 只导出已标注样本
+        #[arg(long)]
+        labeled: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ModelCommand {
+    /// 用已标注样本评估模型
+    Eval {
+        onnx: PathBuf,
+        #[arg(long)]
+        device: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Jsonl,
+    Csv,
+}
+
+#[tokio::main]
+async fn main() -> CliResult {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    let mut config = RuntimeConfig::load(cli.config.as_deref())?;
+    if let Some(db) = cli.db {
+        config.db_url = db;
+    }
+
+    match cli.command {
+        Command::Scan { secs } => scan(&config, secs).await,
+        Command::Trust {
+            fingerprint,
+            name,
+            address,
+        } => {
+            let repo: Arc<dyn ITrustedDeviceRepo> = connect(&config.db_url).await?.resolve();
+            repo.add(TrustedDevice::new(fingerprint.clone(), name).with_address(address))
+                .await?;
+            println!("trusted {}", fingerprint);
+            Ok(())
+        }
+        Command::Untrust { fingerprint } => {
+            let repo: Arc<dyn ITrustedDeviceRepo> = connect(&config.db_url).await?.resolve();
+            if repo.remove(&fingerprint).await? {
+                println!("untrusted {}", fingerprint);
+                Ok(())
+            } else {
+                Err(format!("{} is not trusted", fingerprint).into())
+            }
+        }
+        Command::Trusted => {
+            let repo: Arc<dyn ITrustedDeviceRepo> = connect(&config.db_url).await?.resolve();
+            for d in repo.list().await? {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    d.fingerprint, d.name, d.address, d.created_at
+                );
+            }
+            Ok(())
+        }
+        Command::Thresholds {
+            fingerprint,
+            present,
+            absent,
+            clear,
+        } => {
+            let deps = connect(&config.db_url).await?;
+            let baseline = BaselineManager::new(deps.resolve(), None);
+            let devices = match fingerprint {
+                Some(fingerprint) => {
+                    if clear {
+                        baseline.set_override(&fingerprint, ThresholdOverride::default())?;
+                    } else if present.is_some() || absent.is_some() {
+                        // 只改指定的一项
+                        let current = baseline.override_for(&fingerprint);
+                        let value = ThresholdOverride {
+                            present: present.or(current.present),
+                            absent: absent.or(current.absent),
+                        };
+                        baseline.set_override(&fingerprint, value)?;
+                    }
+                    vec![fingerprint]
+                }
+                None if clear || present.is_some() || absent.is_some() => {
+                    return Err("a fingerprint is required to set thresholds".into())
+                }
+                None => trusted_fingerprints(&deps).await?,
+            };
+            baseline.rebuild(&devices, &config.algo.baseline).await?;
+            for device in devices {
+                let t = baseline.thresholds(&device, &config.algo);
+                let source = match t.source {
+                    ThresholdSource::Hourly { samples } => format!("hourly({})", samples),
+                    ThresholdSource::Daily { samples } => format!("daily({})", samples),
+                    ThresholdSource::Default => "default".to_string(),
+                    ThresholdSource::Override => "override".to_string(),
+                };
+                println!("{}\t{:.0}\t{:.0}\t{}", device, t.present, t.absent, source);
+            }
+            Ok(())
+        }
+        Command::Watch { fingerprint, model } => watch(&config, fingerprint, model).await,
+        Command::Samples { command } => {
+            let deps = connect(&config.db_url).await?;
+            samples(&deps, command).await
+        }
+        Command::Model {
+            command: ModelCommand::Eval { onnx, device },
+        } => {
+            let deps = connect(&config.db_url).await?;
+            eval(&deps, onnx, device).await
+        }
+        #[cfg(unix)]
+        Command::Ctl { socket, command } => {
+            let socket = socket
+                .or(config.ipc_socket)
+                .unwrap_or_else(default_socket_path);
+            ctl(IpcClient::connect(&socket).await?, command).await
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn ctl(mut client: IpcClient, command: CtlCommand) -> CliResult {
+    match command {
+        CtlCommand::Status => {
+            let status = client.status().await?;
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        }
+        CtlCommand::Pause => client.pause().await?,
+        CtlCommand::Resume => client.resume().await?,
+        CtlCommand::Snooze { minutes } => client.snooze(minutes).await?,
+        CtlCommand::Target { fingerprint } => client.set_target(fingerprint).await?,
+        CtlCommand::Adapters {
+            prefer,
+            multi,
+            single,
+        } => {
+            let (adapters, mut preference) = client.adapters().await?;
+            if prefer.is_some() || multi || single {
+                if let Some(name) = prefer {
+                    preference.preferred = Some(name).filter(|n| !n.is_empty());
+                }
+                if multi || single {
+                    preference.multi = multi;
+                }
+                client.set_adapter(preference).await?;
+                return Ok(());
+            }
+            for a in adapters {
+                let mark = if a.active { "*" } else { " " };
+                let power = if a.powered { "on" } else { "off" };
+                println!("{} {} ({})", mark, a.name, power);
+            }
+            println!("{}", serde_json::to_string(&preference)?);
+        }
+        CtlCommand::Label { label, id } => {
+            let id = client.label(id, label).await?;
+            println!("labeled {} as {}", id, label);
+        }
+        CtlCommand::Events => {
+            let mut events = client.subscribe().await?;
+            while let Some(event) = events.next().await? {
+                println!("{}", serde_json::to_string(&event)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn trusted_fingerprints(deps: &Deps) -> CliResult<Vec<String>> {
+    let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+    Ok(repo.list().await?.into_iter().map(|d| d.fingerprint).collect())
+}
+
+async fn scan(config: &RuntimeConfig, secs: u64) -> CliResult {
+    let trusted = trusted_fingerprints(&*connect(&config.db_url).await?).await?;
+    let supervisor = Supervisor::default();
+    let adapters = AdapterManager::start(&supervisor, None).await?;
+    let scanner = DeviceScanner::start(adapters.subscribe(), &supervisor);
+    eprintln!("scanning for {}s...", secs);
+    tokio::time::sleep(Duration::from_secs(secs)).await;
+    if adapters.state() != AdapterState::Ready {
+        eprintln!("{}", adapters.state());
+    }
+    supervisor.shutdown(Duration::from_secs(1)).await;
+    scanner.stop().await;
+
+    println!(
+        "  {:<16}  {:<17}  {:>5}  {:<8}  {:<6}  name",
+        "fingerprint", "address", "rssi", "quality", "kind"
+    );
+    for d in to_device_list(&scanner.snapshot(), &SignalConfig::default()) {
+        let mark = if trusted.contains(&d.mac) { "*" } else { " " };
+        let quality = format!("{}{}%", d.trend.arrow(), d.percent);
+        let kind = d.kind.to_string();
+        println!(
+            "{} {:<16}  {:<17}  {:>5.0}  {:<8}  {:<6}  {}",
+            mark, d.mac, d.id, d.smoothed_rssi, quality, kind, d.name
+        );
+    }
+    Ok(())
+}
+
+async fn watch(
+    config: &RuntimeConfig,
+    fingerprint: Option<String>,
+    model: Option<PathBuf>,
+) -> CliResult {
+    let configured = Some(config.target.clone()).filter(|t| !t.is_empty());
+    let target = match fingerprint.or(configured) {
+        Some(t) => t,
+        None => trusted_fingerprints(&*connect(&config.db_url).await?)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or("no target, pass a fingerprint or run `blue-lock trust` first")?,
+    };
+    let model = load_model(model.as_deref())?;
+    let supervisor = Supervisor::default();
+    let adapters = AdapterManager::start(&supervisor, None).await?;
+    let scanner = DeviceScanner::start(adapters.subscribe(), &supervisor);
+
+    let (sample_tx, mut sample_rx) = mpsc::channel::<Vec<f32>>(100);
+    let detector = Arc::new(PresenceDetector::new(config.algo.clone(), sample_tx).await?);
+    let (raw_tx, mut raw_rx) = mpsc::channel(256);
+    detector.add_raw_sink(raw_tx).await;
+    let (_target_tx, target_rx) = watch::channel(target.clone());
+    supervisor.spawn("detection", {
+        let (detector, scanner) = (detector.clone(), scanner.clone());
+        let target = target_rx;
+        move |token| {
+            let (detector, target) = (detector.clone(), target.clone());
+            let rssi_rx = scanner.subscribe_rssi();
+            async move {
+                detector
+                    .start_detection(rssi_rx, target, token)
+                    .await
+                    .map_err(AppError::from)
+            }
+        }
+    });
+
+    eprintln!("watching {} with model {}, Ctrl-C to stop", target, model.version());
+    loop {
+        tokio::select! {
+            Some(event) = raw_rx.recv() => {
+                println!("{}\trssi\t{}\t{}", event.ts, event.rssi, event.name);
+            }
+            Some(window) = sample_rx.recv() => {
+                match model.inference(window.clone()) {
+                    Ok(res) => println!("predict\t{:?}\t{}", window, res),
+                    Err(e) => eprintln!("inference failed: {}", e),
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    supervisor.shutdown(Duration::from_secs(1)).await;
+    detector.shutdown().await;
+    scanner.stop().await;
+    Ok(())
+}
+
+async fn samples(deps: &Deps, command: SamplesCommand) -> CliResult {
+    let srv: Arc<dyn ISampleService> = deps.resolve();
+    match command {
+        SamplesCommand::List {
+            device,
+            unlabeled,
+            labeled,
+            limit,
+        } => {
+            let labeled = match (labeled, unlabeled) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            let rows = srv
+                .list_samples(ListSamplesQuery {
+                    device,
+                    labeled,
+                    limit: Some(limit),
+                })
+                .await?;
+            for s in rows {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{:?}",
+                    s.id,
+                    s.created_at,
+                    s.device,
+                    s.model,
+                    ModelResult::from(s.predict),
+                    ModelResult::from(s.actual),
+                    s.sample
+                );
+            }
+        }
+        SamplesCommand::Label { id, label } => {
+            if !label.is_label() {
+                return Err(format!("{} is not a valid label", label).into());
+            }
+            srv.label_sample(LabelSampleCommand {
+                id,
+                actual: label.into(),
+            })
+            .await?;
+            println!("labeled {} as {}", id, label);
+        }
+        SamplesCommand::Export {
+            out,
+            format,
+            labeled,
+        } => {
+            let rows = srv
+                .list_samples(ListSamplesQuery {
+                    labeled: labeled.then_some(true),
+                    ..Default::default()
+                })
+                .await?;
+            let mut writer: Box<dyn std::io::Write> = match &out {
+                Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            export(&mut writer, &rows, format)?;
+            writer.flush()?;
+            if let Some(path) = out {
+                eprintln!("exported {} samples to {}", rows.len(), path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn export(w: &mut dyn std::io::Write, rows: &[SampleView], format: ExportFormat) -> CliResult {
+    match format {
+        ExportFormat::Jsonl => {
+            for s in rows {
+                writeln!(w, "{}", serde_json::to_string(s)?)?;
+            }
+        }
+        ExportFormat::Csv => {
+            writeln!(w, "id,device,model,created_at,predict,actual,sample")?;
+            for s in rows {
+                let sample = s
+                    .sample
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    w,
+                    "{},{},{},{},{},{},{}",
+                    s.id,
+                    csv_field(&s.device),
+                    csv_field(&s.model),
+                    csv_field(&s.created_at),
+                    s.predict,
+                    s.actual,
+                    sample
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 含逗号、引号或换行的字段加引号，内部引号成对转义
+fn csv_field(s: &str) -> std::borrow::Cow<'_, str> {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\"")).into()
+    } else {
+        s.into()
+    }
+}
+
+async fn eval(deps: &Deps, onnx: PathBuf, device: Option<String>) -> CliResult {
+    let model = load_model(Some(onnx.as_path()))?;
+    let srv: Arc<dyn ISampleService> = deps.resolve();
+    let rows = srv
+        .list_samples(ListSamplesQuery {
+            device,
+            labeled: Some(true),
+            limit: None,
+        })
+        .await?;
+    if rows.is_empty() {
+        return Err("no labeled samples".into());
+    }
+
+    // 新模型与样本录制时的预测分别统计，便于对比
+    let mut current = ConfusionMatrix::default();
+    let mut recorded = ConfusionMatrix::default();
+    for s in &rows {
+        let actual = ModelResult::from(s.actual);
+        let predict = model
+            .inference(s.sample.clone())
+            .unwrap_or(ModelResult::Unknown);
+        current.record(actual, predict);
+        recorded.record(actual, ModelResult::from(s.predict));
+    }
+
+    let pct = |v: Option<f32>| v.map_or("-".to_string(), |v| format!("{:.1}%", v * 100.0));
+    println!("model {} on {} labeled samples", model.version(), rows.len());
+    println!(
+        "accuracy {} (recorded predictions {})",
+        pct(current.accuracy()),
+        pct(recorded.accuracy())
+    );
+    println!();
+    println!("{:<8}  {:>7}  {:>9}  {:>7}", "class", "support", "precision", "recall");
+    for class in ConfusionMatrix::CLASSES.iter().filter(|c| c.is_label()) {
+        println!(
+            "{:<8}  {:>7}  {:>9}  {:>7}",
+            format!("{:?}", class),
+            current.support(*class),
+            pct(current.precision(*class)),
+            pct(current.recall(*class))
+        );
+    }
+    println!();
+    println!("confusion matrix (rows: actual, cols: predict)");
+    for actual in ConfusionMatrix::CLASSES.iter().filter(|c| c.is_label()) {
+        let row = ConfusionMatrix::CLASSES
+            .iter()
+            .map(|p| format!("{:>6}", current.count(*actual, *p)))
+            .collect::<String>();
+        println!("{:<14}{}", format!("{:?}", actual), row);
+    }
+    Ok(())
+}
@@ -0,0 +1,426 @@
+//! 蓝牙适配器的发现、选择、热插拔与开关状态
+//!
+//! btleplug 不通知适配器的插拔，这里定时枚举；开关状态通过 `CentralEvent::StateUpdate` 获取。
+//! 按持久化的偏好选出要使用的适配器（可同时使用多个），只发布已开启的，
+//! 扫描任务通过 `follow_adapters` 跟随切换。
+
+use std::{
+    fmt::Display,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use btleplug::{
+    api::{Central as _, CentralEvent, CentralState, Manager as _},
+    platform::{Adapter, Manager},
+};
+use futures::{future::try_join_all, stream::SelectAll, Stream, StreamExt as _};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    errors::{AppResult, BleError},
+    infrastructure::json_store::{default_state_path, JsonStore},
+    supervisor::Supervisor,
+};
+
+/// 枚举适配器的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+type EventStream = Pin<Box<dyn Stream<Item = (String, CentralEvent)> + Send>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterState {
+    /// 没有可用的适配器
+    #[default]
+    Missing,
+    PoweredOff,
+    Ready,
+}
+
+impl Display for AdapterState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Missing => "未找到蓝牙适配器",
+            Self::PoweredOff => "蓝牙已关闭",
+            Self::Ready => "蓝牙正常",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// 蓝牙不可用时的锁屏策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdapterLostPolicy {
+    /// 视为目标离开，超时后照常锁屏
+    #[default]
+    FailClosed,
+    /// 暂停超时计时，蓝牙恢复后重新计时
+    FailOpen,
+}
+
+/// 适配器偏好，保存在 `$XDG_STATE_HOME/blue-lock/adapter.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdapterPreference {
+    /// 优先使用的适配器（`adapter_info`），不在或未开启时退回第一个可用的
+    pub preferred: Option<String>,
+    /// 同时在所有已开启的适配器上扫描，按设备合并 RSSI
+    pub multi: bool,
+}
+
+/// 枚举到的适配器
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdapterInfo {
+    /// `adapter_info`，同时作为标识
+    pub name: String,
+    pub powered: bool,
+    /// 是否正在使用
+    pub active: bool,
+}
+
+/// 正在使用的适配器
+#[derive(Debug, Clone)]
+pub struct ActiveAdapter {
+    pub name: String,
+    pub adapter: Adapter,
+}
+
+struct Known {
+    name: String,
+    adapter: Adapter,
+    powered: bool,
+}
+
+pub struct AdapterManager {
+    store: JsonStore<AdapterPreference>,
+    preference_tx: watch::Sender<AdapterPreference>,
+    active_tx: watch::Sender<Vec<ActiveAdapter>>,
+    state_tx: watch::Sender<AdapterState>,
+    adapters: RwLock<Vec<AdapterInfo>>,
+}
+
+impl AdapterManager {
+    /// `preference_path` 为空时使用默认状态目录
+    pub async fn start(
+        supervisor: &Supervisor,
+        preference_path: Option<PathBuf>,
+    ) -> AppResult<Arc<Self>> {
+        let manager = Manager::new()
+            .await
+            .map_err(|e| BleError::Adapter { source: e })?;
+        let store =
+            JsonStore::new(preference_path.unwrap_or_else(|| default_state_path("adapter.json")));
+        let (preference_tx, _) = watch::channel(store.load());
+        let (active_tx, _) = watch::channel(Vec::new());
+        let (state_tx, _) = watch::channel(AdapterState::Missing);
+        let adapters = Arc::new(Self {
+            store,
+            preference_tx,
+            active_tx,
+            state_tx,
+            adapters: RwLock::new(Vec::new()),
+        });
+        let manager = Arc::new(manager);
+        supervisor.spawn("adapter", {
+            let adapters = adapters.clone();
+            move |token| adapters.clone().watch(manager.clone(), token)
+        });
+        Ok(adapters)
+    }
+
+    /// 最近一次枚举到的适配器
+    pub fn list(&self) -> Vec<AdapterInfo> {
+        self.adapters.read().map(|a| a.clone()).unwrap_or_default()
+    }
+
+    pub fn preference(&self) -> AdapterPreference {
+        self.preference_tx.borrow().clone()
+    }
+
+    /// 保存偏好并立即重新选择
+    pub fn set_preference(&self, preference: AdapterPreference) {
+        if let Err(e) = self.store.save(&preference) {
+            warn!("Failed to persist adapter preference: {}", e);
+        }
+        info!("Adapter preference: {:?}", preference);
+        self.preference_tx.send_replace(preference);
+    }
+
+    /// 正在使用且已开启的适配器
+    pub fn active(&self) -> Vec<ActiveAdapter> {
+        self.active_tx.borrow().clone()
+    }
+
+    /// 适配器出现、消失、开关或偏好变化时通知
+    pub fn subscribe(&self) -> watch::Receiver<Vec<ActiveAdapter>> {
+        self.active_tx.subscribe()
+    }
+
+    pub fn state(&self) -> AdapterState {
+        *self.state_tx.borrow()
+    }
+
+    pub fn subscribe_state(&self) -> watch::Receiver<AdapterState> {
+        self.state_tx.subscribe()
+    }
+
+    async fn watch(
+        self: Arc<Self>,
+        manager: Arc<Manager>,
+        token: CancellationToken,
+    ) -> AppResult<()> {
+        let mut known: Vec<Known> = Vec::new();
+        let mut events: SelectAll<EventStream> = SelectAll::new();
+        let mut preference = self.preference_tx.subscribe();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    self.publish(&[], &AdapterPreference::default());
+                    return Ok(());
+                }
+                _ = poll.tick() => self.poll(&manager, &mut known, &mut events).await,
+                Ok(()) = preference.changed() => {}
+                Some((name, event)) = events.next() => match event {
+                    CentralEvent::StateUpdate(state) => {
+                        if let Some(k) = known.iter_mut().find(|k| k.name == name) {
+                            k.powered = state != CentralState::PoweredOff;
+                        }
+                    }
+                    _ => continue,
+                },
+            }
+            let preference = preference.borrow_and_update().clone();
+            self.publish(&known, &preference);
+        }
+    }
+
+    /// 同步适配器列表：移除已拔出的，订阅新插入的，并刷新开关状态
+    async fn poll(
+        &self,
+        manager: &Manager,
+        known: &mut Vec<Known>,
+        events: &mut SelectAll<EventStream>,
+    ) {
+        let adapters = match manager.adapters().await {
+            Ok(adapters) => adapters,
+            Err(e) => {
+                warn!("Failed to list adapters: {}", e);
+                Vec::new()
+            }
+        };
+        let mut present: Vec<(String, Adapter, bool)> = Vec::with_capacity(adapters.len());
+        for (index, adapter) in adapters.into_iter().enumerate() {
+            let info = adapter.adapter_info().await.ok();
+            let taken: Vec<&str> = present.iter().map(|(name, ..)| name.as_str()).collect();
+            let name = adapter_name(info, index, &taken);
+            // 部分平台不一定推送 StateUpdate，每次枚举时兜底查询
+            let powered = adapter
+                .adapter_state()
+                .await
+                .map_or(true, |s| s != CentralState::PoweredOff);
+            present.push((name, adapter, powered));
+        }
+
+        known.retain(|k| {
+            let keep = present.iter().any(|(name, ..)| *name == k.name);
+            if !keep {
+                warn!("Adapter {} removed", k.name);
+            }
+            keep
+        });
+        for (name, adapter, powered) in present {
+            if let Some(k) = known.iter_mut().find(|k| k.name == name) {
+                k.powered = powered;
+                continue;
+            }
+            match adapter.events().await {
+                Ok(stream) => {
+                    let tag = name.clone();
+                    events.push(Box::pin(stream.map(move |e| (tag.clone(), e))));
+                }
+                Err(e) => {
+                    warn!("Failed to subscribe adapter {}: {}", name, e);
+                    continue;
+                }
+            }
+            info!("Found adapter {}", name);
+            known.push(Known {
+                name,
+                adapter,
+                powered,
+            });
+        }
+    }
+
+    fn publish(&self, known: &[Known], preference: &AdapterPreference) {
+        let candidates: Vec<(&str, bool)> =
+            known.iter().map(|k| (k.name.as_str(), k.powered)).collect();
+        let selected = select_adapters(&candidates, preference);
+        let state = if !selected.is_empty() {
+            AdapterState::Ready
+        } else if known.is_empty() {
+            AdapterState::Missing
+        } else {
+            AdapterState::PoweredOff
+        };
+
+        if let Ok(mut adapters) = self.adapters.write() {
+            *adapters = known
+                .iter()
+                .enumerate()
+                .map(|(i, k)| AdapterInfo {
+                    name: k.name.clone(),
+                    powered: k.powered,
+                    active: selected.contains(&i),
+                })
+                .collect();
+        }
+
+        let active: Vec<ActiveAdapter> = selected
+            .iter()
+            .map(|&i| ActiveAdapter {
+                name: known[i].name.clone(),
+                adapter: known[i].adapter.clone(),
+            })
+            .collect();
+        self.active_tx.send_if_modified(|current| {
+            let same = current.len() == active.len()
+                && current.iter().zip(&active).all(|(a, b)| a.name == b.name);
+            if !same {
+                info!(
+                    "Active adapters: {:?}",
+                    active.iter().map(|a| &a.name).collect::<Vec<_>>()
+                );
+                *current = active;
+            }
+            !same
+        });
+        if self.state_tx.send_replace(state) != state {
+            info!("Adapter state: {:?}", state);
+        }
+    }
+}
+
+/// 适配器名同时作为标识：取 `adapter_info`，查询失败、为空或重名时按枚举顺序命名
+fn adapter_name(info: Option<String>, index: usize, taken: &[&str]) -> String {
+    match info.filter(|name| !name.is_empty()) {
+        Some(name) if !taken.contains(&name.as_str()) => name,
+        Some(name) => format!("{} #{}", name, index),
+        None => format!("adapter{}", index),
+    }
+}
+
+/// 按偏好选出要使用的适配器下标，只选已开启的
+fn select_adapters(candidates: &[(&str, bool)], preference: &AdapterPreference) -> Vec<usize> {
+    let powered = candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, powered))| *powered)
+        .map(|(i, _)| i);
+    if preference.multi {
+        return powered.collect();
+    }
+    let preferred = preference.preferred.as_deref().and_then(|p| {
+        candidates
+            .iter()
+            .position(|(name, powered)| *powered && *name == p)
+    });
+    preferred
+        .or_else(|| powered.clone().next())
+        .into_iter()
+        .collect()
+}
+
+/// 在每个正在使用的适配器上并发运行 `run`，适配器变化时全部中断并重新运行；
+/// 任一实例出错时返回错误，全部返回 `Ok` 或取消时结束
+pub async fn follow_adapters<F, Fut>(
+    mut active_rx: watch::Receiver<Vec<ActiveAdapter>>,
+    token: CancellationToken,
+    mut run: F,
+) -> AppResult<()>
+where
+    F: FnMut(ActiveAdapter) -> Fut,
+    Fut: Future<Output = AppResult<()>>,
+{
+    loop {
+        let active = active_rx.borrow_and_update().clone();
+        if !active.is_empty() {
+            let runs = try_join_all(active.into_iter().map(&mut run));
+            tokio::select! {
+                res = runs => return res.map(|_| ()),
+                changed = active_rx.changed() => {
+                    if changed.is_err() {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                _ = token.cancelled() => return Ok(()),
+            }
+        }
+        tokio::select! {
+            changed = active_rx.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+            }
+            _ = token.cancelled() => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_adapters() {
+        let candidates = [
+            ("hci0 (builtin)", true),
+            ("hci1 (usb)", true),
+            ("hci2", false),
+        ];
+        let single = AdapterPreference::default();
+        assert_eq!(select_adapters(&candidates, &single), vec![0]);
+
+        let dongle = AdapterPreference {
+            preferred: Some("hci1 (usb)".into()),
+            multi: false,
+        };
+        assert_eq!(select_adapters(&candidates, &dongle), vec![1]);
+
+        // 偏好的适配器已关闭，退回第一个可用的
+        let off = AdapterPreference {
+            preferred: Some("hci2".into()),
+            multi: false,
+        };
+        assert_eq!(select_adapters(&candidates, &off), vec![0]);
+
+        let multi = AdapterPreference {
+            preferred: None,
+            multi: true,
+        };
+        assert_eq!(select_adapters(&candidates, &multi), vec![0, 1]);
+        assert!(select_adapters(&[("hci0", false)], &multi).is_empty());
+    }
+
+    #[test]
+    fn test_adapter_name() {
+        assert_eq!(
+            adapter_name(Some("hci0 (usb)".into()), 0, &[]),
+            "hci0 (usb)"
+        );
+        // 查询不到时不会都叫空字符串而互相覆盖
+        assert_eq!(adapter_name(None, 1, &["hci0 (usb)"]), "adapter1");
+        assert_eq!(adapter_name(Some(String::new()), 2, &[]), "adapter2");
+        assert_eq!(adapter_name(Some("hci0".into()), 1, &["hci0"]), "hci0 #1");
+    }
+}
@@ -0,0 +1,577 @@
+//! 按设备学习的自适应阈值
+//!
+//! 只统计在场状态机处于 `Present` 期间的 RSSI（见 `t_presence_span`），离开途中与离开后的弱信号不计入：
+//! 按本地时间每小时统计直方图，取低分位作为在场/离开阈值。手表和口袋里的手机基线相差很大，
+//! 各自学习后比统一的 `AlgoConfig::threshold` 更可靠。
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Local, TimeZone as _, Timelike as _};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    domain::{
+        entity::rssi_event::{now_millis, RssiBucket, RssiEvent},
+        repo::rssi_event_repo::IRssiEventRepo,
+    },
+    dto::detection::{AlgoConfig, BaselineConfig},
+    errors::AppResult,
+    infrastructure::json_store::{default_state_path, JsonStore},
+};
+
+/// 直方图覆盖的 RSSI 范围（dBm）
+const MIN_RSSI: i16 = -127;
+const MAX_RSSI: i16 = 20;
+const BINS: usize = (MAX_RSSI - MIN_RSSI + 1) as usize;
+/// 未学习时离开阈值比在场阈值低多少
+const DEFAULT_GAP_DB: f32 = 5.0;
+
+/// 每小时一份的 RSSI 直方图
+#[derive(Debug, Clone)]
+pub struct RssiHistogram {
+    hours: Vec<[u64; BINS]>,
+}
+
+impl Default for RssiHistogram {
+    fn default() -> Self {
+        Self {
+            hours: vec![[0; BINS]; 24],
+        }
+    }
+}
+
+impl RssiHistogram {
+    pub fn from_buckets(buckets: &[RssiBucket]) -> Self {
+        let mut histogram = Self::default();
+        for b in buckets {
+            histogram.add(b.hour, b.rssi, b.count);
+        }
+        histogram
+    }
+
+    pub fn add(&mut self, hour: u32, rssi: i16, count: u64) {
+        let bin = (rssi.clamp(MIN_RSSI, MAX_RSSI) - MIN_RSSI) as usize;
+        self.hours[hour as usize % 24][bin] += count;
+    }
+
+    /// `hour` 为空时统计全天
+    pub fn total(&self, hour: Option<u32>) -> u64 {
+        self.bins(hour).iter().sum()
+    }
+
+    /// 第 `q` 分位的 RSSI，没有样本时返回 `None`
+    pub fn quantile(&self, hour: Option<u32>, q: f32) -> Option<f32> {
+        let bins = self.bins(hour);
+        let total: u64 = bins.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let rank = ((total as f64 * q.clamp(0.0, 1.0) as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in bins.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some((i as i16 + MIN_RSSI) as f32);
+            }
+        }
+        None
+    }
+
+    fn bins(&self, hour: Option<u32>) -> [u64; BINS] {
+        match hour {
+            Some(h) => self.hours[h as usize % 24],
+            None => self.hours.iter().fold([0; BINS], |mut acc, h| {
+                acc.iter_mut().zip(h).for_each(|(a, b)| *a += b);
+                acc
+            }),
+        }
+    }
+}
+
+/// 手动指定的阈值，未指定的一项仍使用学习值
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThresholdOverride {
+    pub present: Option<f32>,
+    pub absent: Option<f32>,
+}
+
+impl ThresholdOverride {
+    pub fn is_empty(&self) -> bool {
+        self.present.is_none() && self.absent.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThresholdSource {
+    /// 由该小时的历史学习
+    Hourly { samples: u64 },
+    /// 该小时样本不足，由全天历史学习
+    Daily { samples: u64 },
+    /// 历史不足，使用 `AlgoConfig::threshold`
+    Default,
+    /// 两项都由用户指定
+    Override,
+}
+
+/// RSSI 高于 `present` 视为在场，低于 `absent` 视为离开，中间保持原状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Thresholds {
+    pub present: f32,
+    pub absent: f32,
+    pub source: ThresholdSource,
+}
+
+impl Thresholds {
+    pub fn fixed(threshold: f32) -> Self {
+        Self {
+            present: threshold,
+            absent: threshold - DEFAULT_GAP_DB,
+            source: ThresholdSource::Default,
+        }
+    }
+}
+
+/// 各设备的直方图与手动阈值
+#[derive(Debug, Default)]
+pub struct BaselineEstimator {
+    histograms: HashMap<String, RssiHistogram>,
+    overrides: HashMap<String, ThresholdOverride>,
+}
+
+impl BaselineEstimator {
+    pub fn set_histogram(&mut self, device: &str, histogram: RssiHistogram) {
+        self.histograms.insert(device.to_string(), histogram);
+    }
+
+    /// 实时广播计入直方图，下次重建前持续修正阈值
+    pub fn observe(&mut self, event: &RssiEvent) {
+        if let Some(histogram) = self.histograms.get_mut(&event.device) {
+            histogram.add(local_hour(event.ts), event.rssi, 1);
+        }
+    }
+
+    /// 整体替换手动阈值，文件中已删除的设备随之清除
+    pub fn replace_overrides(&mut self, overrides: HashMap<String, ThresholdOverride>) {
+        self.overrides = overrides
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+    }
+
+    pub fn set_override(&mut self, device: &str, value: ThresholdOverride) {
+        if value.is_empty() {
+            self.overrides.remove(device);
+        } else {
+            self.overrides.insert(device.to_string(), value);
+        }
+    }
+
+    pub fn overrides(&self) -> &HashMap<String, ThresholdOverride> {
+        &self.overrides
+    }
+
+    pub fn thresholds(&self, device: &str, hour: u32, config: &AlgoConfig) -> Thresholds {
+        let learned = match self.histograms.get(device) {
+            Some(h) if config.baseline.enabled => learn(h, hour, &config.baseline),
+            _ => None,
+        }
+        .unwrap_or_else(|| Thresholds::fixed(config.threshold));
+
+        let Some(o) = self.overrides.get(device) else {
+            return learned;
+        };
+        let present = o.present.unwrap_or(learned.present);
+        let absent = o.absent.unwrap_or(learned.absent).min(present);
+        let source = match (o.present, o.absent) {
+            (Some(_), Some(_)) => ThresholdSource::Override,
+            _ => learned.source,
+        };
+        Thresholds {
+            present,
+            absent,
+            source,
+        }
+    }
+}
+
+fn learn(histogram: &RssiHistogram, hour: u32, config: &BaselineConfig) -> Option<Thresholds> {
+    let (range, source) = if histogram.total(Some(hour)) >= config.min_samples {
+        let samples = histogram.total(Some(hour));
+        (Some(hour), ThresholdSource::Hourly { samples })
+    } else if histogram.total(None) >= config.min_samples {
+        let samples = histogram.total(None);
+        (None, ThresholdSource::Daily { samples })
+    } else {
+        return None;
+    };
+    let present = histogram.quantile(range, config.present_quantile)?;
+    let absent = histogram
+        .quantile(range, config.absent_quantile)?
+        .min(present - config.min_gap_db);
+    Some(Thresholds {
+        present,
+        absent,
+        source,
+    })
+}
+
+pub fn local_hour(ts: i64) -> u32 {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.hour())
+        .unwrap_or_default()
+}
+
+/// `Present` 期间的实时读数。低于离开阈值的读数可能已是离开途中，
+/// 等之后出现有效读数（不晚于状态机的 `last_good`）才确认在场，离开 `Present` 时丢弃
+#[derive(Debug, Default)]
+pub struct PresentReadings {
+    pending: VecDeque<RssiEvent>,
+}
+
+impl PresentReadings {
+    /// 在场时收到的读数，返回已确认的读数
+    pub fn push(&mut self, event: RssiEvent, last_good: i64) -> Vec<RssiEvent> {
+        self.pending.push_back(event);
+        self.confirm(last_good)
+    }
+
+    /// 离开 `Present`，返回 `last_good` 之前的读数并丢弃其余的
+    pub fn finish(&mut self, last_good: i64) -> Vec<RssiEvent> {
+        let confirmed = self.confirm(last_good);
+        self.pending.clear();
+        confirmed
+    }
+
+    fn confirm(&mut self, last_good: i64) -> Vec<RssiEvent> {
+        let n = self
+            .pending
+            .iter()
+            .take_while(|e| e.ts <= last_good)
+            .count();
+        self.pending.drain(..n).collect()
+    }
+}
+
+/// 运行时使用的阈值服务：从数据库重建直方图，手动阈值保存在 `$XDG_STATE_HOME/blue-lock/thresholds.json`
+pub struct BaselineManager {
+    repo: Arc<dyn IRssiEventRepo>,
+    estimator: Mutex<BaselineEstimator>,
+    present: Mutex<PresentReadings>,
+    store: JsonStore<HashMap<String, ThresholdOverride>>,
+}
+
+impl BaselineManager {
+    pub fn new(repo: Arc<dyn IRssiEventRepo>, overrides_path: Option<PathBuf>) -> Self {
+        let store =
+            JsonStore::new(overrides_path.unwrap_or_else(|| default_state_path("thresholds.json")));
+        let mut estimator = BaselineEstimator::default();
+        estimator.replace_overrides(store.load());
+        Self {
+            repo,
+            estimator: Mutex::new(estimator),
+            present: Mutex::default(),
+            store,
+        }
+    }
+
+    fn estimator(&self) -> std::sync::MutexGuard<'_, BaselineEstimator> {
+        self.estimator.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 用最近 `history_days` 天的记录重新学习这些设备
+    pub async fn rebuild(&self, devices: &[String], config: &BaselineConfig) -> AppResult<()> {
+        let from = now_millis() - config.history_days as i64 * 24 * 3600 * 1000;
+        // 命令行可能修改或清除了手动阈值
+        let overrides = self.store.load();
+        self.estimator().replace_overrides(overrides);
+        for device in devices {
+            let buckets = self.repo.rssi_histogram(device, from).await?;
+            let histogram = RssiHistogram::from_buckets(&buckets);
+            info!(
+                "Learned baseline for {} from {} samples",
+                device,
+                histogram.total(None)
+            );
+            self.estimator().set_histogram(device, histogram);
+        }
+        Ok(())
+    }
+
+    /// 状态机处于 `Present` 时收到的读数，确认在场后计入直方图
+    pub fn observe(&self, event: &RssiEvent, last_good: i64) {
+        let confirmed = self.present().push(event.clone(), last_good);
+        self.observe_all(&confirmed);
+    }
+
+    /// 进入 `Present`，此后该设备的记录参与学习
+    pub async fn begin_present(&self, device: &str, ts: i64) -> AppResult<()> {
+        self.repo.begin_present(device, ts).await
+    }
+
+    /// 离开 `Present`，在场时段到 `last_good` 为止；`device` 为空时结束所有设备的
+    pub async fn end_present(&self, device: Option<&str>, last_good: i64) -> AppResult<()> {
+        let confirmed = self.present().finish(last_good);
+        self.observe_all(&confirmed);
+        self.repo.end_present(device, last_good).await
+    }
+
+    fn present(&self) -> std::sync::MutexGuard<'_, PresentReadings> {
+        self.present.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn observe_all(&self, events: &[RssiEvent]) {
+        let mut estimator = self.estimator();
+        for event in events {
+            estimator.observe(event);
+        }
+    }
+
+    /// 当前小时的阈值
+    pub fn thresholds(&self, device: &str, config: &AlgoConfig) -> Thresholds {
+        self.estimator()
+            .thresholds(device, local_hour(now_millis()), config)
+    }
+
+    pub fn override_for(&self, device: &str) -> ThresholdOverride {
+        self.estimator()
+            .overrides()
+            .get(device)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 两项都为空时清除手动阈值
+    pub fn set_override(&self, device: &str, value: ThresholdOverride) -> AppResult<()> {
+        let overrides = {
+            let mut estimator = self.estimator();
+            estimator.set_override(device, value);
+            estimator.overrides().clone()
+        };
+        self.store.save(&overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::ble::presence_state::{PresenceMachine, PresenceState, PresenceTiming};
+
+    fn histogram(hour: u32, samples: &[(i16, u64)]) -> RssiHistogram {
+        let mut h = RssiHistogram::default();
+        for (rssi, count) in samples {
+            h.add(hour, *rssi, *count);
+        }
+        h
+    }
+
+    #[test]
+    fn test_quantile() {
+        let h = histogram(9, &[(-50, 10), (-60, 70), (-80, 20)]);
+        assert_eq!(h.quantile(Some(9), 0.1), Some(-80.0));
+        assert_eq!(h.quantile(Some(9), 0.25), Some(-60.0));
+        assert_eq!(h.quantile(Some(9), 0.95), Some(-50.0));
+        assert_eq!(h.quantile(Some(10), 0.5), None);
+        assert_eq!(h.total(None), 100);
+    }
+
+    #[test]
+    fn test_thresholds_per_device_and_hour() {
+        let config = AlgoConfig {
+            baseline: BaselineConfig {
+                min_samples: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut estimator = BaselineEstimator::default();
+        // 手表离得近，信号强；口袋里的手机弱得多
+        estimator.set_histogram("watch", histogram(9, &[(-45, 80), (-55, 20)]));
+        estimator.set_histogram("phone", histogram(9, &[(-75, 80), (-85, 20)]));
+
+        let watch = estimator.thresholds("watch", 9, &config);
+        assert_eq!(watch.present, -45.0);
+        assert_eq!(watch.absent, -55.0);
+        assert_eq!(watch.source, ThresholdSource::Hourly { samples: 100 });
+        assert_eq!(estimator.thresholds("phone", 9, &config).present, -75.0);
+        // 其他小时没有样本时用全天分布
+        assert_eq!(
+            estimator.thresholds("watch", 15, &config).source,
+            ThresholdSource::Daily { samples: 100 }
+        );
+        // 没有历史时使用固定阈值
+        assert_eq!(
+            estimator.thresholds("unknown", 9, &config),
+            Thresholds::fixed(config.threshold)
+        );
+
+        estimator.set_override(
+            "phone",
+            ThresholdOverride {
+                present: Some(-80.0),
+                absent: None,
+            },
+        );
+        let phone = estimator.thresholds("phone", 9, &config);
+        assert_eq!(phone.present, -80.0);
+        assert_eq!(phone.absent, -85.0);
+        estimator.set_override("phone", ThresholdOverride::default());
+        assert_eq!(estimator.thresholds("phone", 9, &config).present, -75.0);
+    }
+
+    fn reading(ts: i64, rssi: i16) -> RssiEvent {
+        RssiEvent::new("phone".to_string(), String::new(), rssi, ts)
+    }
+
+    /// 按秒回放轨迹（`None` 表示没有广播），只把 `Present` 期间确认的读数计入直方图
+    fn learn_from_trace(trace: &[Option<i16>]) -> BaselineEstimator {
+        let timing = PresenceTiming {
+            leave_dwell: Duration::from_secs(5),
+            return_dwell: Duration::from_secs(3),
+            timeout: Duration::from_secs(15),
+        };
+        let t = Thresholds::fixed(-70.0);
+        let mut machine = PresenceMachine::new(timing, 0);
+        let mut readings = PresentReadings::default();
+        let mut estimator = BaselineEstimator::default();
+        estimator.set_histogram("phone", RssiHistogram::default());
+        for (i, rssi) in trace.iter().enumerate() {
+            let ts = i as i64 * 1000;
+            let mut transitions = vec![];
+            if let Some(rssi) = rssi {
+                transitions.extend(machine.on_rssi(ts, *rssi as f32, &t));
+                if machine.state() == PresenceState::Present {
+                    for e in readings.push(reading(ts, *rssi), machine.last_good()) {
+                        estimator.observe(&e);
+                    }
+                }
+            }
+            transitions.extend(machine.on_tick(ts, &t));
+            for transition in transitions {
+                if transition.from == PresenceState::Present {
+                    for e in readings.finish(machine.last_good()) {
+                        estimator.observe(&e);
+                    }
+                }
+            }
+        }
+        estimator
+    }
+
+    #[test]
+    fn test_away_periods_do_not_lower_thresholds() {
+        let config = AlgoConfig {
+            baseline: BaselineConfig {
+                min_samples: 20,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let desk = |n: usize| -> Vec<Option<i16>> {
+            (0..n).map(|i| Some(-58 - (i % 4) as i16 * 2)).collect()
+        };
+        let mut away = desk(40);
+        // 起身走开：先是几次弱信号，然后收不到广播直到超时
+        away.extend([Some(-80), Some(-85), Some(-88)]);
+        away.extend([None; 20]);
+        // 离开后偶尔收到的弱信号
+        away.extend([Some(-90); 10]);
+        // 回到座位，确认回来前的读数不算在场
+        away.extend(desk(43));
+
+        let mut desk_only = desk(40);
+        desk_only.extend(desk(43).into_iter().skip(3));
+
+        let hour = local_hour(0);
+        let learned = learn_from_trace(&away);
+        let expected = learn_from_trace(&desk_only);
+        assert_eq!(
+            learned.histograms["phone"].bins(None),
+            expected.histograms["phone"].bins(None)
+        );
+        assert_eq!(
+            learned.thresholds("phone", hour, &config),
+            expected.thresholds("phone", hour, &config)
+        );
+
+        // 不区分状态时，离开后的弱信号会拉低离开阈值
+        let mut naive = RssiHistogram::default();
+        for (i, rssi) in away.iter().enumerate() {
+            if let Some(rssi) = rssi {
+                naive.add(local_hour(i as i64 * 1000), *rssi, 1);
+            }
+        }
+        let mut all = BaselineEstimator::default();
+        all.set_histogram("phone", naive);
+        assert!(
+            all.thresholds("phone", hour, &config).absent
+                < learned.thresholds("phone", hour, &config).absent
+        );
+    }
+
+    #[test]
+    fn test_present_readings_wait_for_confirmation() {
+        let mut readings = PresentReadings::default();
+        assert_eq!(readings.push(reading(1000, -60), 1000).len(), 1);
+        // 低于离开阈值，last_good 未前进
+        assert!(readings.push(reading(2000, -85), 1000).is_empty());
+        assert!(readings.push(reading(3000, -86), 1000).is_empty());
+        // 之后出现有效读数，前面的弱读数确认在场
+        assert_eq!(readings.push(reading(4000, -62), 4000).len(), 3);
+        assert!(readings.push(reading(5000, -88), 4000).is_empty());
+        // 离开 Present，last_good 之后的读数丢弃
+        assert!(readings.finish(4000).is_empty());
+        assert!(readings.finish(9000).is_empty());
+    }
+
+    async fn manager(path: PathBuf) -> BaselineManager {
+        let db = sea_orm::Database::connect("sqlite::memory:").await.unwrap();
+        crate::infrastructure::migration::migrate(&db)
+            .await
+            .unwrap();
+        let deps = crate::di::Deps::builder()
+            .with_component_parameters::<crate::infrastructure::DbProvider>(
+                crate::infrastructure::DbProviderParameters { conn: Arc::new(db) },
+            )
+            .build();
+        BaselineManager::new(shaku::HasComponent::resolve(&deps), Some(path))
+    }
+
+    #[tokio::test]
+    async fn test_cleared_override_stays_cleared() {
+        let path = std::env::temp_dir().join(format!("blue-lock-thresholds-{}.json", now_millis()));
+        let manual = ThresholdOverride {
+            present: Some(-60.0),
+            absent: Some(-70.0),
+        };
+        let daemon = manager(path.clone()).await;
+        daemon.set_override("phone", manual).unwrap();
+        assert_eq!(daemon.override_for("phone"), manual);
+
+        // 命令行 `thresholds phone --clear` 直接改写文件
+        let store = JsonStore::<HashMap<String, ThresholdOverride>>::new(path.clone());
+        let mut overrides = store.load();
+        overrides.remove("phone");
+        store.save(&overrides).unwrap();
+
+        daemon
+            .rebuild(&[], &BaselineConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(daemon.override_for("phone"), ThresholdOverride::default());
+        // 之后设置其他设备不会把已清除的值写回
+        daemon.set_override("watch", manual).unwrap();
+        assert!(!store.load().contains_key("phone"));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,346 @@
+//! 根据广播内容判断设备类型
+//!
+//! 优先级：Appearance > Class of Device > Apple Continuity > 服务 UUID > 厂商 ID > 名称。
+
+use std::{collections::HashMap, fmt::Display};
+
+use uuid::Uuid;
+
+const APPLE: u16 = 0x004C;
+const MICROSOFT: u16 = 0x0006;
+const SAMSUNG: u16 = 0x0075;
+const GARMIN: u16 = 0x0087;
+const GOOGLE: u16 = 0x00E0;
+const HUAMI: u16 = 0x0157;
+const HUAWEI: u16 = 0x027D;
+const XIAOMI: u16 = 0x038F;
+
+/// Bluetooth Base UUID 去掉 16 位短 UUID 后的部分
+const BASE_UUID: u128 = 0x0000_0000_0000_1000_8000_0080_5F9B_34FB;
+const BASE_UUID_MASK: u128 = 0xFFFF_0000_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DeviceKind {
+    Phone,
+    Tablet,
+    Computer,
+    Watch,
+    Headphones,
+    /// 手环、心率带等穿戴传感器
+    Wearable,
+    /// 键盘、鼠标等 HID 外设
+    Peripheral,
+    /// AirTag、Tile 等防丢器
+    Tracker,
+    Tv,
+    Beacon,
+    #[default]
+    Unknown,
+}
+
+impl DeviceKind {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Phone => "📱",
+            Self::Tablet => "📱",
+            Self::Computer => "💻",
+            Self::Watch => "⌚️",
+            Self::Headphones => "🎧",
+            Self::Wearable => "💓",
+            Self::Peripheral => "⌨️",
+            Self::Tracker => "🏷️",
+            Self::Tv => "📺",
+            Self::Beacon => "📡",
+            Self::Unknown => "",
+        }
+    }
+
+    /// 适合作为信任目标（随身携带）的设备
+    pub fn is_personal(&self) -> bool {
+        matches!(self, Self::Phone | Self::Watch | Self::Wearable | Self::Tracker)
+    }
+}
+
+impl Display for DeviceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Phone => "手机",
+            Self::Tablet => "平板",
+            Self::Computer => "电脑",
+            Self::Watch => "手表",
+            Self::Headphones => "耳机",
+            Self::Wearable => "穿戴设备",
+            Self::Peripheral => "外设",
+            Self::Tracker => "防丢器",
+            Self::Tv => "电视",
+            Self::Beacon => "信标",
+            Self::Unknown => "未知",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 分类所需的广播字段
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AdvertisementInfo<'a> {
+    pub name: Option<&'a str>,
+    /// GAP Appearance，见 `ScannedDevice::appearance`
+    pub appearance: Option<u16>,
+    /// BR/EDR Class of Device（BlueZ 提供）
+    pub class: Option<u32>,
+    pub manufacturer_data: Option<&'a HashMap<u16, Vec<u8>>>,
+    pub services: &'a [Uuid],
+    pub service_data: Option<&'a HashMap<Uuid, Vec<u8>>>,
+}
+
+pub fn classify(info: &AdvertisementInfo) -> DeviceKind {
+    info.appearance
+        .and_then(from_appearance)
+        .or_else(|| info.class.and_then(from_class_of_device))
+        .or_else(|| {
+            info.manufacturer_data
+                .and_then(|m| m.get(&APPLE))
+                .and_then(|d| from_apple_continuity(d.as_slice()))
+        })
+        .or_else(|| {
+            let service_data = info.service_data.into_iter().flat_map(|d| d.keys());
+            info.services
+                .iter()
+                .chain(service_data)
+                .filter_map(uuid16)
+                .find_map(from_service_uuid)
+        })
+        .or_else(|| {
+            info.manufacturer_data
+                .into_iter()
+                .flat_map(|m| m.keys())
+                .find_map(|id| from_manufacturer(*id))
+        })
+        .or_else(|| info.name.and_then(from_name))
+        .unwrap_or_default()
+}
+
+/// Appearance 高 10 位为类别，低 6 位为子类别
+fn from_appearance(appearance: u16) -> Option<DeviceKind> {
+    let (category, sub) = (appearance >> 6, appearance & 0x3F);
+    match category {
+        0x001 => Some(DeviceKind::Phone),
+        // 子类 0x06 为腕戴式计算机，0x07 为平板
+        0x002 if sub == 0x06 => Some(DeviceKind::Wearable),
+        0x002 if sub == 0x07 => Some(DeviceKind::Tablet),
+        0x002 => Some(DeviceKind::Computer),
+        0x003 => Some(DeviceKind::Watch),
+        0x005 => Some(DeviceKind::Tv),
+        0x008 | 0x009 => Some(DeviceKind::Tracker),
+        0x00D | 0x011 | 0x012 | 0x031 => Some(DeviceKind::Wearable),
+        0x00F => Some(DeviceKind::Peripheral),
+        0x025 => Some(DeviceKind::Headphones),
+        _ => None,
+    }
+}
+
+/// Class of Device：bit 8-12 为主类，bit 2-7 为子类
+fn from_class_of_device(class: u32) -> Option<DeviceKind> {
+    let (major, minor) = ((class >> 8) & 0x1F, (class >> 2) & 0x3F);
+    match major {
+        // 子类 0x06 为腕戴式计算机，0x07 为平板
+        0x01 if minor == 0x06 => Some(DeviceKind::Wearable),
+        0x01 if minor == 0x07 => Some(DeviceKind::Tablet),
+        0x01 => Some(DeviceKind::Computer),
+        0x02 => Some(DeviceKind::Phone),
+        0x04 if matches!(minor, 0x01 | 0x02 | 0x06) => Some(DeviceKind::Headphones),
+        0x04 if matches!(minor, 0x0F | 0x10) => Some(DeviceKind::Tv),
+        0x05 => Some(DeviceKind::Peripheral),
+        0x07 if minor == 0x01 => Some(DeviceKind::Watch),
+        0x07 => Some(DeviceKind::Wearable),
+        _ => None,
+    }
+}
+
+/// Apple 厂商数据由若干 [type, len, payload] 组成
+fn from_apple_continuity(data: &[u8]) -> Option<DeviceKind> {
+    let mut types = Vec::new();
+    let mut i = 0;
+    while i + 1 < data.len() {
+        types.push(data[i]);
+        i += 2 + data[i + 1] as usize;
+    }
+
+    // 按可信程度依次匹配
+    if types.contains(&0x07) {
+        // Proximity Pairing：AirPods / Beats
+        return Some(DeviceKind::Headphones);
+    }
+    if types.contains(&0x0B) {
+        // Watch 连接状态
+        return Some(DeviceKind::Watch);
+    }
+    if types.contains(&0x12) {
+        // Find My 离线查找
+        return Some(DeviceKind::Tracker);
+    }
+    if types.contains(&0x02) {
+        return Some(DeviceKind::Beacon);
+    }
+    if types.contains(&0x09) {
+        // AirPlay 目标
+        return Some(DeviceKind::Tv);
+    }
+    // AirDrop / Handoff / 热点 / Nearby 等消息 iPhone、iPad、Mac、Watch 都会发送，
+    // 无法区分，交给名称等后续规则判断
+    None
+}
+
+fn from_service_uuid(uuid: u16) -> Option<DeviceKind> {
+    match uuid {
+        0x180D | 0x1814 | 0x1816 => Some(DeviceKind::Wearable),
+        0x1812 => Some(DeviceKind::Peripheral),
+        // Exposure Notification 只有手机会广播
+        0xFD6F => Some(DeviceKind::Phone),
+        // Google Fast Pair
+        0xFE2C => Some(DeviceKind::Headphones),
+        // Samsung SmartTag / Tile
+        0xFD5A | 0xFEED => Some(DeviceKind::Tracker),
+        0x110B => Some(DeviceKind::Headphones),
+        _ => None,
+    }
+}
+
+fn from_manufacturer(id: u16) -> Option<DeviceKind> {
+    match id {
+        SAMSUNG | GOOGLE | XIAOMI | HUAWEI => Some(DeviceKind::Phone),
+        GARMIN | HUAMI => Some(DeviceKind::Watch),
+        // Windows Swift Pair / CDP
+        MICROSOFT => Some(DeviceKind::Computer),
+        _ => None,
+    }
+}
+
+fn from_name(name: &str) -> Option<DeviceKind> {
+    let name = name.to_lowercase();
+    let has = |keys: &[&str]| keys.iter().any(|k| name.contains(k));
+    if has(&["watch", "mi band", "smart band"]) {
+        Some(DeviceKind::Watch)
+    } else if has(&["ipad", "galaxy tab", "matepad"]) {
+        Some(DeviceKind::Tablet)
+    } else if has(&["iphone", "galaxy", "pixel", "phone"]) {
+        Some(DeviceKind::Phone)
+    } else if has(&["macbook", "imac", "laptop", "desktop"]) {
+        Some(DeviceKind::Computer)
+    } else if has(&["airpods", "buds", "headphone", "headset"]) {
+        Some(DeviceKind::Headphones)
+    } else {
+        None
+    }
+}
+
+/// 标准 16 位 UUID
+fn uuid16(uuid: &Uuid) -> Option<u16> {
+    let v = uuid.as_u128();
+    (v & BASE_UUID_MASK == BASE_UUID).then(|| (v >> 96) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uuid_from_u16(v: u16) -> Uuid {
+        Uuid::from_u128(BASE_UUID | ((v as u128) << 96))
+    }
+
+    #[test]
+    fn test_appearance_wins() {
+        let info = AdvertisementInfo {
+            name: Some("My iPhone"),
+            appearance: Some(0x00C1), // 手表
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Watch);
+    }
+
+    #[test]
+    fn test_class_of_device() {
+        // 主类 Phone，子类 Smartphone
+        let info = AdvertisementInfo {
+            class: Some(0x5A020C),
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Phone);
+
+        // 主类 Computer：子类 0x03 笔记本、0x06 腕戴式、0x07 平板
+        assert_eq!(from_class_of_device(0x00010C), Some(DeviceKind::Computer));
+        assert_eq!(from_class_of_device(0x000118), Some(DeviceKind::Wearable));
+        assert_eq!(from_class_of_device(0x00011C), Some(DeviceKind::Tablet));
+    }
+
+    #[test]
+    fn test_appearance_computer_subcategories() {
+        // 0x0080 Generic Computer，0x0083 Laptop，0x0086 Wearable Computer，0x0087 Tablet
+        assert_eq!(from_appearance(0x0080), Some(DeviceKind::Computer));
+        assert_eq!(from_appearance(0x0083), Some(DeviceKind::Computer));
+        assert_eq!(from_appearance(0x0086), Some(DeviceKind::Wearable));
+        assert_eq!(from_appearance(0x0087), Some(DeviceKind::Tablet));
+    }
+
+    #[test]
+    fn test_apple_continuity() {
+        let airpods = HashMap::from([(APPLE, vec![0x07, 0x02, 0x01, 0x02])]);
+        let info = AdvertisementInfo {
+            name: Some("Renamed"),
+            manufacturer_data: Some(&airpods),
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Headphones);
+
+        // Nearby 与 Handoff 不代表手机，由名称决定
+        let nearby = HashMap::from([(APPLE, vec![0x10, 0x05, 0x01, 0x18, 0x00, 0x00, 0x00])]);
+        let info = AdvertisementInfo {
+            manufacturer_data: Some(&nearby),
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Unknown);
+        let ipad = AdvertisementInfo {
+            name: Some("Fang's iPad"),
+            manufacturer_data: Some(&nearby),
+            ..Default::default()
+        };
+        assert_eq!(classify(&ipad), DeviceKind::Tablet);
+        let handoff = HashMap::from([(APPLE, vec![0x0C, 0x0E, 0x00, 0x00, 0x00, 0x00])]);
+        let mac = AdvertisementInfo {
+            name: Some("Fang's MacBook Pro"),
+            manufacturer_data: Some(&handoff),
+            ..Default::default()
+        };
+        assert_eq!(classify(&mac), DeviceKind::Computer);
+    }
+
+    #[test]
+    fn test_service_uuid_and_manufacturer() {
+        assert_eq!(uuid16(&uuid_from_u16(0xFD6F)), Some(0xFD6F));
+        let services = [uuid_from_u16(0x180D)];
+        let info = AdvertisementInfo {
+            services: &services,
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Wearable);
+
+        // 非英文名称的安卓手机
+        let samsung = HashMap::from([(SAMSUNG, vec![0x42, 0x04])]);
+        let info = AdvertisementInfo {
+            name: Some("张三的手机"),
+            manufacturer_data: Some(&samsung),
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Phone);
+    }
+
+    #[test]
+    fn test_name_fallback() {
+        let info = AdvertisementInfo {
+            name: Some("Fang's Apple Watch"),
+            ..Default::default()
+        };
+        assert_eq!(classify(&info), DeviceKind::Watch);
+        assert_eq!(classify(&AdvertisementInfo::default()), DeviceKind::Unknown);
+    }
+}
@@ -0,0 +1,40 @@
+//! 常见 Bluetooth SIG 厂商 ID（Assigned Numbers - Company Identifiers）
+
+const COMPANIES: &[(u16, &str)] = &[
+    (0x0000, "Ericsson"),
+    (0x0001, "Nokia"),
+    (0x0002, "Intel"),
+    (0x0006, "Microsoft"),
+    (0x000A, "Qualcomm (CSR)"),
+    (0x000D, "Texas Instruments"),
+    (0x000F, "Broadcom"),
+    (0x001D, "Qualcomm"),
+    (0x004C, "Apple"),
+    (0x0059, "Nordic Semiconductor"),
+    (0x0075, "Samsung"),
+    (0x0087, "Garmin"),
+    (0x00E0, "Google"),
+    (0x012D, "Sony"),
+    (0x0131, "Cypress Semiconductor"),
+    (0x0157, "Huami"),
+    (0x0171, "Amazon"),
+    (0x027D, "Huawei"),
+    (0x02E5, "Espressif"),
+    (0x038F, "Xiaomi"),
+    (0x0499, "Ruuvi Innovations"),
+];
+
+pub fn company_name(id: u16) -> Option<&'static str> {
+    COMPANIES
+        .iter()
+        .find(|(cid, _)| *cid == id)
+        .map(|(_, name)| *name)
+}
+
+/// 十六进制展示原始数据
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
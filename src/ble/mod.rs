@@ -1,7 +1,14 @@
 pub mod service;
+pub mod scanner;
+pub mod adapter;
+pub mod company_ids;
+pub mod classifier;
+pub mod signal;
 pub mod presence_detector;
 pub mod sampler;
 pub mod ring_buffer;
 pub mod detection;
 pub mod model;
-pub mod sliding_window;
\ No newline at end of file
+pub mod sliding_window;
+pub mod session;
+pub mod baseline;pub mod presence_state;
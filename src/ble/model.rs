@@ -1,45 +1,15 @@
 use ort::session::{builder::GraphOptimizationLevel, Session};
+use md5::{Digest, Md5};
 use ndarray::Array;
-use std::{error::Error, fmt::Display, time::Instant};
-use tokio::sync::{mpsc, oneshot};
-
-pub enum DetectionState {
-    /// 物体静止不动  
-    Stationary,  
-    /// 物体正在远离检测器（距离增加）  
-    MovingAway,  
-    /// 物体正在靠近检测器（距离减小）  
-    MovingCloser,  
-    /// 物体状态未知（如遮挡或短暂丢失）  
-    Unknown,  
-}
-
-impl Into<i32> for DetectionState {
-    fn into(self) -> i32 {
-        match self {
-            Self::Stationary => 0,
-            Self::MovingAway => 1,
-            Self::MovingCloser => 2,
-            Self::Unknown => -1,
-        }
-    }
-}
-
-
-impl Display for DetectionState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Stationary => write!(f, "静止"),
-            Self::MovingAway => write!(f, "远离"),
-            Self::MovingCloser => write!(f, "靠近"),
-            Self::Unknown => write!(f, "Unknown"),
-        }
-    }
-}
+use std::time::Instant;
+use tracing::trace;
 
+use crate::{domain::value_objects::ModelResult, errors::ModelError};
+use tokio::sync::{mpsc, oneshot};
 
 pub struct Model {
     session: Session,
+    version: String,
 }
 
 pub struct InstantTimer{
@@ -67,51 +37,82 @@ impl Drop for InstantTimer {
         self.end_time = Instant::now();
         let duration_ns = self.end_time.duration_since(self.start_time).as_nanos();
         let duration_ms = duration_ns as f64 / 1_000_000.0; // 转为毫秒
-        println!("Elapsed: {:.6} ms", duration_ms); // 保留6位小数
+        // 命令行的 watch、model eval 输出到 stdout，计时只进日志
+        trace!("Inference elapsed: {:.6} ms", duration_ms);
     }
 }
 
 
 
 impl Model {
-    pub fn new(model: &[u8]) -> Result<Self, Box<dyn Error>> {
-        let session = Session::builder()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(4)?
+    /// 模型输入窗口长度
+    pub const INPUT_LEN: usize = 9;
+
+    pub fn new(model: &[u8]) -> Result<Self, ModelError> {
+        let load = |e| ModelError::ModelLoad { source: e };
+        let session = Session::builder()
+            .map_err(load)?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(load)?
+            .with_intra_threads(4)
+            .map_err(load)?
             // .with_execution_providers(vec![CoreMLExecutionProvider::default().build()])?
-            .commit_from_memory(model)?;
+            .commit_from_memory(model)
+            .map_err(load)?;
+
+        // 以模型内容的 md5 作为版本，同名模型重新训练后也能区分
+        let version = format!("{:x}", Md5::digest(model))[..8].to_string();
+
+        Ok(Model { session, version })
+    }
 
-        Ok(Model { session })
+    pub fn version(&self) -> &str {
+        &self.version
     }
 
-    pub fn inference(&self, data: Vec<f32>) -> Result<DetectionState, Box<dyn Error>> {
-        if data.len() != 9 {
-            return Err("输入数据长度不正确".into());
+    pub fn inference(&self, data: Vec<f32>) -> Result<ModelResult, ModelError> {
+        let shape_err = |actual| ModelError::InferenceShape {
+            expected: Self::INPUT_LEN,
+            actual,
+        };
+        if data.len() != Self::INPUT_LEN {
+            return Err(shape_err(data.len()));
         }
         if data.iter().filter(|e|**e==0.0).count()>0{
-            return Ok(DetectionState::Unknown);
+            return Ok(ModelResult::Unknown);
         }
         let _tm   = InstantTimer::new();
-        let input_array = Array::from_shape_vec((1, 9), data)?;
-        
-        let outputs = self.session.run(ort::inputs! {
-            "input" => input_array
-        }?)?;
-
-        let output = outputs["output"].try_extract_tensor::<f32>()?;
-        if let Some((index, _)) = output.outer_iter().next().unwrap()
+        let len = data.len();
+        let input_array =
+            Array::from_shape_vec((1, Self::INPUT_LEN), data).map_err(|_| shape_err(len))?;
+
+        let run = |e| ModelError::Inference { source: e };
+        let outputs = self
+            .session
+            .run(ort::inputs! {
+                "input" => input_array
+            }
+            .map_err(run)?)
+            .map_err(run)?;
+
+        let output = outputs["output"].try_extract_tensor::<f32>().map_err(run)?;
+        let invalid = |reason: &str| ModelError::InvalidOutput {
+            reason: reason.to_string(),
+        };
+        let scores = output.outer_iter().next().ok_or_else(|| invalid("empty output"))?;
+        if let Some((index, _)) = scores
             .iter()
             .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap()) 
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
         {
             Ok(match index {
-                0 => DetectionState::Stationary,
-                1 => DetectionState::MovingAway,
-                2 => DetectionState::MovingCloser,
-                _ => return Err("未知状态".into()),
+                0 => ModelResult::Stationary,
+                1 => ModelResult::MovingAway,
+                2 => ModelResult::MovingCloser,
+                _ => return Err(invalid(&format!("unknown class {}", index))),
             })
         } else {
-            Err("无法确定预测类别".into())
+            Err(invalid("no class scores"))
         }
     }
 }
@@ -120,7 +121,7 @@ impl Model {
 // async fn main() -> Result<(), Box<dyn Error>> {
 //     let detector = Model::new(include_bytes!("/Users/fangf/opensource/d2l/rssi-detect/hybrid_model.onnx"))?;
 //     // 创建数据接收channel和结果返回channel
-//     let (tx, mut rx) = mpsc::channel::<(Vec<f32>, oneshot::Sender<DetectionState>)>(10);
+//     let (tx, mut rx) = mpsc::channel::<(Vec<f32>, oneshot::Sender<ModelResult>)>(10);
 
 //     // 启动推理处理循环
 //     tokio::spawn(async move {
@@ -138,10 +139,10 @@ impl Model {
 //     tx.send((test_data.clone(), resp_tx)).await?;
     
 //     match resp_rx.await? {
-//         DetectionState::Stationary => println!("检测结果: 静止"),
-//         DetectionState::MovingCloser => println!("检测结果: 靠近"),
-//         DetectionState::MovingAway => println!("检测结果: 远离"),
-//         DetectionState::Unknown => println!("检测结果: 未知"),
+//         ModelResult::Stationary => println!("检测结果: 静止"),
+//         ModelResult::MovingCloser => println!("检测结果: 靠近"),
+//         ModelResult::MovingAway => println!("检测结果: 远离"),
+//         ModelResult::Unknown => println!("检测结果: 未知"),
 //     }
 
 //     Ok(())
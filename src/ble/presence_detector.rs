@@ -1,39 +1,47 @@
 use crate::{
-    ble::model::Model,
+    domain::entity::rssi_event::RssiEvent,
     dto::detection::{AlgoConfig, DetectionEvent},
-};
-use btleplug::{
-    api::{Central as _, Peripheral as _},
-    platform::{Adapter, PeripheralId},
+    errors::BleError,
 };
 use dioxus::logger::tracing::{info, warn};
-use futures::StreamExt as _;
-use std::{
-    collections::HashMap,
-    time::{Duration, Instant},
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+use tokio::{
+    sync::{broadcast, mpsc, oneshot, watch},
+    task::JoinHandle,
 };
-use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+use super::detection::Detector;
 
-use super::{detection::Detector, service::get_device_fingerprint};
+/// 两次采样的最小间隔
+const SAMPLE_INTERVAL_MS: i64 = 10;
 
 pub struct PresenceDetector {
     cmd_tx: mpsc::Sender<ProcessorMsg>,
+    /// 处理与事件任务，`shutdown` 时等待其退出
+    tasks: Mutex<Vec<JoinHandle<()>>>,
 }
 
 enum ProcessorMsg {
-    Sample { device_id: String, rssi: i16 },
+    Sample(RssiEvent),
+    /// 订阅原始广播记录（如落库、录制）
+    AddRawSink(mpsc::Sender<RssiEvent>),
     GetStatus(oneshot::Sender<HashMap<String, DeviceStatus>>),
     Shutdown,
 }
 
-#[derive(Debug)]
-struct DeviceStatus {
-    last_rssi: f32,
-    last_seen: Instant,
+/// 检测器最近收到的目标设备广播
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceStatus {
+    pub name: String,
+    pub last_rssi: i16,
+    /// unix 毫秒
+    pub last_seen: i64,
 }
 
 impl PresenceDetector {
-    pub async fn new(config: AlgoConfig,sample_tx: mpsc::Sender<Vec<f32>>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn new(config: AlgoConfig,sample_tx: mpsc::Sender<Vec<f32>>) -> Result<Self, BleError> {
         let (cmd_tx, cmd_rx) = mpsc::channel(100);
         let (event_tx, mut event_rx) = mpsc::channel(100);
 
@@ -44,8 +52,8 @@ impl PresenceDetector {
             event_tx,
         );
 
-        // 处理事件
-        tokio::spawn(async move {
+        // 处理事件，`Detector` 随处理任务退出而释放后结束
+        let event_task = tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
                 match event {
                     DetectionEvent::DevicePresent {
@@ -67,11 +75,12 @@ impl PresenceDetector {
             }
         });
 
-        tokio::spawn(Self::processing_task(
-            cmd_rx, detector,
-        ));
+        let processing_task = tokio::spawn(Self::processing_task(cmd_rx, detector));
 
-        Ok(Self { cmd_tx})
+        Ok(Self {
+            cmd_tx,
+            tasks: Mutex::new(vec![processing_task, event_task]),
+        })
     }
 
     async fn processing_task(
@@ -79,76 +88,107 @@ impl PresenceDetector {
         // mut sample_rx: mpsc::Receiver<Vec<f32>>,
         mut detector: Detector<f32>,
     ) {
-        let mut last_sample_at = Instant::now();
-
-        loop {
-            tokio::select! {
-                Some(msg) = cmd_rx.recv() => {
-                    match msg {
-                        ProcessorMsg::Sample { device_id, rssi } => {
-                            // 采样
-                            if last_sample_at.elapsed() >= Duration::from_millis(10) {
-                                info!(
-                                    name: "processor",
-                                    device_id = %format!(r#""{}""#, device_id),  // 用引号包裹
-                                    rssi,
-                                    "Processing sample for device"
-                                );
-                                let _ = detector.process(rssi as f32).await;
-                                last_sample_at = Instant::now();
-                            }
-                        }
-                        ProcessorMsg::GetStatus(reply) => {
-                            let _ = reply.send(HashMap::new());
+        // 以事件时间戳节流，回放时与实时运行结果一致
+        let mut last_sample_ts = i64::MIN;
+        let mut raw_sinks: Vec<mpsc::Sender<RssiEvent>> = Vec::new();
+        let mut status: HashMap<String, DeviceStatus> = HashMap::new();
+
+        while let Some(msg) = cmd_rx.recv().await {
+            match msg {
+                ProcessorMsg::Sample(event) => {
+                    status.insert(event.device.clone(), DeviceStatus {
+                        name: event.name.clone(),
+                        last_rssi: event.rssi,
+                        last_seen: event.ts,
+                    });
+
+                    // 原始记录不做节流，全部转发
+                    raw_sinks.retain(|sink| match sink.try_send(event.clone()) {
+                        Ok(()) => true,
+                        Err(mpsc::error::TrySendError::Full(_)) => {
+                            warn!("Raw sink is full, dropping rssi event");
+                            true
                         }
-                        ProcessorMsg::Shutdown => break,
+                        Err(mpsc::error::TrySendError::Closed(_)) => false,
+                    });
+
+                    // 采样
+                    if event.ts.saturating_sub(last_sample_ts) >= SAMPLE_INTERVAL_MS {
+                        info!(
+                            name: "processor",
+                            device_id = %format!(r#""{}""#, event.device),  // 用引号包裹
+                            rssi = event.rssi,
+                            "Processing sample for device"
+                        );
+                        let _ = detector.process(event.rssi as f32).await;
+                        last_sample_ts = event.ts;
                     }
                 }
+                ProcessorMsg::AddRawSink(sink) => raw_sinks.push(sink),
+                ProcessorMsg::GetStatus(reply) => {
+                    let _ = reply.send(status.clone());
+                }
+                ProcessorMsg::Shutdown => break,
             }
         }
     }
 
+    /// 持续检测目标设备，`target` 更新后立即切换；取消时返回 `Ok`，扫描服务关闭时返回错误。
+    /// 广播来自 `DeviceScanner::subscribe_rssi`，多适配器时已按设备合并
     pub async fn start_detection(
-        self,
-        adapter: Adapter,
-        target: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut events = adapter.events().await?;
-
+        &self,
+        mut rssi_rx: broadcast::Receiver<RssiEvent>,
+        target: watch::Receiver<String>,
+        token: CancellationToken,
+    ) -> Result<(), BleError> {
         loop {
-            if let Some(btleplug::api::CentralEvent::DeviceUpdated(id)) = events.next().await {
-                if let Err(e) = self.handle_device_update(&adapter, &id, target).await {
-                    warn!("Device update error: {}", e);
+            let event = tokio::select! {
+                event = rssi_rx.recv() => event,
+                _ = token.cancelled() => return Ok(()),
+            };
+            match event {
+                Ok(event) if *target.borrow() == event.device => {
+                    self.feed(event).await?;
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("Detection lagged, skipped {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err(BleError::ScannerStopped);
                 }
             }
         }
     }
 
-    async fn handle_device_update(
-        &self,
-        adapter: &Adapter,
-        device_id: &PeripheralId,
-        target: &str,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let device = adapter.peripheral(device_id).await?;
-        let props = device.properties().await?.unwrap_or_default();
-
-        if let (Some(rssi), Some(name)) = (props.rssi, props.local_name) {
-            let fingerprint = get_device_fingerprint(&name);
-            if fingerprint.eq(target) {
-                self.cmd_tx
-                    .send(ProcessorMsg::Sample {
-                        device_id: name.clone(),
-                        rssi,
-                    })
-                    .await?;
-            }
-        }
+    /// 直接注入一条广播记录，用于会话回放
+    pub async fn feed(&self, event: RssiEvent) -> Result<(), BleError> {
+        self.cmd_tx
+            .send(ProcessorMsg::Sample(event))
+            .await
+            .map_err(|_| BleError::DetectorClosed)
+    }
 
-        Ok(())
+    /// 订阅目标设备的原始广播记录，需在 `start_detection` 之前调用
+    pub async fn add_raw_sink(&self, sink: mpsc::Sender<RssiEvent>) {
+        let _ = self.cmd_tx.send(ProcessorMsg::AddRawSink(sink)).await;
     }
 
+    /// 各设备最近一次广播
+    pub async fn status(&self) -> HashMap<String, DeviceStatus> {
+        let (tx, rx) = oneshot::channel();
+        if self.cmd_tx.send(ProcessorMsg::GetStatus(tx)).await.is_err() {
+            return HashMap::new();
+        }
+        rx.await.unwrap_or_default()
+    }
+
+    /// 停止处理并等待内部任务退出，可重复调用
     pub async fn shutdown(&self) {
         let _ = self.cmd_tx.send(ProcessorMsg::Shutdown).await;
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        for task in tasks {
+            let _ = task.await;
+        }
     }
 }
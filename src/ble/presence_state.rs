@@ -0,0 +1,430 @@
+//! 目标设备的在场状态机
+//!
+//! 状态转换由 `TRANSITIONS` 表定义，同一状态按表中顺序取第一条满足条件的转换，每次转换都记录原因
+//! （`TransitionReason`，显示时再按界面语言本地化）。
+//! 在场/离开阈值来自 `BaselineManager`，驻留时间与超时来自 `AlgoConfig`。
+//! 运行时只在进入 `Absent` 时锁屏，状态机是锁屏决策的唯一依据。
+
+use std::{fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{ble::baseline::Thresholds, dto::detection::AlgoConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceState {
+    /// 启动或切换目标后尚未判断
+    #[default]
+    Unknown,
+    Present,
+    /// 信号变弱或中断，尚未超时
+    Leaving,
+    Absent,
+    /// 离开后重新出现强信号，尚未持续足够时间
+    Returning,
+}
+
+impl Display for PresenceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Unknown => "unknown",
+            Self::Present => "present",
+            Self::Leaving => "leaving",
+            Self::Absent => "absent",
+            Self::Returning => "returning",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 状态机使用的时长
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresenceTiming {
+    /// 持续多久收不到不低于离开阈值的信号视为正在离开
+    pub leave_dwell: Duration,
+    /// 重新出现后需持续多久才确认回来
+    pub return_dwell: Duration,
+    /// 最后一次有效信号之后多久判定离开
+    pub timeout: Duration,
+}
+
+impl From<&AlgoConfig> for PresenceTiming {
+    fn from(config: &AlgoConfig) -> Self {
+        Self {
+            leave_dwell: Duration::from_secs(config.leave_dwell_secs),
+            return_dwell: Duration::from_secs(config.return_dwell_secs),
+            timeout: Duration::from_secs(config.timeout_secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dwell {
+    Leave,
+    Return,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Condition {
+    /// 本次读数不低于在场阈值
+    Strong,
+    /// 本次读数低于离开阈值
+    Weak,
+    /// 距最后一次不低于离开阈值的读数已超过给定时长
+    Quiet(Dwell),
+    /// 进入当前状态后持续收到有效读数超过给定时长
+    Sustained(Dwell),
+}
+
+use Condition::*;
+use PresenceState::*;
+
+const TRANSITIONS: &[(PresenceState, Condition, PresenceState)] = &[
+    (Unknown, Strong, Present),
+    (Unknown, Quiet(Dwell::Leave), Leaving),
+    (Present, Quiet(Dwell::Leave), Leaving),
+    (Leaving, Strong, Present),
+    (Leaving, Quiet(Dwell::Timeout), Absent),
+    (Absent, Strong, Returning),
+    (Returning, Weak, Absent),
+    (Returning, Quiet(Dwell::Leave), Absent),
+    (Returning, Sustained(Dwell::Return), Present),
+];
+
+/// 状态转换的原因，`Display` 用于日志，界面与通知见 `Localize`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TransitionReason {
+    /// 读数不低于在场阈值
+    Strong { rssi: f32, threshold: f32 },
+    /// 读数低于离开阈值
+    Weak { rssi: f32, threshold: f32 },
+    /// `secs` 秒未收到不低于离开阈值的读数
+    Quiet { secs: u64, threshold: f32 },
+    /// 持续 `secs` 秒收到不低于离开阈值的读数
+    Sustained { secs: u64 },
+}
+
+impl Display for TransitionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strong { rssi, threshold } => {
+                write!(
+                    f,
+                    "信号 {:.0} dBm 不低于在场阈值 {:.0} dBm",
+                    rssi, threshold
+                )
+            }
+            Self::Weak { rssi, threshold } => {
+                write!(f, "信号 {:.0} dBm 低于离开阈值 {:.0} dBm", rssi, threshold)
+            }
+            Self::Quiet { secs, threshold } => write!(
+                f,
+                "{} 秒未收到不低于离开阈值 {:.0} dBm 的信号",
+                secs, threshold
+            ),
+            Self::Sustained { secs } => write!(f, "信号持续 {} 秒未低于离开阈值", secs),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenceTransition {
+    pub ts: i64,
+    pub from: PresenceState,
+    pub to: PresenceState,
+    pub reason: TransitionReason,
+}
+
+impl PresenceTransition {
+    /// 是否为需要锁屏的离开，回来未能确认而退回 `Absent` 不算再次离开
+    pub fn is_departure(&self) -> bool {
+        self.to == Absent && self.from != Returning
+    }
+}
+
+pub struct PresenceMachine {
+    state: PresenceState,
+    timing: PresenceTiming,
+    /// 进入当前状态的时间
+    entered: i64,
+    /// 最后一次不低于离开阈值的读数时间
+    last_good: i64,
+}
+
+impl PresenceMachine {
+    pub fn new(timing: PresenceTiming, now: i64) -> Self {
+        Self {
+            state: Unknown,
+            timing,
+            entered: now,
+            last_good: now,
+        }
+    }
+
+    pub fn state(&self) -> PresenceState {
+        self.state
+    }
+
+    /// 最后一次不低于离开阈值的读数时间
+    pub fn last_good(&self) -> i64 {
+        self.last_good
+    }
+
+    pub fn set_timing(&mut self, timing: PresenceTiming) {
+        self.timing = timing;
+    }
+
+    /// 切换目标后从 `Unknown` 重新判断
+    pub fn reset(&mut self, now: i64) {
+        *self = Self::new(self.timing, now);
+    }
+
+    /// 视为信号未中断，用于适配器不可用且策略为放行时
+    pub fn hold(&mut self, now: i64) {
+        self.last_good = self.last_good.max(now);
+    }
+
+    pub fn on_rssi(
+        &mut self,
+        ts: i64,
+        rssi: f32,
+        thresholds: &Thresholds,
+    ) -> Option<PresenceTransition> {
+        if rssi >= thresholds.absent {
+            self.last_good = self.last_good.max(ts);
+        }
+        self.step(ts, Some(rssi), thresholds)
+    }
+
+    /// 定时调用，处理没有广播时的超时
+    pub fn on_tick(&mut self, now: i64, thresholds: &Thresholds) -> Option<PresenceTransition> {
+        self.step(now, None, thresholds)
+    }
+
+    fn step(
+        &mut self,
+        now: i64,
+        rssi: Option<f32>,
+        thresholds: &Thresholds,
+    ) -> Option<PresenceTransition> {
+        let &(from, when, to) = TRANSITIONS
+            .iter()
+            .filter(|(from, _, _)| *from == self.state)
+            .find(|(_, when, _)| self.holds(*when, now, rssi, thresholds))?;
+        let reason = self.reason(when, rssi, thresholds);
+        info!("Presence {} -> {}: {}", from, to, reason);
+        self.state = to;
+        self.entered = now;
+        Some(PresenceTransition {
+            ts: now,
+            from,
+            to,
+            reason,
+        })
+    }
+
+    fn dwell(&self, dwell: Dwell) -> i64 {
+        let d = match dwell {
+            Dwell::Leave => self.timing.leave_dwell,
+            Dwell::Return => self.timing.return_dwell,
+            Dwell::Timeout => self.timing.timeout,
+        };
+        d.as_millis() as i64
+    }
+
+    fn holds(&self, when: Condition, now: i64, rssi: Option<f32>, t: &Thresholds) -> bool {
+        match when {
+            Strong => rssi.is_some_and(|r| r >= t.present),
+            Weak => rssi.is_some_and(|r| r < t.absent),
+            Quiet(d) => now - self.last_good >= self.dwell(d),
+            Sustained(d) => self.last_good - self.entered >= self.dwell(d),
+        }
+    }
+
+    fn reason(&self, when: Condition, rssi: Option<f32>, t: &Thresholds) -> TransitionReason {
+        let rssi = rssi.unwrap_or_default();
+        let secs = |d| self.dwell(d) as u64 / 1000;
+        match when {
+            Strong => TransitionReason::Strong {
+                rssi,
+                threshold: t.present,
+            },
+            Weak => TransitionReason::Weak {
+                rssi,
+                threshold: t.absent,
+            },
+            Quiet(d) => TransitionReason::Quiet {
+                secs: secs(d),
+                threshold: t.absent,
+            },
+            Sustained(d) => TransitionReason::Sustained { secs: secs(d) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMING: PresenceTiming = PresenceTiming {
+        leave_dwell: Duration::from_secs(5),
+        return_dwell: Duration::from_secs(3),
+        timeout: Duration::from_secs(15),
+    };
+
+    fn thresholds() -> Thresholds {
+        Thresholds::fixed(-70.0)
+    }
+
+    /// 按秒回放轨迹，每秒一条读数（`None` 表示没有广播）并触发一次定时检查，返回所有转换
+    fn replay(
+        machine: &mut PresenceMachine,
+        start: i64,
+        trace: &[Option<i16>],
+    ) -> Vec<(i64, PresenceState)> {
+        let t = thresholds();
+        let mut transitions = vec![];
+        for (i, rssi) in trace.iter().enumerate() {
+            let now = (start + i as i64) * 1000;
+            if let Some(rssi) = rssi {
+                transitions.extend(machine.on_rssi(now, *rssi as f32, &t));
+            }
+            transitions.extend(machine.on_tick(now, &t));
+        }
+        transitions
+            .into_iter()
+            .map(|t| (t.ts / 1000, t.to))
+            .collect()
+    }
+
+    fn trace(parts: &[(Option<i16>, usize)]) -> Vec<Option<i16>> {
+        parts
+            .iter()
+            .flat_map(|(rssi, n)| std::iter::repeat(*rssi).take(*n))
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_away_and_back() {
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let transitions = replay(
+            &mut m,
+            0,
+            &trace(&[(Some(-60), 10), (Some(-85), 25), (Some(-60), 5)]),
+        );
+        assert_eq!(
+            transitions,
+            vec![
+                (0, Present),
+                // 最后一次有效读数在第 9 秒
+                (14, Leaving),
+                (24, Absent),
+                (35, Returning),
+                (38, Present),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_short_dropout_keeps_present() {
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let transitions = replay(
+            &mut m,
+            0,
+            &trace(&[(Some(-60), 5), (None, 3), (Some(-90), 1), (Some(-60), 5)]),
+        );
+        assert_eq!(transitions, vec![(0, Present)]);
+    }
+
+    #[test]
+    fn test_middle_band_is_hysteresis() {
+        // 介于离开阈值 -75 与在场阈值 -70 之间：既不离开，也不确认在场
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let transitions = replay(&mut m, 0, &trace(&[(Some(-60), 2), (Some(-72), 60)]));
+        assert_eq!(transitions, vec![(0, Present)]);
+
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let transitions = replay(&mut m, 0, &trace(&[(Some(-72), 60)]));
+        assert!(transitions.is_empty());
+        assert_eq!(m.state(), Unknown);
+    }
+
+    #[test]
+    fn test_return_requires_dwell() {
+        let mut m = PresenceMachine::new(TIMING, 0);
+        replay(&mut m, 0, &trace(&[(None, 30)]));
+        assert_eq!(m.state(), Absent);
+
+        // 一次强信号随即变弱，不算回来
+        let transitions = replay(&mut m, 30, &trace(&[(Some(-60), 1), (Some(-90), 1)]));
+        assert_eq!(transitions, vec![(30, Returning), (31, Absent)]);
+
+        let transitions = replay(&mut m, 40, &trace(&[(Some(-60), 2), (None, 10)]));
+        assert_eq!(transitions, vec![(40, Returning), (46, Absent)]);
+    }
+
+    #[test]
+    fn test_departure() {
+        let t = thresholds();
+        let mut m = PresenceMachine::new(TIMING, 0);
+        m.on_rssi(0, -60.0, &t);
+        m.on_tick(5000, &t);
+        assert!(m.on_tick(15_000, &t).unwrap().is_departure());
+        m.on_rssi(20_000, -60.0, &t);
+        assert!(!m.on_rssi(21_000, -90.0, &t).unwrap().is_departure());
+    }
+
+    #[test]
+    fn test_leaving_recovers_and_hold() {
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let transitions = replay(
+            &mut m,
+            0,
+            &trace(&[(Some(-60), 1), (None, 8), (Some(-65), 1)]),
+        );
+        assert_eq!(transitions, vec![(0, Present), (5, Leaving), (9, Present)]);
+
+        // 放行期间不会超时
+        for s in 10..60 {
+            m.hold(s * 1000);
+            assert!(m.on_tick(s * 1000, &thresholds()).is_none());
+        }
+        m.reset(60_000);
+        assert_eq!(m.state(), Unknown);
+    }
+
+    #[test]
+    fn test_transition_reasons() {
+        let t = thresholds();
+        let mut m = PresenceMachine::new(TIMING, 0);
+        let present = m.on_rssi(0, -62.0, &t).unwrap();
+        assert_eq!(
+            present.reason,
+            TransitionReason::Strong {
+                rssi: -62.0,
+                threshold: -70.0
+            }
+        );
+        let leaving = m.on_tick(5000, &t).unwrap();
+        assert_eq!(
+            leaving.reason,
+            TransitionReason::Quiet {
+                secs: 5,
+                threshold: -75.0
+            }
+        );
+        assert_eq!(
+            leaving.reason.to_string(),
+            "5 秒未收到不低于离开阈值 -75 dBm 的信号"
+        );
+        // 原因按结构序列化，由订阅方本地化
+        assert_eq!(
+            serde_json::to_value(leaving.reason).unwrap(),
+            serde_json::json!({ "kind": "quiet", "secs": 5, "threshold": -75.0 })
+        );
+    }
+}
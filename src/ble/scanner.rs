@@ -0,0 +1,716 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use btleplug::{
+    api::{
+        BDAddr, Central as _, CentralEvent, CharPropFlags, Peripheral as _, PeripheralProperties,
+        ScanFilter, WriteType,
+    },
+    platform::{Adapter, Peripheral, PeripheralId},
+};
+use futures::StreamExt as _;
+use tokio::sync::{broadcast, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    domain::entity::rssi_event::{now_millis, RssiEvent},
+    errors::{AppResult, BleError},
+    supervisor::Supervisor,
+};
+
+use super::{
+    adapter::{follow_adapters, ActiveAdapter},
+    classifier::{classify, AdvertisementInfo, DeviceKind},
+    service::get_device_fingerprint,
+};
+
+/// 超过该时间未收到广播即视为离开
+const STALE_AFTER_MS: i64 = 60_000;
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(5);
+/// 每个设备保留的 RSSI 历史条数
+const RSSI_HISTORY_LEN: usize = 120;
+/// 多适配器合并时，只取该时间内各适配器的读数
+const MERGE_WINDOW_MS: i64 = 3_000;
+/// Generic Access 服务中的 Appearance 特征
+const APPEARANCE_UUID: Uuid = Uuid::from_u128(0x0000_2A01_0000_1000_8000_0080_5F9B_34FB);
+
+/// 扫描到的设备及其最近的广播内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScannedDevice {
+    pub id: String,
+    pub name: Option<String>,
+    pub fingerprint: Option<String>,
+    pub address: String,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub rssi: Option<i16>,
+    /// (时间戳, RSSI)，old->new，多适配器时为合并后的值
+    pub rssi_history: VecDeque<(i64, i16)>,
+    /// 各适配器最近一次的 (时间戳, RSSI)
+    pub sources: HashMap<String, (i64, i16)>,
+    pub tx_power: Option<i16>,
+    /// BR/EDR Class of Device
+    pub class: Option<u32>,
+    /// GAP Appearance，btleplug 不暴露广播中的该字段，检查 GATT 时从 Generic Access 服务读取
+    pub appearance: Option<u16>,
+    pub manufacturer_data: HashMap<u16, Vec<u8>>,
+    pub service_data: HashMap<Uuid, Vec<u8>>,
+    pub services: Vec<Uuid>,
+}
+
+impl ScannedDevice {
+    pub fn new(id: String, now: i64) -> Self {
+        Self {
+            id,
+            name: None,
+            fingerprint: None,
+            address: String::new(),
+            first_seen: now,
+            last_seen: now,
+            rssi: None,
+            rssi_history: VecDeque::new(),
+            tx_power: None,
+            class: None,
+            appearance: None,
+            manufacturer_data: HashMap::new(),
+            service_data: HashMap::new(),
+            services: Vec::new(),
+            sources: HashMap::new(),
+        }
+    }
+
+    /// 记录某个适配器的读数，返回合并后的 RSSI：取最近窗口内最强的一个
+    pub fn record_rssi(&mut self, source: &str, ts: i64, rssi: i16) -> i16 {
+        self.sources.insert(source.to_string(), (ts, rssi));
+        self.sources.retain(|_, (t, _)| ts - *t <= MERGE_WINDOW_MS);
+        let merged = self
+            .sources
+            .values()
+            .map(|(_, r)| *r)
+            .max()
+            .unwrap_or(rssi);
+        self.rssi = Some(merged);
+        self.rssi_history.push_back((ts, merged));
+        if self.rssi_history.len() > RSSI_HISTORY_LEN {
+            self.rssi_history.pop_front();
+        }
+        merged
+    }
+
+    pub fn kind(&self) -> DeviceKind {
+        classify(&AdvertisementInfo {
+            name: self.name.as_deref(),
+            appearance: self.appearance,
+            class: self.class,
+            manufacturer_data: Some(&self.manufacturer_data),
+            services: &self.services,
+            service_data: Some(&self.service_data),
+        })
+    }
+}
+
+/// GATT 特征
+#[derive(Debug, Clone, PartialEq)]
+pub struct GattCharacteristic {
+    pub uuid: Uuid,
+    pub properties: String,
+}
+
+/// GATT 服务
+#[derive(Debug, Clone, PartialEq)]
+pub struct GattService {
+    pub uuid: Uuid,
+    pub primary: bool,
+    pub characteristics: Vec<GattCharacteristic>,
+}
+
+/// 设备 ID 可能包含 `/` 或 `:`，放入路由前转为十六进制
+pub fn encode_device_key(id: &str) -> String {
+    id.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Appearance 特征值为小端 u16
+fn parse_appearance(value: &[u8]) -> Option<u16> {
+    match value {
+        [lo, hi, ..] => Some(u16::from_le_bytes([*lo, *hi])),
+        _ => None,
+    }
+}
+
+pub fn decode_device_key(key: &str) -> Option<String> {
+    let bytes = (0..key.len())
+        .step_by(2)
+        .map(|i| key.get(i..i + 2).and_then(|h| u8::from_str_radix(h, 16).ok()))
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// 推送给 UI 的增量更新
+#[derive(Debug, Clone)]
+pub enum ScanUpdate {
+    Upsert(ScannedDevice),
+    Removed(String),
+}
+
+/// 常驻扫描服务，全局只启动一次，检测器与设备页共享同一次扫描。
+/// 可同时在多个适配器上扫描，同一设备（按地址）的读数合并到一条记录。
+/// 扫描与过期清理任务由 `Supervisor` 持有，适配器切换或重新开启后自动重新扫描，
+/// 关闭后需调用 `stop` 停止适配器扫描
+pub struct DeviceScanner {
+    active_rx: watch::Receiver<Vec<ActiveAdapter>>,
+    /// 开始过扫描的适配器，`stop` 时停止它们的扫描
+    scanning: Mutex<HashMap<String, Adapter>>,
+    devices: RwLock<HashMap<String, ScannedDevice>>,
+    update_tx: broadcast::Sender<ScanUpdate>,
+    rssi_tx: broadcast::Sender<RssiEvent>,
+}
+
+impl DeviceScanner {
+    pub fn start(active_rx: watch::Receiver<Vec<ActiveAdapter>>, supervisor: &Supervisor) -> Arc<Self> {
+        let scanner = Arc::new(Self::new(active_rx));
+
+        supervisor.spawn("scanner", {
+            let scanner = scanner.clone();
+            move |token| scanner.clone().run(token)
+        });
+        supervisor.spawn("scanner-expire", {
+            let scanner = scanner.clone();
+            move |token| {
+                let scanner = scanner.clone();
+                async move {
+                    let mut tick = tokio::time::interval(EXPIRE_INTERVAL);
+                    loop {
+                        tokio::select! {
+                            _ = tick.tick() => scanner.expire(now_millis() - STALE_AFTER_MS),
+                            _ = token.cancelled() => return Ok(()),
+                        }
+                    }
+                }
+            }
+        });
+
+        scanner
+    }
+
+    fn new(active_rx: watch::Receiver<Vec<ActiveAdapter>>) -> Self {
+        let (update_tx, _) = broadcast::channel(256);
+        let (rssi_tx, _) = broadcast::channel(1024);
+        Self {
+            active_rx,
+            scanning: Mutex::new(HashMap::new()),
+            devices: RwLock::new(HashMap::new()),
+            update_tx,
+            rssi_tx,
+        }
+    }
+
+    async fn run(self: Arc<Self>, token: CancellationToken) -> AppResult<()> {
+        follow_adapters(self.active_rx.clone(), token.clone(), |active| {
+            self.clone().scan(active, token.clone())
+        })
+        .await
+    }
+
+    /// 订阅适配器事件并开始扫描，事件流中断时返回错误由 `Supervisor` 重启
+    async fn scan(self: Arc<Self>, active: ActiveAdapter, token: CancellationToken) -> AppResult<()> {
+        let ActiveAdapter { name, adapter } = active;
+        let map_err = |e| BleError::Scan {
+            adapter: name.clone(),
+            source: e,
+        };
+        info!("Scanning on {}", name);
+        let mut events = adapter.events().await.map_err(map_err)?;
+        adapter
+            .start_scan(ScanFilter::default())
+            .await
+            .map_err(map_err)?;
+        self.scanning
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.clone(), adapter.clone());
+
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = token.cancelled() => return Ok(()),
+            };
+            // 以收到事件的时间作为广播时间，不受后续查询属性的耗时影响
+            let ts = now_millis();
+            // 一次广播会同时产生 DeviceDiscovered/DeviceUpdated 与若干数据事件，属性中的 RSSI
+            // 是同一个缓存值，只有前两者代表新的读数（首次出现时只有 DeviceDiscovered），
+            // 其余事件只更新广播内容
+            let (id, new_rssi) = match event {
+                Some(CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id)) => {
+                    (id, true)
+                }
+                Some(
+                    CentralEvent::ManufacturerDataAdvertisement { id, .. }
+                    | CentralEvent::ServiceDataAdvertisement { id, .. }
+                    | CentralEvent::ServicesAdvertisement { id, .. },
+                ) => (id, false),
+                Some(_) => continue,
+                None => return Err(BleError::ScanClosed { adapter: name }.into()),
+            };
+            if let Err(e) = self.refresh(&name, &adapter, &id, ts, new_rssi).await {
+                warn!("Failed to refresh device {}: {}", id, e);
+            }
+        }
+    }
+
+    /// 停止适配器扫描，应在 `Supervisor::shutdown` 之后调用
+    pub async fn stop(&self) {
+        let scanning = std::mem::take(&mut *self.scanning.lock().unwrap_or_else(|e| e.into_inner()));
+        for (name, adapter) in scanning {
+            match adapter.stop_scan().await {
+                Ok(()) => info!("Scan stopped on {}", name),
+                Err(e) => warn!("Failed to stop scan on {}: {}", name, e),
+            }
+        }
+    }
+
+    /// 合并后的目标广播流，只包含有名称的设备
+    pub fn subscribe_rssi(&self) -> broadcast::Receiver<RssiEvent> {
+        self.rssi_tx.subscribe()
+    }
+
+    pub fn snapshot(&self) -> Vec<ScannedDevice> {
+        self.devices
+            .read()
+            .map(|d| d.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<ScannedDevice> {
+        self.devices.read().ok()?.get(id).cloned()
+    }
+
+    /// 订阅增量更新，先订阅再取 `snapshot` 以免遗漏
+    pub fn subscribe(&self) -> broadcast::Receiver<ScanUpdate> {
+        self.update_tx.subscribe()
+    }
+
+    /// 按指纹查找最近出现的设备
+    pub fn find_by_fingerprint(&self, fingerprint: &str) -> Option<ScannedDevice> {
+        self.devices
+            .read()
+            .ok()?
+            .values()
+            .filter(|d| d.fingerprint.as_deref() == Some(fingerprint))
+            .max_by_key(|d| d.last_seen)
+            .cloned()
+    }
+
+    /// 在活动适配器上查找外设，`id` 为外设 ID 或地址
+    async fn peripheral(&self, id: &str) -> Result<Peripheral, BleError> {
+        let active = self.active_rx.borrow().clone();
+        if active.is_empty() {
+            return Err(BleError::AdapterUnavailable);
+        }
+        for a in active {
+            let found = a
+                .adapter
+                .peripherals()
+                .await
+                .map_err(|e| BleError::Adapter { source: e })?
+                .into_iter()
+                .find(|p| p.id().to_string() == id || p.address().to_string() == id);
+            if let Some(p) = found {
+                return Ok(p);
+            }
+        }
+        Err(BleError::DeviceNotFound { id: id.to_string() })
+    }
+
+    /// 设备当前是否与本机保持连接，已配对的手机、手表通常会保持连接
+    pub async fn is_connected(&self, id: &str) -> Result<bool, BleError> {
+        self.peripheral(id)
+            .await?
+            .is_connected()
+            .await
+            .map_err(|e| BleError::Gatt {
+                id: id.to_string(),
+                source: e,
+            })
+    }
+
+    /// 在 `limit` 内连接设备、向特征写入质询并读回应答；
+    /// 由本方建立的连接在完成、超时或被取消时都会断开
+    pub async fn challenge(
+        &self,
+        id: &str,
+        service: Uuid,
+        characteristic: Uuid,
+        challenge: &[u8],
+        limit: Duration,
+    ) -> Result<Vec<u8>, BleError> {
+        let peripheral = self.peripheral(id).await?;
+        let gatt = |e| BleError::Gatt {
+            id: id.to_string(),
+            source: e,
+        };
+        let was_connected = peripheral.is_connected().await.map_err(gatt)?;
+        let owned = (!was_connected).then(|| peripheral.clone());
+        with_owned_link(owned, id, limit, async {
+            if !was_connected {
+                peripheral.connect().await.map_err(gatt)?;
+            }
+            peripheral.discover_services().await.map_err(gatt)?;
+            let c = peripheral
+                .characteristics()
+                .into_iter()
+                .find(|c| c.service_uuid == service && c.uuid == characteristic)
+                .ok_or_else(|| BleError::CharacteristicNotFound {
+                    id: id.to_string(),
+                    uuid: characteristic,
+                })?;
+            peripheral
+                .write(&c, challenge, WriteType::WithResponse)
+                .await
+                .map_err(gatt)?;
+            peripheral.read(&c).await.map_err(gatt)
+        })
+        .await
+    }
+
+    /// 连接设备并枚举 GATT 服务与特征，完成后断开；
+    /// 平台开放 Generic Access 服务时顺带读取 Appearance 用于分类
+    pub async fn inspect_gatt(
+        &self,
+        id: &str,
+    ) -> Result<Vec<GattService>, BleError> {
+        let gatt = |e| BleError::Gatt {
+            id: id.to_string(),
+            source: e,
+        };
+        let peripheral = self.peripheral(id).await?;
+
+        let was_connected = peripheral.is_connected().await.map_err(gatt)?;
+        if !was_connected {
+            peripheral.connect().await.map_err(gatt)?;
+        }
+        let discovered = peripheral.discover_services().await;
+        let appearance = match &discovered {
+            Ok(()) => self.read_appearance(&peripheral).await,
+            Err(_) => None,
+        };
+        let services = peripheral
+            .services()
+            .into_iter()
+            .map(|s| GattService {
+                uuid: s.uuid,
+                primary: s.primary,
+                characteristics: s
+                    .characteristics
+                    .into_iter()
+                    .map(|c| GattCharacteristic {
+                        uuid: c.uuid,
+                        properties: format!("{:?}", c.properties),
+                    })
+                    .collect(),
+            })
+            .collect();
+        if !was_connected {
+            if let Err(e) = peripheral.disconnect().await {
+                warn!("Failed to disconnect {}: {}", id, e);
+            }
+        }
+        discovered.map_err(gatt)?;
+        if let Some(appearance) = appearance {
+            self.set_appearance(id, appearance);
+        }
+        Ok(services)
+    }
+
+    /// 读取失败或特征不可读时返回 `None`，不影响检查结果
+    async fn read_appearance(&self, peripheral: &Peripheral) -> Option<u16> {
+        let c = peripheral
+            .characteristics()
+            .into_iter()
+            .find(|c| c.uuid == APPEARANCE_UUID && c.properties.contains(CharPropFlags::READ))?;
+        match peripheral.read(&c).await {
+            Ok(value) => parse_appearance(&value),
+            Err(e) => {
+                warn!("Failed to read appearance: {}", e);
+                None
+            }
+        }
+    }
+
+    fn set_appearance(&self, id: &str, appearance: u16) {
+        let updated = {
+            let mut devices = self.devices.write().unwrap_or_else(|e| e.into_inner());
+            let Some(device) = devices.get_mut(id) else {
+                return;
+            };
+            device.appearance = Some(appearance);
+            device.clone()
+        };
+        let _ = self.update_tx.send(ScanUpdate::Upsert(updated));
+    }
+
+    async fn refresh(
+        &self,
+        source: &str,
+        adapter: &Adapter,
+        id: &PeripheralId,
+        ts: i64,
+        new_rssi: bool,
+    ) -> Result<(), BleError> {
+        let scan = |e| BleError::Scan {
+            adapter: source.to_string(),
+            source: e,
+        };
+        let peripheral = adapter.peripheral(id).await.map_err(scan)?;
+        let Some(props) = peripheral.properties().await.map_err(scan)? else {
+            return Ok(());
+        };
+        self.apply(source, &id.to_string(), props, ts, new_rssi);
+        Ok(())
+    }
+
+    /// 合并一次广播事件的属性；`new_rssi` 为 false 时不记录 RSSI，也不发送 `RssiEvent`
+    fn apply(
+        &self,
+        source: &str,
+        id: &str,
+        props: PeripheralProperties,
+        now: i64,
+        new_rssi: bool,
+    ) {
+        // 不同适配器上的 PeripheralId 不同，有地址时按地址合并
+        let key = if props.address == BDAddr::default() {
+            id.to_string()
+        } else {
+            props.address.to_string()
+        };
+
+        let updated = {
+            let mut devices = self.devices.write().unwrap_or_else(|e| e.into_inner());
+            let device = devices
+                .entry(key.clone())
+                .or_insert_with(|| ScannedDevice::new(key, now));
+            device.last_seen = now;
+            device.address = props.address.to_string();
+            if let Some(name) = props.local_name {
+                device.fingerprint = Some(get_device_fingerprint(&name));
+                device.name = Some(name);
+            }
+            let merged = props
+                .rssi
+                .filter(|_| new_rssi)
+                .map(|rssi| device.record_rssi(source, now, rssi));
+            if let (Some(rssi), Some(fp), Some(name)) = (merged, &device.fingerprint, &device.name) {
+                // 没有订阅者时发送失败，忽略即可
+                let _ = self
+                    .rssi_tx
+                    .send(RssiEvent::new(fp.clone(), name.clone(), rssi, now));
+            }
+            device.tx_power = props.tx_power_level.or(device.tx_power);
+            device.class = props.class.or(device.class);
+            // 广播内容按字段累积，新值覆盖旧值
+            device.manufacturer_data.extend(props.manufacturer_data);
+            device.service_data.extend(props.service_data);
+            for uuid in props.services {
+                if !device.services.contains(&uuid) {
+                    device.services.push(uuid);
+                }
+            }
+            device.clone()
+        };
+
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.update_tx.send(ScanUpdate::Upsert(updated));
+    }
+
+    fn expire(&self, before: i64) {
+        let removed: Vec<String> = match self.devices.write() {
+            Ok(mut devices) => {
+                let stale: Vec<String> = devices
+                    .values()
+                    .filter(|d| d.last_seen < before)
+                    .map(|d| d.id.clone())
+                    .collect();
+                for id in &stale {
+                    devices.remove(id);
+                }
+                stale
+            }
+            Err(_) => return,
+        };
+        for id in removed {
+            let _ = self.update_tx.send(ScanUpdate::Removed(id));
+        }
+    }
+}
+
+/// 可断开的 GATT 连接，测试中以假外设替代
+#[async_trait::async_trait]
+trait Link: Send + Sync + 'static {
+    async fn close(&self) -> Result<(), btleplug::Error>;
+}
+
+#[async_trait::async_trait]
+impl Link for Peripheral {
+    async fn close(&self) -> Result<(), btleplug::Error> {
+        self.disconnect().await
+    }
+}
+
+/// 本方建立的连接：正常结束时等待断开，随 future 被丢弃时在后台断开
+struct OwnedLink<L: Link> {
+    link: Option<L>,
+    id: String,
+}
+
+impl<L: Link> OwnedLink<L> {
+    async fn release(mut self) {
+        if let Some(link) = self.link.take() {
+            close_link(&link, &self.id).await;
+        }
+    }
+}
+
+impl<L: Link> Drop for OwnedLink<L> {
+    fn drop(&mut self) {
+        let Some(link) = self.link.take() else {
+            return;
+        };
+        let id = std::mem::take(&mut self.id);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move { close_link(&link, &id).await });
+        }
+    }
+}
+
+async fn close_link<L: Link>(link: &L, id: &str) {
+    if let Err(e) = link.close().await {
+        warn!("Failed to disconnect {}: {}", id, e);
+    }
+}
+
+/// 在时限内执行 GATT 操作；`link` 为本方建立的连接，无论成功、超时还是被取消都会断开
+async fn with_owned_link<L: Link, T>(
+    link: Option<L>,
+    id: &str,
+    limit: Duration,
+    op: impl std::future::Future<Output = Result<T, BleError>>,
+) -> Result<T, BleError> {
+    let owned = OwnedLink {
+        link,
+        id: id.to_string(),
+    };
+    let res = tokio::time::timeout(limit, op).await;
+    owned.release().await;
+    res.map_err(|_| BleError::GattTimeout { id: id.to_string() })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_rssi_across_adapters() {
+        let mut d = ScannedDevice::new("AA:BB:CC:DD:EE:FF".into(), 0);
+        assert_eq!(d.record_rssi("hci0", 0, -80), -80);
+        // 外置适配器信号更好
+        assert_eq!(d.record_rssi("hci1", 500, -60), -60);
+        assert_eq!(d.record_rssi("hci0", 1_000, -78), -60);
+        // hci1 的读数过期后退回 hci0
+        assert_eq!(d.record_rssi("hci0", 4_000, -75), -75);
+        assert_eq!(d.sources.len(), 1);
+        assert_eq!(d.rssi, Some(-75));
+        assert_eq!(d.rssi_history.len(), 4);
+    }
+
+    #[test]
+    fn test_appearance_classifies_device() {
+        let mut d = ScannedDevice::new("AA:BB:CC:DD:EE:FF".into(), 0);
+        d.name = Some("Fang's iPhone".into());
+        assert_eq!(d.kind(), DeviceKind::Phone);
+        // Generic Watch (0x00C0)
+        d.appearance = parse_appearance(&[0xC0, 0x00]);
+        assert_eq!(d.kind(), DeviceKind::Watch);
+        assert_eq!(parse_appearance(&[0xC0]), None);
+    }
+
+    #[test]
+    fn test_one_advertisement_one_event() {
+        let (_tx, active_rx) = watch::channel(vec![]);
+        let scanner = DeviceScanner::new(active_rx);
+        let mut rx = scanner.subscribe_rssi();
+        let props = PeripheralProperties {
+            local_name: Some("phone".into()),
+            rssi: Some(-60),
+            manufacturer_data: HashMap::from([(0x004C, vec![0x10, 0x00])]),
+            ..Default::default()
+        };
+
+        // 一次广播：DeviceUpdated 加上厂商数据、服务事件
+        scanner.apply("hci0", "dev", props.clone(), 1_000, true);
+        scanner.apply("hci0", "dev", props.clone(), 1_001, false);
+        scanner.apply("hci0", "dev", props.clone(), 1_002, false);
+        let event = rx.try_recv().unwrap();
+        assert_eq!((event.rssi, event.ts), (-60, 1_000));
+        assert!(rx.try_recv().is_err());
+
+        let device = scanner.get("dev").unwrap();
+        assert_eq!(device.rssi_history.len(), 1);
+        assert_eq!(device.last_seen, 1_002);
+        assert_eq!(device.sources["hci0"], (1_000, -60));
+    }
+
+    #[test]
+    fn test_device_key_roundtrip() {
+        let id = "hci0/dev_AA_BB";
+        assert_eq!(decode_device_key(&encode_device_key(id)).as_deref(), Some(id));
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeLink(Arc<std::sync::atomic::AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl Link for FakeLink {
+        async fn close(&self) -> Result<(), btleplug::Error> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    impl FakeLink {
+        fn closed(&self) -> usize {
+            self.0.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stalled_peer_is_disconnected() {
+        let link = FakeLink::default();
+        // 对端不应答，操作一直挂起
+        let stalled = std::future::pending::<Result<Vec<u8>, BleError>>();
+        let res = with_owned_link(Some(link.clone()), "dev", Duration::from_secs(3), stalled).await;
+        assert!(matches!(res, Err(BleError::GattTimeout { .. })));
+        assert_eq!(link.closed(), 1);
+
+        // 调用方先放弃等待时，连接在后台断开
+        let stalled = std::future::pending::<Result<Vec<u8>, BleError>>();
+        let op = with_owned_link(Some(link.clone()), "dev", Duration::from_secs(10), stalled);
+        assert!(tokio::time::timeout(Duration::from_secs(1), op)
+            .await
+            .is_err());
+        tokio::task::yield_now().await;
+        assert_eq!(link.closed(), 2);
+
+        // 已有的连接不属于本方，不断开
+        let res = with_owned_link(None::<FakeLink>, "dev", Duration::from_secs(3), async {
+            Ok(vec![1u8])
+        })
+        .await;
+        assert_eq!(res.unwrap(), vec![1]);
+        assert_eq!(link.closed(), 2);
+    }
+}
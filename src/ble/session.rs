@@ -0,0 +1,329 @@
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter},
+    sync::mpsc,
+};
+use tracing::warn;
+
+use crate::{
+    domain::{
+        entity::rssi_event::{now_millis, RssiEvent},
+        value_objects::ModelResult,
+    },
+    dto::detection::AlgoConfig,
+    errors::{AppError, AppResult},
+};
+
+use super::{
+    baseline::Thresholds,
+    presence_detector::PresenceDetector,
+    presence_state::{PresenceMachine, PresenceTiming, PresenceTransition},
+};
+
+/// 运行时在场状态机的定时检查间隔
+const PRESENCE_TICK_MS: i64 = 1000;
+
+/// 会话文件（JSONL）中的一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEntry {
+    /// 目标设备的原始广播
+    Advertisement(RssiEvent),
+    /// 送入模型的窗口
+    Window { ts: i64, data: Vec<f32> },
+    /// 模型预测结果（`ModelResult` 编码）
+    Prediction { ts: i64, result: i32 },
+    /// 锁屏/解锁决策
+    Lock { ts: i64, locked: bool, reason: String },
+}
+
+impl SessionEntry {
+    pub fn ts(&self) -> i64 {
+        match self {
+            Self::Advertisement(e) => e.ts,
+            Self::Window { ts, .. } | Self::Prediction { ts, .. } | Self::Lock { ts, .. } => *ts,
+        }
+    }
+}
+
+/// 会话录制器，可克隆后在各处记录
+#[derive(Clone)]
+pub struct SessionRecorder {
+    tx: mpsc::Sender<SessionEntry>,
+}
+
+impl SessionRecorder {
+    pub async fn create(path: impl AsRef<Path>) -> AppResult<Self> {
+        let file = File::create(path)
+            .await
+            .map_err(|e| AppError::IoError { source: e })?;
+        let (tx, mut rx) = mpsc::channel::<SessionEntry>(1024);
+
+        tokio::spawn(async move {
+            let mut writer = BufWriter::new(file);
+            while let Some(entry) = rx.recv().await {
+                let line = match serde_json::to_string(&entry) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        warn!("Failed to encode session entry: {}", e);
+                        continue;
+                    }
+                };
+                let res = async {
+                    writer.write_all(line.as_bytes()).await?;
+                    writer.write_all(b"\n").await?;
+                    // 没有积压时落盘，避免崩溃时丢失录制
+                    if rx.is_empty() {
+                        writer.flush().await?;
+                    }
+                    Ok::<_, std::io::Error>(())
+                }
+                .await;
+                if let Err(e) = res {
+                    warn!("Failed to write session entry: {}", e);
+                    break;
+                }
+            }
+            let _ = writer.flush().await;
+        });
+
+        Ok(Self { tx })
+    }
+
+    pub fn record(&self, entry: SessionEntry) {
+        if self.tx.try_send(entry).is_err() {
+            warn!("Session recorder is lagging, entry dropped");
+        }
+    }
+
+    pub fn window(&self, data: Vec<f32>) {
+        self.record(SessionEntry::Window {
+            ts: now_millis(),
+            data,
+        });
+    }
+
+    pub fn prediction(&self, result: ModelResult) {
+        self.record(SessionEntry::Prediction {
+            ts: now_millis(),
+            result: result.into(),
+        });
+    }
+
+    pub fn lock(&self, locked: bool, reason: impl Into<String>) {
+        self.record(SessionEntry::Lock {
+            ts: now_millis(),
+            locked,
+            reason: reason.into(),
+        });
+    }
+
+    /// 供 `PresenceDetector::add_raw_sink` 使用的广播接收端
+    pub fn raw_sink(&self) -> mpsc::Sender<RssiEvent> {
+        let (tx, mut rx) = mpsc::channel::<RssiEvent>(256);
+        let recorder = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                recorder.record(SessionEntry::Advertisement(event));
+            }
+        });
+        tx
+    }
+}
+
+/// 回放速度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// 按录制时的时间间隔
+    RealTime,
+    /// 时间间隔缩短为 1/n
+    Accelerated(f32),
+    /// 不等待，尽快回放
+    Instant,
+}
+
+impl ReplaySpeed {
+    fn delay(&self, delta_ms: i64) -> Option<Duration> {
+        let delta = Duration::from_millis(delta_ms.max(0) as u64);
+        match self {
+            Self::RealTime => Some(delta),
+            Self::Accelerated(n) if *n > 0.0 => Some(delta.div_f32(*n)),
+            Self::Accelerated(_) | Self::Instant => None,
+        }
+    }
+}
+
+/// 读取会话文件并驱动 `PresenceDetector` 重新运行
+pub struct SessionReplayer {
+    entries: Vec<SessionEntry>,
+}
+
+impl SessionReplayer {
+    pub async fn load(path: impl AsRef<Path>) -> AppResult<Self> {
+        let file = File::open(path)
+            .await
+            .map_err(|e| AppError::IoError { source: e })?;
+        let mut lines = BufReader::new(file).lines();
+        let mut entries = Vec::new();
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| AppError::IoError { source: e })?
+        {
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(
+                serde_json::from_str(&line).map_err(|e| AppError::InvalidData { source: e })?,
+            );
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.entries
+    }
+
+    /// 录制时的预测序列，用于与回放结果比对
+    pub fn predictions(&self) -> Vec<ModelResult> {
+        self.entries
+            .iter()
+            .filter_map(|e| match e {
+                SessionEntry::Prediction { result, .. } => Some(ModelResult::from(*result)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 按录制时间驱动在场状态机，与运行时一样每秒检查一次超时，返回所有状态转换。
+    /// 会话中没有录制学习到的阈值，按 `config.threshold` 判断；录制结束后再检查到超时为止
+    pub fn replay_presence(&self, config: &AlgoConfig) -> Vec<PresenceTransition> {
+        let thresholds = Thresholds::fixed(config.threshold);
+        let timing = PresenceTiming::from(config);
+        let mut ads = self.entries.iter().filter_map(|e| match e {
+            SessionEntry::Advertisement(event) => Some(event),
+            _ => None,
+        });
+        let Some(first) = ads.next() else {
+            return Vec::new();
+        };
+        let mut machine = PresenceMachine::new(timing, first.ts);
+        let mut transitions: Vec<_> = machine
+            .on_rssi(first.ts, first.rssi as f32, &thresholds)
+            .into_iter()
+            .collect();
+        let mut tick = first.ts;
+        let mut last_ts = first.ts;
+        for event in ads {
+            while tick + PRESENCE_TICK_MS <= event.ts {
+                tick += PRESENCE_TICK_MS;
+                transitions.extend(machine.on_tick(tick, &thresholds));
+            }
+            transitions.extend(machine.on_rssi(event.ts, event.rssi as f32, &thresholds));
+            last_ts = event.ts;
+        }
+        let end = last_ts + timing.timeout.as_millis() as i64;
+        while tick + PRESENCE_TICK_MS <= end {
+            tick += PRESENCE_TICK_MS;
+            transitions.extend(machine.on_tick(tick, &thresholds));
+        }
+        transitions
+    }
+
+    /// 按录制顺序注入广播，完成后关闭检测器
+    pub async fn replay(&self, detector: &PresenceDetector, speed: ReplaySpeed) -> AppResult<()> {
+        let mut last_ts = None;
+        for entry in &self.entries {
+            let SessionEntry::Advertisement(event) = entry else {
+                continue;
+            };
+            if let Some(delay) = last_ts.and_then(|t| speed.delay(event.ts - t)) {
+                tokio::time::sleep(delay).await;
+            }
+            last_ts = Some(event.ts);
+            detector.feed(event.clone()).await?;
+        }
+        detector.shutdown().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::presence_state::PresenceState;
+
+    #[test]
+    fn test_entry_json_round_trip() {
+        let entries = vec![
+            SessionEntry::Advertisement(RssiEvent::new("fp".into(), "iPhone".into(), -60, 1)),
+            SessionEntry::Window { ts: 2, data: vec![-60.0; 9] },
+            SessionEntry::Prediction { ts: 3, result: 2 },
+            SessionEntry::Lock { ts: 4, locked: true, reason: "lost".into() },
+        ];
+        for e in entries {
+            let line = serde_json::to_string(&e).unwrap();
+            assert_eq!(serde_json::from_str::<SessionEntry>(&line).unwrap(), e);
+        }
+    }
+
+    #[test]
+    fn test_replay_presence() {
+        let ad = |rssi, ts| {
+            SessionEntry::Advertisement(RssiEvent::new("fp".into(), "iPhone".into(), rssi, ts))
+        };
+        let replayer = SessionReplayer {
+            entries: vec![ad(-60, 0), ad(-60, 1500), ad(-90, 3000)],
+        };
+        let config = AlgoConfig::default();
+        assert_eq!((config.leave_dwell_secs, config.timeout_secs), (5, 15));
+        let transitions = replayer.replay_presence(&config);
+        let states: Vec<_> = transitions.iter().map(|t| (t.ts, t.to)).collect();
+        // 最后一次有效读数在 1.5 秒，5 秒后开始离开、15 秒后判定离开，按整秒检查
+        assert_eq!(
+            states,
+            vec![
+                (0, PresenceState::Present),
+                (7000, PresenceState::Leaving),
+                (17_000, PresenceState::Absent),
+            ]
+        );
+        assert!(transitions[2].is_departure());
+        assert_eq!(replayer.replay_presence(&config), transitions);
+    }
+
+    #[tokio::test]
+    async fn test_replay_is_deterministic() {
+        let replayer = SessionReplayer {
+            entries: (0..30)
+                .map(|i| {
+                    // 5ms 间隔，节流后只保留一半
+                    SessionEntry::Advertisement(RssiEvent::new(
+                        "fp".into(),
+                        "iPhone".into(),
+                        -50 - i as i16,
+                        i * 5,
+                    ))
+                })
+                .collect(),
+        };
+
+        let mut runs = Vec::new();
+        for speed in [ReplaySpeed::Instant, ReplaySpeed::Accelerated(100.0)] {
+            let (tx, mut rx) = mpsc::channel(128);
+            let detector = PresenceDetector::new(AlgoConfig::default(), tx).await.unwrap();
+            replayer.replay(&detector, speed).await.unwrap();
+            drop(detector);
+            let mut windows = Vec::new();
+            while let Some(w) = rx.recv().await {
+                windows.push(w);
+            }
+            runs.push(windows);
+        }
+        assert_eq!(runs[0].len(), 15);
+        assert_eq!(runs[0], runs[1]);
+    }
+}
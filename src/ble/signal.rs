@@ -0,0 +1,215 @@
+//! 信号质量评估
+//!
+//! 以固定的 dBm 区间换算百分比，不同次扫描、不同设备之间可直接比较。
+
+use std::{collections::VecDeque, fmt::Display};
+
+use crate::dto::detection::SignalConfig;
+
+/// 广播中的发射功率是 0 米处的值，1 米处大约再衰减 41 dB
+const TX_POWER_TO_1M_DB: f32 = 41.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalQuality {
+    Excellent,
+    Good,
+    Fair,
+    Weak,
+    #[default]
+    Lost,
+}
+
+impl SignalQuality {
+    pub fn from_percent(percent: u8) -> Self {
+        match percent {
+            75.. => Self::Excellent,
+            50..=74 => Self::Good,
+            25..=49 => Self::Fair,
+            1..=24 => Self::Weak,
+            0 => Self::Lost,
+        }
+    }
+
+    /// 头像背景渐变
+    pub fn gradient(&self) -> &'static str {
+        match self {
+            Self::Excellent => "from-green-400 to-green-600",
+            Self::Good => "from-blue-400 to-blue-600",
+            Self::Fair => "from-amber-400 to-amber-600",
+            Self::Weak => "from-red-400 to-red-600",
+            Self::Lost => "from-gray-300 to-gray-400",
+        }
+    }
+
+    /// 进度条颜色
+    pub fn progress_class(&self) -> &'static str {
+        match self {
+            Self::Excellent => "progress-success",
+            Self::Good => "progress-primary",
+            Self::Fair => "progress-warning",
+            Self::Weak | Self::Lost => "progress-error",
+        }
+    }
+}
+
+impl Display for SignalQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Excellent => "极好",
+            Self::Good => "良好",
+            Self::Fair => "一般",
+            Self::Weak => "较弱",
+            Self::Lost => "无信号",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Trend {
+    Rising,
+    Falling,
+    #[default]
+    Steady,
+}
+
+impl Trend {
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Self::Rising => "↑",
+            Self::Falling => "↓",
+            Self::Steady => "→",
+        }
+    }
+}
+
+/// 根据最近若干条 RSSI 得出的信号评估
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SignalReading {
+    /// 平滑后的 RSSI（dBm）
+    pub smoothed: f32,
+    /// 0~100，按 `SignalConfig` 的 dBm 区间换算
+    pub percent: u8,
+    pub quality: SignalQuality,
+    pub trend: Trend,
+    /// 估算距离（米），广播未携带发射功率时为空
+    pub distance: Option<f32>,
+}
+
+impl SignalReading {
+    /// `history` 为 (时间戳, RSSI)，old->new
+    pub fn assess(
+        history: &VecDeque<(i64, i16)>,
+        tx_power: Option<i16>,
+        config: &SignalConfig,
+    ) -> Option<Self> {
+        let &(last_ts, _) = history.back()?;
+        let alpha = config.smoothing.clamp(0.0, 1.0);
+
+        // 指数移动平均，同时记下趋势窗口起点处的平滑值
+        let mut smoothed = None::<f32>;
+        let mut baseline = None::<f32>;
+        for &(ts, rssi) in history {
+            let raw = rssi as f32;
+            let v = match smoothed {
+                Some(s) => s + alpha * (raw - s),
+                None => raw,
+            };
+            smoothed = Some(v);
+            if ts <= last_ts - config.trend_window_ms {
+                baseline = Some(v);
+            }
+        }
+        let smoothed = smoothed?;
+
+        let trend = match baseline.map(|b| smoothed - b) {
+            Some(d) if d >= config.trend_delta_db => Trend::Rising,
+            Some(d) if d <= -config.trend_delta_db => Trend::Falling,
+            _ => Trend::Steady,
+        };
+
+        let percent = percent(smoothed, config);
+        Some(Self {
+            smoothed,
+            percent,
+            quality: SignalQuality::from_percent(percent),
+            trend,
+            distance: tx_power.map(|tx| estimate_distance(smoothed, tx, config)),
+        })
+    }
+}
+
+fn percent(rssi: f32, config: &SignalConfig) -> u8 {
+    let range = (config.max_dbm - config.min_dbm).max(1.0);
+    ((rssi - config.min_dbm) / range * 100.0).clamp(0.0, 100.0).round() as u8
+}
+
+/// 对数距离路径损耗模型，仅作粗略参考
+fn estimate_distance(rssi: f32, tx_power: i16, config: &SignalConfig) -> f32 {
+    let measured_1m = tx_power as f32 - TX_POWER_TO_1M_DB;
+    let n = config.path_loss_exponent.max(0.1);
+    10f32.powf((measured_1m - rssi) / (10.0 * n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(points: &[(i64, i16)]) -> VecDeque<(i64, i16)> {
+        points.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_absolute_scale() {
+        let config = SignalConfig::default();
+        // 单个设备也有确定的百分比
+        let r = SignalReading::assess(&history(&[(0, -70)]), None, &config).unwrap();
+        assert_eq!(r.percent, 50);
+        assert_eq!(r.quality, SignalQuality::Good);
+        assert_eq!(r.trend, Trend::Steady);
+
+        let r = SignalReading::assess(&history(&[(0, -120)]), None, &config).unwrap();
+        assert_eq!(r.percent, 0);
+        assert_eq!(r.quality, SignalQuality::Lost);
+        let r = SignalReading::assess(&history(&[(0, -20)]), None, &config).unwrap();
+        assert_eq!(r.percent, 100);
+
+        // 区间配置错误时不出现 NaN
+        let bad = SignalConfig {
+            min_dbm: -60.0,
+            max_dbm: -60.0,
+            ..Default::default()
+        };
+        assert_eq!(percent(-60.0, &bad), 0);
+        assert_eq!(percent(-50.0, &bad), 100);
+        assert!(SignalReading::assess(&VecDeque::new(), None, &config).is_none());
+    }
+
+    #[test]
+    fn test_smoothing_and_trend() {
+        let config = SignalConfig::default();
+        let rising = history(&[(0, -80), (2_000, -80), (4_000, -70), (6_000, -60), (8_000, -60)]);
+        let r = SignalReading::assess(&rising, None, &config).unwrap();
+        assert!(r.smoothed > -80.0 && r.smoothed < -60.0);
+        assert_eq!(r.trend, Trend::Rising);
+
+        let falling = history(&[(0, -50), (3_000, -50), (6_000, -75), (7_000, -80)]);
+        let r = SignalReading::assess(&falling, None, &config).unwrap();
+        assert_eq!(r.trend, Trend::Falling);
+
+        // 单次抖动不影响趋势
+        let noisy = history(&[(0, -60), (3_000, -60), (6_000, -64), (7_000, -60)]);
+        let r = SignalReading::assess(&noisy, None, &config).unwrap();
+        assert_eq!(r.trend, Trend::Steady);
+    }
+
+    #[test]
+    fn test_distance_estimate() {
+        let config = SignalConfig::default();
+        // 发射功率 0 dBm，1 米处约 -41 dBm
+        let r = SignalReading::assess(&history(&[(0, -41)]), Some(0), &config).unwrap();
+        assert!((r.distance.unwrap() - 1.0).abs() < 1e-3);
+        let r = SignalReading::assess(&history(&[(0, -61)]), Some(0), &config).unwrap();
+        assert!((r.distance.unwrap() - 10.0).abs() < 1e-2);
+    }
+}
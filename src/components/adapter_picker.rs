@@ -0,0 +1,95 @@
+use std::{sync::Arc, time::Duration};
+
+use dioxus::prelude::*;
+
+use crate::{
+    ble::adapter::{AdapterInfo, AdapterPreference},
+    i18n::use_locale,
+    runtime::Runtime,
+    t,
+};
+
+/// 选择蓝牙适配器，只有一个适配器时不显示
+#[component]
+pub fn AdapterPicker() -> Element {
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let mut adapters = use_signal::<Vec<AdapterInfo>>(Vec::new);
+    let mut preference = use_signal(AdapterPreference::default);
+    let l = use_locale()();
+
+    // 适配器会热插拔，定时刷新列表
+    let mut refresh = use_future(move || async move {
+        loop {
+            let rt = runtime.peek().clone();
+            if let Some(rt) = rt {
+                let manager = rt.adapters();
+                adapters.set(manager.list());
+                preference.set(manager.preference());
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+
+    let mut apply = move |pref: AdapterPreference| {
+        if let Some(rt) = runtime.read().as_ref() {
+            rt.adapters().set_preference(pref);
+        }
+        refresh.restart();
+    };
+
+    if adapters.read().len() < 2 {
+        return rsx! {};
+    }
+    let pref = preference.read().clone();
+    let selected = pref.preferred.clone().unwrap_or_default();
+
+    rsx! {
+        div { class: "mb-6 p-4 rounded-xl border border-gray-200 bg-gray-50 flex flex-wrap items-center gap-3 text-sm",
+            span { class: "font-medium mr-auto", {t!(l, "adapter-picker-title")} }
+            select {
+                class: "px-2 py-1 rounded-lg border border-gray-200 bg-white",
+                disabled: pref.multi,
+                value: "{selected}",
+                onchange: {
+                    let pref = pref.clone();
+                    move |e: Event<FormData>| {
+                        let name = e.value();
+                        apply(AdapterPreference {
+                            preferred: Some(name).filter(|n| !n.is_empty()),
+                            ..pref.clone()
+                        });
+                    }
+                },
+                option { value: "", {t!(l, "adapter-picker-auto")} }
+                for a in adapters.read().iter() {
+                    option {
+                        key: "{a.name}",
+                        value: "{a.name}",
+                        selected: a.name == selected,
+                        if a.powered {
+                            "{a.name}"
+                        } else {
+                            {t!(l, "adapter-picker-off", name = a.name.as_str())}
+                        }
+                    }
+                }
+            }
+            label { class: "flex items-center gap-1",
+                input {
+                    r#type: "checkbox",
+                    checked: pref.multi,
+                    onchange: {
+                        let pref = pref.clone();
+                        move |e: Event<FormData>| {
+                            apply(AdapterPreference {
+                                multi: e.checked(),
+                                ..pref.clone()
+                            });
+                        }
+                    },
+                }
+                {t!(l, "adapter-picker-multi")}
+            }
+        }
+    }
+}
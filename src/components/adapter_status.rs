@@ -0,0 +1,26 @@
+use dioxus::prelude::*;
+
+use crate::{
+    ble::adapter::AdapterState,
+    i18n::{use_locale, Localize as _},
+    t,
+};
+
+/// 蓝牙不可用时的提示条，正常时不显示
+#[component]
+pub fn AdapterStatus() -> Element {
+    let state: Signal<AdapterState> = use_context();
+    let state = *state.read();
+    let l = use_locale()();
+    if state == AdapterState::Ready {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "mb-6 p-4 rounded-xl border border-red-200 bg-red-50 text-red-700 flex items-center gap-3",
+            span { class: "text-xl", "⚠️" }
+            span { class: "font-medium", {state.localize(l)} }
+            span { class: "text-sm text-red-500", {t!(l, "adapter-recover-hint")} }
+        }
+    }
+}
@@ -1,28 +1,66 @@
-use crate::{ble::service::get_all_device_list, dto::device::Device};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crate::{
+    ble::{
+        scanner::{encode_device_key, DeviceScanner, ScanUpdate, ScannedDevice},
+        service::to_device_list,
+    },
+    dto::detection::SignalConfig,
+    i18n::{use_locale, Localize as _},
+    routes::Route,
+    t,
+};
 use dioxus::prelude::*;
-use tracing::info;
+use tokio::sync::broadcast::error::RecvError;
 
 #[component]
 pub fn DeviceList() -> Element {
-    // 信号量用于更新设备列表
-    let devices = use_signal(Vec::<Device>::new);
-    use_future({
-        to_owned![devices];
-        move || async move {
-            loop {
-                let lst = get_all_device_list().await.unwrap();
-                info!("get device list: {:?}", lst);
-                devices.set(lst);
-                // tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    let scanner: Signal<Option<Arc<DeviceScanner>>> = use_context();
+    let signal_config: Signal<SignalConfig> = use_context();
+    let l = use_locale()();
+    // 扫描服务推送的设备表
+    let mut table = use_signal(HashMap::<String, ScannedDevice>::new);
+    use_future(move || async move {
+        // 等待扫描服务就绪
+        let scanner = loop {
+            if let Some(s) = scanner.peek().clone() {
+                break s;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+        let mut rx = scanner.subscribe();
+        let snapshot = |s: &DeviceScanner| {
+            s.snapshot()
+                .into_iter()
+                .map(|d| (d.id.clone(), d))
+                .collect::<HashMap<_, _>>()
+        };
+        table.set(snapshot(&scanner));
+        loop {
+            match rx.recv().await {
+                Ok(ScanUpdate::Upsert(d)) => {
+                    table.write().insert(d.id.clone(), d);
+                }
+                Ok(ScanUpdate::Removed(id)) => {
+                    table.write().remove(&id);
+                }
+                // 落后太多时重新取全量
+                Err(RecvError::Lagged(_)) => table.set(snapshot(&scanner)),
+                Err(RecvError::Closed) => break,
             }
         }
     });
 
+    let devices = {
+        let table = table.read();
+        to_device_list(&table.values().cloned().collect::<Vec<_>>(), &signal_config.read())
+    };
+
     rsx! {
         // --- 头部 ---
         div { class: "mb-8 flex justify-between items-center",
             div { class: "flex gap-3 items-center",
-                h1 { class: "text-2xl font-bold", "附近的蓝牙设备" }
+                h1 { class: "text-2xl font-bold", {t!(l, "device-list-title")} }
                 div { class: "text-blue-500  animate-pulse text-2xl font-bold", "{devices.len()}" }
             }
             // button { class: "btn btn-sm btn-primary btn-outline flex gap-2 items-center",
@@ -63,7 +101,7 @@ pub fn DeviceList() -> Element {
                                         div { 
                                             class: "avatar h-12 w-12 flex items-center justify-center",
                                             div {
-                                                class: "rounded-full text-2xl font-bold uppercase select-none flex items-center justify-center leading-none text-center bg-blue-500 text-white",
+                                                class: "rounded-full text-2xl font-bold uppercase select-none flex items-center justify-center leading-none text-center bg-gradient-to-br {d.quality.gradient()} text-white",
                                                 style: "height:3rem;width:3rem;line-height:1;display:flex;align-items:center;justify-content:center;",
                                                 "{initial}"
                                             }
@@ -71,8 +109,12 @@ pub fn DeviceList() -> Element {
                                         // 设备信息
                                         div { class: "flex-1 min-w-0",
                                             div { class: "flex gap-2 items-center",
-                                                span { class: "font-bold text-lg truncate", "{d.name}" }
-                                                div { class: "text-base text-2xl font-bold", "{d.device_type}" }
+                                                Link {
+                                                    class: "font-bold text-lg truncate hover:underline",
+                                                    to: Route::DeviceDetail { id: encode_device_key(&d.id) },
+                                                    "{d.name}"
+                                                }
+                                                div { class: "text-base text-2xl font-bold", title: "{d.kind}", "{d.kind.icon()}" }
                                             }
                                             div { class: "flex gap-2 items-center text-gray-500 mt-1 text-sm",
                                                 span { class: "flex gap-1 items-center",
@@ -89,11 +131,15 @@ pub fn DeviceList() -> Element {
                                                         d: "M5 12h14M12 5v14M18 8v8M6 8v8",
                                                     }
                                                 }
-                                                    "{d.rssi} dBm"
+                                                    "{d.smoothed_rssi:.0} dBm"
                                                 }
                                                 span { class: "opacity-60", "|" }
                                                 span { class: "flex gap-1 items-center text-base",
                                                     "{d.mac}" }
+                                                if let Some(m) = d.distance {
+                                                    span { class: "opacity-60", "|" }
+                                                    span { "≈{m:.1} m" }
+                                                }
                                             }
                                         }
                                         // 右侧状态/操作
@@ -112,7 +158,7 @@ pub fn DeviceList() -> Element {
                                                         d: "M13.19 8.688a4.5 4.5 0 011.242 7.244l-4.5 4.5a4.5 4.5 0 01-6.364-6.364l1.757-1.757m13.35-.622l1.757-1.757a4.5 4.5 0 00-6.364-6.364l-4.5 4.5a4.5 4.5 0 001.242 7.244",
                                                     }
                                                 }
-                                                "选择"
+                                                {t!(l, "device-list-select")}
                                             }
                                         }
                                     }
@@ -120,9 +166,19 @@ pub fn DeviceList() -> Element {
                                     div { class: "mt-4 grid grid-cols-2 gap-4 items-center",
                                         // --- 进度条区域 ---
                                         div { class: "flex flex-col gap-2",
-                                            span { class: "label-text text-xs text-gray-400 mb-1", "信号强度 {d.percent}%" }
+                                            span { class: "label-text text-xs text-gray-400 mb-1",
+                                                {
+                                                    t!(
+                                                        l,
+                                                        "device-list-signal",
+                                                        quality = d.quality.localize(l),
+                                                        percent = d.percent,
+                                                        trend = d.trend.arrow(),
+                                                    )
+                                                }
+                                            }
                                             progress {
-                                                class: "progress progress-primary w-full h-2",
+                                                class: "progress {d.quality.progress_class()} w-full h-2",
                                                 max: "100",
                                                 value: "{d.percent}",
                                             }
@@ -1,2 +1,6 @@
 pub mod device_list;
-pub mod menu;
\ No newline at end of file
+pub mod menu;
+pub mod rssi_chart;pub mod snooze_panel;
+pub mod adapter_status;
+pub mod adapter_picker;
+pub mod tray;
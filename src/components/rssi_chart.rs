@@ -0,0 +1,312 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+};
+
+use dioxus::prelude::*;
+
+use crate::{
+    domain::{
+        entity::rssi_event::{now_millis, RssiEvent},
+        value_objects::ModelResult,
+    },
+    i18n::{use_locale, Localize as _},
+    t,
+};
+
+/// 滤波系数（指数移动平均）
+const EMA_ALPHA: f32 = 0.3;
+/// 纵轴范围（dBm）
+const Y_MIN: f32 = -100.0;
+const Y_MAX: f32 = -30.0;
+const WIDTH: f32 = 800.0;
+const HEIGHT: f32 = 240.0;
+const PALETTE: [&str; 5] = ["#0891b2", "#7c3aed", "#db2777", "#65a30d", "#ea580c"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RssiPoint {
+    pub ts: i64,
+    pub raw: f32,
+    pub filtered: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceTrace {
+    pub name: String,
+    pub points: VecDeque<RssiPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PredictionMark {
+    pub ts: i64,
+    pub result: ModelResult,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockMark {
+    pub ts: i64,
+    pub locked: bool,
+}
+
+/// 最近一段时间的 RSSI、预测与锁屏记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct RssiHistory {
+    /// 保留时长（毫秒）
+    pub span_ms: i64,
+    pub devices: BTreeMap<String, DeviceTrace>,
+    pub predictions: VecDeque<PredictionMark>,
+    pub locks: VecDeque<LockMark>,
+}
+
+impl Default for RssiHistory {
+    fn default() -> Self {
+        Self::new(5 * 60 * 1000)
+    }
+}
+
+impl RssiHistory {
+    pub fn new(span_ms: i64) -> Self {
+        Self {
+            span_ms,
+            devices: BTreeMap::new(),
+            predictions: VecDeque::new(),
+            locks: VecDeque::new(),
+        }
+    }
+
+    pub fn push_rssi(&mut self, event: &RssiEvent) {
+        let trace = self.devices.entry(event.device.clone()).or_default();
+        trace.name = event.name.clone();
+        let raw = event.rssi as f32;
+        let filtered = match trace.points.back() {
+            Some(last) => last.filtered + EMA_ALPHA * (raw - last.filtered),
+            None => raw,
+        };
+        trace.points.push_back(RssiPoint {
+            ts: event.ts,
+            raw,
+            filtered,
+        });
+        self.trim(event.ts);
+    }
+
+    pub fn push_prediction(&mut self, ts: i64, result: ModelResult) {
+        self.predictions.push_back(PredictionMark { ts, result });
+        self.trim(ts);
+    }
+
+    pub fn push_lock(&mut self, ts: i64, locked: bool) {
+        self.locks.push_back(LockMark { ts, locked });
+        self.trim(ts);
+    }
+
+    fn trim(&mut self, now: i64) {
+        let start = now - self.span_ms;
+        for trace in self.devices.values_mut() {
+            while trace.points.front().is_some_and(|p| p.ts < start) {
+                trace.points.pop_front();
+            }
+        }
+        self.devices.retain(|_, t| !t.points.is_empty());
+        // 保留区间起点之前的最后一条预测，用于绘制第一段色带
+        while self.predictions.len() > 1 && self.predictions[1].ts < start {
+            self.predictions.pop_front();
+        }
+        while self.locks.front().is_some_and(|l| l.ts < start) {
+            self.locks.pop_front();
+        }
+    }
+}
+
+fn band_color(result: ModelResult) -> Option<&'static str> {
+    match result {
+        ModelResult::MovingCloser => Some("#0ea5e9"),
+        ModelResult::MovingAway => Some("#f59e42"),
+        ModelResult::Stationary => Some("#16a34a"),
+        ModelResult::Unknown | ModelResult::Unlabeled => None,
+    }
+}
+
+struct Scale {
+    start: i64,
+    span: i64,
+}
+
+impl Scale {
+    fn x(&self, ts: i64) -> f32 {
+        ((ts - self.start) as f32 / self.span.max(1) as f32 * WIDTH).clamp(0.0, WIDTH)
+    }
+
+    fn y(&self, dbm: f32) -> f32 {
+        let v = dbm.clamp(Y_MIN, Y_MAX);
+        (Y_MAX - v) / (Y_MAX - Y_MIN) * HEIGHT
+    }
+
+    fn path(&self, points: impl Iterator<Item = (i64, f32)>) -> String {
+        let mut d = String::new();
+        for (i, (ts, v)) in points.enumerate() {
+            let cmd = if i == 0 { 'M' } else { 'L' };
+            let _ = write!(d, "{}{:.1},{:.1} ", cmd, self.x(ts), self.y(v));
+        }
+        d
+    }
+}
+
+#[component]
+pub fn RssiChart(
+    history: ReadOnlySignal<RssiHistory>,
+    threshold: f32,
+    /// 学习到的离开阈值，未学习时不画
+    absent: Option<f32>,
+) -> Element {
+    let l = use_locale()();
+    let history = history.read();
+    let now = now_millis();
+    let scale = Scale {
+        start: now - history.span_ms,
+        span: history.span_ms,
+    };
+
+    // 预测色带：每条预测持续到下一条预测
+    let bands: Vec<(f32, f32, &str, String)> = history
+        .predictions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            let color = band_color(p.result)?;
+            let end = history.predictions.get(i + 1).map_or(now, |n| n.ts);
+            let (x0, x1) = (scale.x(p.ts), scale.x(end));
+            (x1 > x0).then(|| (x0, x1 - x0, color, p.result.to_string()))
+        })
+        .collect();
+
+    let traces: Vec<(String, &str, String, String)> = history
+        .devices
+        .iter()
+        .enumerate()
+        .map(|(i, (_, trace))| {
+            let color = PALETTE[i % PALETTE.len()];
+            let raw = scale.path(trace.points.iter().map(|p| (p.ts, p.raw)));
+            let filtered = scale.path(trace.points.iter().map(|p| (p.ts, p.filtered)));
+            (trace.name.clone(), color, raw, filtered)
+        })
+        .collect();
+
+    let threshold_y = scale.y(threshold);
+    let absent_y = absent.map(|v| (scale.y(v), v));
+    let grid: Vec<(f32, i32)> = (-90..=-40)
+        .step_by(10)
+        .map(|v| (scale.y(v as f32), v))
+        .collect();
+
+    rsx! {
+        div { class: "w-full",
+            svg {
+                class: "w-full h-auto bg-white rounded-xl border border-gray-100",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                preserve_aspect_ratio: "none",
+                xmlns: "http://www.w3.org/2000/svg",
+                // 预测色带
+                for (x, w, color, label) in bands.iter() {
+                    rect {
+                        x: "{x}",
+                        y: "0",
+                        width: "{w}",
+                        height: "{HEIGHT}",
+                        fill: "{color}",
+                        fill_opacity: "0.12",
+                        title { "{label}" }
+                    }
+                }
+                // 网格
+                for (y, v) in grid.iter() {
+                    line { x1: "0", x2: "{WIDTH}", y1: "{y}", y2: "{y}", stroke: "#e5e7eb", stroke_width: "1" }
+                    text { x: "4", y: "{y - 2.0}", font_size: "10", fill: "#9ca3af", "{v}" }
+                }
+                // 锁屏阈值
+                line {
+                    x1: "0",
+                    x2: "{WIDTH}",
+                    y1: "{threshold_y}",
+                    y2: "{threshold_y}",
+                    stroke: "#dc2626",
+                    stroke_width: "1.5",
+                    stroke_dasharray: "6 4",
+                }
+                text { x: "{WIDTH - 4.0}", y: "{threshold_y - 4.0}", font_size: "10", fill: "#dc2626", text_anchor: "end", {t!(l, "chart-threshold", threshold = threshold)} }
+                if let Some((y, v)) = absent_y {
+                    line {
+                        x1: "0",
+                        x2: "{WIDTH}",
+                        y1: "{y}",
+                        y2: "{y}",
+                        stroke: "#f59e0b",
+                        stroke_width: "1",
+                        stroke_dasharray: "2 4",
+                    }
+                    text { x: "{WIDTH - 4.0}", y: "{y + 12.0}", font_size: "10", fill: "#f59e0b", text_anchor: "end", {t!(l, "chart-absent", threshold = v)} }
+                }
+                // RSSI 曲线
+                for (name, color, raw, filtered) in traces.iter() {
+                    g { key: "{name}",
+                        path { d: "{raw}", fill: "none", stroke: "{color}", stroke_opacity: "0.35", stroke_width: "1" }
+                        path { d: "{filtered}", fill: "none", stroke: "{color}", stroke_width: "2.5" }
+                    }
+                }
+                // 锁屏事件
+                for lock in history.locks.iter() {
+                    line {
+                        x1: "{scale.x(lock.ts)}",
+                        x2: "{scale.x(lock.ts)}",
+                        y1: "0",
+                        y2: "{HEIGHT}",
+                        stroke: if lock.locked { "#dc2626" } else { "#16a34a" },
+                        stroke_width: "2",
+                    }
+                }
+            }
+            // 图例
+            div { class: "flex flex-wrap gap-4 mt-2 text-xs text-gray-500",
+                for (name, color, _, _) in traces.iter() {
+                    span { class: "flex items-center gap-1",
+                        span { class: "inline-block w-3 h-1", style: "background:{color}" }
+                        "{name}"
+                    }
+                }
+                for r in [ModelResult::MovingCloser, ModelResult::MovingAway, ModelResult::Stationary] {
+                    span { class: "flex items-center gap-1",
+                        span { class: "inline-block w-3 h-3 rounded-sm opacity-40", style: "background:{band_color(r).unwrap_or_default()}" }
+                        {r.localize(l)}
+                    }
+                }
+                span { class: "flex items-center gap-1",
+                    span { class: "inline-block w-1 h-3 bg-red-600" }
+                    {t!(l, "chart-lock")}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_filters_and_trims() {
+        let mut h = RssiHistory::new(1000);
+        h.push_rssi(&RssiEvent::new("fp".into(), "phone".into(), -60, 0));
+        h.push_rssi(&RssiEvent::new("fp".into(), "phone".into(), -70, 500));
+        let trace = &h.devices["fp"];
+        assert_eq!(trace.points[1].raw, -70.0);
+        assert!((trace.points[1].filtered - -63.0).abs() < 1e-4);
+
+        h.push_prediction(100, ModelResult::Stationary);
+        h.push_prediction(200, ModelResult::MovingAway);
+        h.push_rssi(&RssiEvent::new("fp".into(), "phone".into(), -70, 1300));
+        // 早于 300ms 的点被移除，保留跨越起点的一条预测
+        assert_eq!(h.devices["fp"].points.len(), 2);
+        assert_eq!(h.predictions.len(), 1);
+        assert_eq!(h.predictions[0].result, ModelResult::MovingAway);
+    }
+}
@@ -0,0 +1,73 @@
+use std::{sync::Arc, time::Duration};
+
+use dioxus::prelude::*;
+
+use crate::{
+    i18n::{use_locale, Localize as _},
+    runtime::Runtime,
+    snooze::{Snooze, SnoozeReason},
+    t,
+};
+
+/// 暂停自动锁屏的状态与快捷操作
+#[component]
+pub fn SnoozePanel() -> Element {
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let mut reason = use_signal::<Option<SnoozeReason>>(|| None);
+    let l = use_locale()();
+
+    // 免打扰时段和应用检测随时间变化，定时刷新
+    let mut refresh = use_future(move || async move {
+        loop {
+            let rt = runtime.peek().clone();
+            if let Some(rt) = rt {
+                reason.set(rt.snooze_reason().await);
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+
+    let mut set = move |snooze: Option<Snooze>| {
+        if let Some(rt) = runtime.read().as_ref() {
+            rt.set_snooze(snooze);
+        }
+        refresh.restart();
+    };
+
+    let manual = matches!(*reason.read(), Some(SnoozeReason::Snoozed { .. }));
+    let (text, style) = match &*reason.read() {
+        Some(r) => (r.localize(l), "bg-amber-50 border-amber-200 text-amber-800"),
+        None => (
+            t!(l, "snooze-disabled"),
+            "bg-teal-50/60 border-teal-100 text-teal-800",
+        ),
+    };
+    let button = "px-3 py-1 rounded-lg text-sm bg-white border border-gray-200 hover:bg-gray-50 disabled:opacity-50";
+
+    rsx! {
+        div { class: "mb-6 p-4 rounded-xl border flex flex-wrap items-center gap-3 {style}",
+            span { class: "font-medium mr-auto", "{text}" }
+            button {
+                class: button,
+                onclick: move |_| set(Some(Snooze::for_minutes(15))),
+                {t!(l, "snooze-15-minutes")}
+            }
+            button {
+                class: button,
+                onclick: move |_| set(Some(Snooze::for_minutes(60))),
+                {t!(l, "snooze-1-hour")}
+            }
+            button {
+                class: button,
+                onclick: move |_| set(Some(Snooze::UntilUnlock)),
+                {t!(l, "snooze-until-unlock-button")}
+            }
+            button {
+                class: button,
+                disabled: !manual,
+                onclick: move |_| set(None),
+                {t!(l, "snooze-cancel")}
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{cell::Cell, rc::Rc, sync::Arc};
+
+use dioxus::{
+    desktop::{
+        trayicon::{
+            init_tray_icon,
+            menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+            DioxusTray,
+        },
+        use_tray_menu_event_handler, window,
+    },
+    prelude::*,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::{
+    ble::presence_state::PresenceState,
+    i18n::{Locale, Localize as _},
+    runtime::{Runtime, RuntimeEvent},
+    snooze::Snooze,
+    t,
+};
+
+const MENU_SNOOZE_15: &str = "snooze-15";
+const MENU_SNOOZE_60: &str = "snooze-60";
+const MENU_SNOOZE_UNLOCK: &str = "snooze-unlock";
+const MENU_RESUME: &str = "resume";
+const MENU_SHOW: &str = "show";
+const MENU_QUIT: &str = "quit";
+
+struct Tray {
+    icon: DioxusTray,
+    status: MenuItem,
+    /// 随界面语言切换的菜单项及其文本 key
+    items: Vec<(MenuItem, &'static str)>,
+    /// 最近的在场状态与 RSSI，切换语言时据此重写状态行
+    last: Cell<(PresenceState, Option<i16>)>,
+}
+
+impl Tray {
+    /// 按 `l` 重写所有菜单文本
+    fn relabel(&self, l: Locale) {
+        for (item, key) in &self.items {
+            item.set_text(t!(l, key));
+        }
+        self.refresh_status(l);
+    }
+
+    fn refresh_status(&self, l: Locale) {
+        let (state, rssi) = self.last.get();
+        let state = state.localize(l);
+        let text = match rssi {
+            Some(rssi) => t!(l, "tray-status", state = state, rssi = rssi),
+            None => state,
+        };
+        self.status.set_text(&text);
+        if let Err(e) = self.icon.set_tooltip(Some(&text)) {
+            warn!("Failed to update tray tooltip: {}", e);
+        }
+    }
+}
+
+/// 创建托盘图标：显示目标状态与最近 RSSI，菜单提供暂停、显示窗口和退出
+pub fn use_tray(runtime: Signal<Option<Arc<Runtime>>>, locale: Signal<Locale>) {
+    let tray = use_hook(|| {
+        let l = *locale.peek();
+        let status = MenuItem::with_id("status", PresenceState::Unknown.localize(l), false, None);
+        let item =
+            |id: &str, key: &'static str| (MenuItem::with_id(id, t!(l, key), true, None), key);
+        let snoozes = [
+            item(MENU_SNOOZE_15, "tray-snooze-15-minutes"),
+            item(MENU_SNOOZE_60, "tray-snooze-1-hour"),
+            item(MENU_SNOOZE_UNLOCK, "tray-snooze-until-unlock"),
+            item(MENU_RESUME, "tray-resume"),
+        ];
+        let window_items = [item(MENU_SHOW, "tray-show"), item(MENU_QUIT, "tray-quit")];
+        let menu = Menu::new();
+        let res = menu
+            .append(&status)
+            .and_then(|_| menu.append(&PredefinedMenuItem::separator()))
+            .and_then(|_| snoozes.iter().try_for_each(|(item, _)| menu.append(item)))
+            .and_then(|_| menu.append(&PredefinedMenuItem::separator()))
+            .and_then(|_| {
+                window_items
+                    .iter()
+                    .try_for_each(|(item, _)| menu.append(item))
+            });
+        if let Err(e) = res {
+            warn!("Failed to build tray menu: {}", e);
+        }
+        Rc::new(Tray {
+            icon: init_tray_icon(menu, None),
+            status,
+            items: snoozes.into_iter().chain(window_items).collect(),
+            last: Cell::new((PresenceState::Unknown, None)),
+        })
+    });
+
+    // 切换界面语言后重写菜单
+    use_effect({
+        let tray = tray.clone();
+        move || tray.relabel(locale())
+    });
+
+    use_tray_menu_event_handler(move |event: &MenuEvent| {
+        let snooze = match event.id.0.as_str() {
+            MENU_SNOOZE_15 => Some(Snooze::for_minutes(15)),
+            MENU_SNOOZE_60 => Some(Snooze::for_minutes(60)),
+            MENU_SNOOZE_UNLOCK => Some(Snooze::UntilUnlock),
+            MENU_RESUME => None,
+            MENU_SHOW => {
+                window().set_visible(true);
+                window().set_focus();
+                return;
+            }
+            // 走窗口关闭流程，由关闭事件停止运行时
+            MENU_QUIT => {
+                window().close();
+                return;
+            }
+            _ => return,
+        };
+        if let Some(rt) = runtime.peek().as_ref() {
+            rt.set_snooze(snooze);
+        }
+    });
+
+    // 跟随在场状态机与 RSSI 广播刷新状态与提示
+    use_future(move || {
+        let tray = tray.clone();
+        async move {
+            let rt = loop {
+                if let Some(rt) = runtime.peek().clone() {
+                    break rt;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            };
+            let mut rx = rt.subscribe();
+            let mut presence = rt.subscribe_presence();
+            presence.mark_changed();
+            let mut last_rssi = None;
+            loop {
+                tokio::select! {
+                    event = rx.recv() => match event {
+                        Ok(RuntimeEvent::Rssi(event)) => last_rssi = Some(event.rssi),
+                        Ok(RuntimeEvent::TargetChanged { .. }) => last_rssi = None,
+                        Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                    changed = presence.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                }
+                tray.last.set((*presence.borrow_and_update(), last_rssi));
+                tray.refresh_status(*locale.peek());
+            }
+        }
+    });
+}
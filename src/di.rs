@@ -1,10 +1,19 @@
 use shaku::module;
 
-use crate::{application::sample_service::SampleService, infrastructure::{sample_repo::SampleRepo, DbProvider}};
+use crate::{
+    application::{
+        rssi_event_service::RssiEventService, sample_service::SampleService,
+        stats_service::StatsService,
+    },
+    infrastructure::{
+        rssi_event_repo::RssiEventRepo, sample_repo::SampleRepo,
+        trusted_device_repo::TrustedDeviceRepo, DbProvider,
+    },
+};
 
 module!{
     pub Deps{
-        components = [DbProvider,SampleService,SampleRepo],
+        components = [DbProvider,SampleService,SampleRepo,StatsService,RssiEventRepo,RssiEventService,TrustedDeviceRepo],
         providers = []
     }
 }
\ No newline at end of file
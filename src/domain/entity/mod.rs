@@ -1,2 +1,4 @@
 
-pub mod sample;
\ No newline at end of file
+pub mod rssi_event;
+pub mod sample;
+pub mod trusted_device;
\ No newline at end of file
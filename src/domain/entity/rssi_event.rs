@@ -0,0 +1,45 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// 单条原始广播 RSSI 记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RssiEvent {
+    /// 设备指纹
+    pub device: String,
+    pub name: String,
+    pub rssi: i16,
+    /// unix 毫秒时间戳
+    pub ts: i64,
+}
+
+impl RssiEvent {
+    pub fn new(device: String, name: String, rssi: i16, ts: i64) -> Self {
+        Self {
+            device,
+            name,
+            rssi,
+            ts,
+        }
+    }
+
+    /// 以当前时间创建
+    pub fn now(device: String, name: String, rssi: i16) -> Self {
+        Self::new(device, name, rssi, now_millis())
+    }
+}
+
+/// 某设备在一天中某小时（本地时间）出现某 RSSI 值的次数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RssiBucket {
+    pub hour: u32,
+    pub rssi: i16,
+    pub count: u64,
+}
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
@@ -4,6 +4,7 @@ use crate::domain::value_objects::{ModelResult, SampleData, SampleID};
 pub struct SampleAggregate{
     pub id:SampleID,
     pub device:String,
+    pub model:String,
     pub data:SampleData,
     pub predict:ModelResult,
     pub actual:ModelResult,
@@ -12,10 +13,11 @@ pub struct SampleAggregate{
 
 impl SampleAggregate{
     
-    pub fn new (id:SampleID, device:String, data:SampleData, predict:ModelResult, actual:ModelResult, created_at:String)->Self{
+    pub fn new (id:SampleID, device:String, model:String, data:SampleData, predict:ModelResult, actual:ModelResult, created_at:String)->Self{
         Self{
             id,
             device,
+            model,
             data,
             predict,
             actual,
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// 受信任的目标设备，以名称指纹标识
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustedDevice {
+    pub fingerprint: String,
+    pub name: String,
+    pub created_at: String,
+    /// 添加时扫描到的地址（没有地址的平台为外设 ID），靠近解锁只验证这台设备；为空表示未绑定
+    pub address: String,
+}
+
+impl TrustedDevice {
+    pub fn new(fingerprint: String, name: String) -> Self {
+        Self {
+            fingerprint,
+            name,
+            created_at: String::new(),
+            address: String::new(),
+        }
+    }
+
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = address;
+        self
+    }
+}
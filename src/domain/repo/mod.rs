@@ -1 +1,3 @@
-pub mod sample_repo;
\ No newline at end of file
+pub mod rssi_event_repo;
+pub mod sample_repo;
+pub mod trusted_device_repo;
\ No newline at end of file
@@ -0,0 +1,21 @@
+use shaku::Interface;
+
+use crate::{
+    domain::entity::rssi_event::{RssiBucket, RssiEvent},
+    errors::AppResult,
+};
+
+#[async_trait::async_trait]
+pub trait IRssiEventRepo: Interface {
+    async fn insert_batch(&self, events: Vec<RssiEvent>) -> AppResult<()>;
+    /// 按时间顺序读取 `[from, to]` 区间内某设备的原始记录
+    async fn load_range(&self, device: &str, from: i64, to: i64) -> AppResult<Vec<RssiEvent>>;
+    /// 统计 `from` 之后某设备在场时段内每小时（本地时间）各 RSSI 值的出现次数
+    async fn rssi_histogram(&self, device: &str, from: i64) -> AppResult<Vec<RssiBucket>>;
+    /// 删除早于 `before` 的记录与在场时段，并只保留最新的 `max_rows` 条记录，返回删除的记录数
+    async fn purge(&self, before: i64, max_rows: u64) -> AppResult<u64>;
+    /// 设备从 `ts` 起处于在场状态，先结束它未结束的时段
+    async fn begin_present(&self, device: &str, ts: i64) -> AppResult<()>;
+    /// 在 `ts` 结束未结束的在场时段，`device` 为空时结束所有设备的
+    async fn end_present(&self, device: Option<&str>, ts: i64) -> AppResult<()>;
+}
@@ -0,0 +1,14 @@
+use shaku::Interface;
+
+use crate::{domain::entity::trusted_device::TrustedDevice, errors::AppResult};
+
+#[async_trait::async_trait]
+pub trait ITrustedDeviceRepo: Interface {
+    /// 按添加顺序返回
+    async fn list(&self) -> AppResult<Vec<TrustedDevice>>;
+    async fn get(&self, fingerprint: &str) -> AppResult<Option<TrustedDevice>>;
+    /// 已存在时更新名称，地址不为空时一并更新
+    async fn add(&self, device: TrustedDevice) -> AppResult<()>;
+    /// 返回是否确实删除了记录
+    async fn remove(&self, fingerprint: &str) -> AppResult<bool>;
+}
@@ -0,0 +1,91 @@
+use super::ModelResult;
+
+/// 混淆矩阵，行为人工标注（actual），列为模型预测（predict）
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConfusionMatrix {
+    counts: [[u32; 4]; 4],
+}
+
+impl ConfusionMatrix {
+    /// 矩阵行列顺序
+    pub const CLASSES: [ModelResult; 4] = [
+        ModelResult::Stationary,
+        ModelResult::MovingAway,
+        ModelResult::MovingCloser,
+        ModelResult::Unknown,
+    ];
+
+    fn index(r: ModelResult) -> Option<usize> {
+        Self::CLASSES.iter().position(|c| *c == r)
+    }
+
+    pub fn record(&mut self, actual: ModelResult, predict: ModelResult) {
+        if let (Some(a), Some(p)) = (Self::index(actual), Self::index(predict)) {
+            self.counts[a][p] += 1;
+        }
+    }
+
+    pub fn count(&self, actual: ModelResult, predict: ModelResult) -> u32 {
+        match (Self::index(actual), Self::index(predict)) {
+            (Some(a), Some(p)) => self.counts[a][p],
+            _ => 0,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().flatten().sum()
+    }
+
+    pub fn correct(&self) -> u32 {
+        (0..Self::CLASSES.len()).map(|i| self.counts[i][i]).sum()
+    }
+
+    /// 该类别的人工标注数量
+    pub fn support(&self, class: ModelResult) -> u32 {
+        Self::index(class).map_or(0, |i| self.counts[i].iter().sum())
+    }
+
+    /// 该类别的预测数量
+    pub fn predicted(&self, class: ModelResult) -> u32 {
+        Self::index(class).map_or(0, |i| self.counts.iter().map(|row| row[i]).sum())
+    }
+
+    pub fn accuracy(&self) -> Option<f32> {
+        ratio(self.correct(), self.total())
+    }
+
+    pub fn precision(&self, class: ModelResult) -> Option<f32> {
+        ratio(self.count(class, class), self.predicted(class))
+    }
+
+    pub fn recall(&self, class: ModelResult) -> Option<f32> {
+        ratio(self.count(class, class), self.support(class))
+    }
+}
+
+fn ratio(num: u32, den: u32) -> Option<f32> {
+    (den > 0).then(|| num as f32 / den as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precision_recall() {
+        let mut m = ConfusionMatrix::default();
+        m.record(ModelResult::Stationary, ModelResult::Stationary);
+        m.record(ModelResult::Stationary, ModelResult::Stationary);
+        m.record(ModelResult::Stationary, ModelResult::MovingAway);
+        m.record(ModelResult::MovingAway, ModelResult::MovingAway);
+        m.record(ModelResult::MovingCloser, ModelResult::Unknown);
+
+        assert_eq!(m.total(), 5);
+        assert_eq!(m.correct(), 3);
+        assert_eq!(m.accuracy(), Some(0.6));
+        assert_eq!(m.precision(ModelResult::MovingAway), Some(0.5));
+        assert_eq!(m.recall(ModelResult::Stationary), Some(2.0 / 3.0));
+        assert_eq!(m.recall(ModelResult::MovingCloser), Some(0.0));
+        assert_eq!(m.precision(ModelResult::MovingCloser), None);
+    }
+}
@@ -1,17 +1,43 @@
+pub mod confusion_matrix;
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
 pub type  SampleID = i32;
 
 pub type SampleData = Vec<f32>;
 
-#[derive(PartialEq, Debug,Eq,Default)]
+/// 模型输出 / 人工标注的统一编码，数据库中 `predict` 与 `actual` 均使用此编码：
+///
+/// | 值 | 含义 |
+/// |----|------|
+/// | -1 | 未标注 / 未预测（数据库默认值） |
+/// | 0  | 静止 |
+/// | 1  | 远离 |
+/// | 2  | 靠近 |
+/// | 3  | 模型无法判断 |
+#[derive(PartialEq, Debug,Eq,Default,Clone,Copy,Hash,Serialize,Deserialize)]
+#[serde(into = "i32", from = "i32")]
 pub enum ModelResult {
+    /// 物体静止不动
     Stationary,
-    /// 物体正在远离检测器（距离增加）  
+    /// 物体正在远离检测器（距离增加）
     MovingAway,
-    /// 物体正在靠近检测器（距离减小）  
+    /// 物体正在靠近检测器（距离减小）
     MovingCloser,
-    /// 物体状态未知（如遮挡或短暂丢失）  
-    #[default]
+    /// 物体状态未知（如遮挡或短暂丢失）
     Unknown,
+    /// 尚未人工标注（或尚未预测）
+    #[default]
+    Unlabeled,
+}
+
+impl ModelResult {
+    /// 是否为人工可选的标注值
+    pub fn is_label(&self) -> bool {
+        matches!(self, Self::Stationary | Self::MovingAway | Self::MovingCloser)
+    }
 }
 
 impl From<i32> for ModelResult {
@@ -20,18 +46,71 @@ impl From<i32> for ModelResult {
             0 => ModelResult::Stationary,
             1 => ModelResult::MovingAway,
             2 => ModelResult::MovingCloser,
-            _ => ModelResult::Unknown,
+            3 => ModelResult::Unknown,
+            _ => ModelResult::Unlabeled,
         }
     }
 }
 
-impl Into<i32> for ModelResult {
-    fn into(self) -> i32 {
-        match self {
+impl From<ModelResult> for i32 {
+    fn from(value: ModelResult) -> Self {
+        match value {
             ModelResult::Stationary => 0,
             ModelResult::MovingAway => 1,
             ModelResult::MovingCloser => 2,
             ModelResult::Unknown => 3,
+            ModelResult::Unlabeled => -1,
+        }
+    }
+}
+
+impl Display for ModelResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stationary => write!(f, "静止"),
+            Self::MovingAway => write!(f, "远离"),
+            Self::MovingCloser => write!(f, "靠近"),
+            Self::Unknown => write!(f, "未知"),
+            Self::Unlabeled => write!(f, "未标注"),
+        }
+    }
+}
+
+/// 命令行等文本输入：数字编码、英文名或中文名
+impl FromStr for ModelResult {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "0" | "stationary" | "静止" => Ok(Self::Stationary),
+            "1" | "away" | "moving-away" | "远离" => Ok(Self::MovingAway),
+            "2" | "closer" | "moving-closer" | "靠近" => Ok(Self::MovingCloser),
+            "3" | "unknown" | "未知" => Ok(Self::Unknown),
+            "-1" | "unlabeled" | "未标注" => Ok(Self::Unlabeled),
+            other => Err(format!("unknown label: {}", other)),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_i32_round_trip() {
+        for r in [
+            ModelResult::Stationary,
+            ModelResult::MovingAway,
+            ModelResult::MovingCloser,
+            ModelResult::Unknown,
+            ModelResult::Unlabeled,
+        ] {
+            assert_eq!(ModelResult::from(i32::from(r)), r);
+        }
+        assert_eq!(ModelResult::from(-1), ModelResult::Unlabeled);
+        assert_eq!(ModelResult::default(), ModelResult::Unlabeled);
+        assert_eq!("away".parse(), Ok(ModelResult::MovingAway));
+        assert_eq!("靠近".parse(), Ok(ModelResult::MovingCloser));
+        assert!("left".parse::<ModelResult>().is_err());
+    }
+}
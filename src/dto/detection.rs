@@ -1,5 +1,7 @@
 use std::time::{Duration, Instant};
 
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub struct DetectionConfig<T> {
     pub window_size: usize,    // 采样窗口大小
@@ -20,13 +22,53 @@ pub struct DeviceState<T> {
     pub last_seen: Instant,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AlgoConfig {
     pub window_size: usize,
     pub threshold: f32,
     pub stability_window: usize,
     pub timeout_secs: u64,
+    /// 持续多久收不到有效信号视为正在离开
+    pub leave_dwell_secs: u64,
+    /// 离开后重新出现需持续多久才确认回来
+    pub return_dwell_secs: u64,
     pub batch_size: usize,
+    /// 原始 RSSI 记录最长保留时间
+    pub raw_retention_secs: u64,
+    /// 原始 RSSI 记录最多保留条数
+    pub raw_max_rows: u64,
+    /// 按设备学习的自适应阈值
+    pub baseline: BaselineConfig,
+}
+
+/// 自适应阈值参数：在场阈值取历史 RSSI 的 `present_quantile` 分位，离开阈值取 `absent_quantile` 分位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BaselineConfig {
+    /// 关闭时所有设备都使用 `AlgoConfig::threshold`
+    pub enabled: bool,
+    pub present_quantile: f32,
+    pub absent_quantile: f32,
+    /// 某小时样本数不足时改用全天分布，全天也不足时使用固定阈值
+    pub min_samples: u64,
+    /// 参与学习的历史天数，不超过 `raw_retention_secs` 保留的范围
+    pub history_days: u64,
+    /// 在场与离开阈值之间至少相差的 dB，避免来回切换
+    pub min_gap_db: f32,
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            present_quantile: 0.25,
+            absent_quantile: 0.05,
+            min_samples: 200,
+            history_days: 7,
+            min_gap_db: 4.0,
+        }
+    }
 }
 
 impl Default for AlgoConfig {
@@ -36,7 +78,42 @@ impl Default for AlgoConfig {
             threshold: -70.0,
             stability_window: 5,
             timeout_secs: 15,
+            leave_dwell_secs: 5,
+            return_dwell_secs: 3,
             batch_size: 50,
+            raw_retention_secs: 7 * 24 * 3600,
+            raw_max_rows: 1_000_000,
+            baseline: BaselineConfig::default(),
+        }
+    }
+}
+
+/// 信号质量换算参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalConfig {
+    /// 视为 0% 的 RSSI（dBm）
+    pub min_dbm: f32,
+    /// 视为 100% 的 RSSI（dBm）
+    pub max_dbm: f32,
+    /// 平滑系数（指数移动平均）
+    pub smoothing: f32,
+    /// 趋势比较的时间跨度（毫秒）
+    pub trend_window_ms: i64,
+    /// 超过该变化量（dB）才认为有上升/下降趋势
+    pub trend_delta_db: f32,
+    /// 路径损耗指数，用于估算距离
+    pub path_loss_exponent: f32,
+}
+
+impl Default for SignalConfig {
+    fn default() -> Self {
+        Self {
+            min_dbm: -100.0,
+            max_dbm: -40.0,
+            smoothing: 0.3,
+            trend_window_ms: 5_000,
+            trend_delta_db: 3.0,
+            path_loss_exponent: 2.0,
         }
     }
 }
@@ -1,9 +1,20 @@
+use crate::ble::{
+    classifier::DeviceKind,
+    signal::{SignalQuality, Trend},
+};
+
 #[derive(Default,Debug)]
 pub struct Device {
+    pub id: String,
     pub name: String,
-    pub device_type: String,
+    pub kind: DeviceKind,
     pub mac: String,
     pub rssi: i16,
+    /// 平滑后的 RSSI
+    pub smoothed_rssi: f32,
     pub percent: u8,
-    pub signal_color: String, // 'from-blue-400 to-blue-600'
-}
\ No newline at end of file
+    pub quality: SignalQuality,
+    pub trend: Trend,
+    /// 估算距离（米）
+    pub distance: Option<f32>,
+}
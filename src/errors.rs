@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 
 use snafu::Snafu;
 
@@ -16,6 +17,328 @@ pub enum AppError {
     DbError{source:sea_orm::DbErr},
     #[snafu(display("Error serializing data: {}",source))]
     InvalidData { source: serde_json::Error },
+    #[snafu(display("io error: {}",source))]
+    IoError { source: std::io::Error },
     #[snafu(display(" not found"))]
     NotFound ,
-}
\ No newline at end of file
+    #[snafu(display("bluetooth error: {}", source))]
+    Ble { source: BleError },
+    #[snafu(display("model error: {}", source))]
+    Model { source: ModelError },
+    #[snafu(display("config error: {}", source))]
+    Config { source: ConfigError },
+    #[snafu(display("notification error: {}", source))]
+    Notify { source: notify_rust::error::Error },
+    #[snafu(display("publish error: {}", source))]
+    Publish { source: PublishError },
+}
+
+/// 蓝牙适配器、扫描与锁屏相关错误
+#[derive(Snafu, Debug)]
+pub enum BleError {
+    #[snafu(display("no bluetooth adapter available"))]
+    AdapterUnavailable,
+    #[snafu(display("bluetooth adapter error: {}", source))]
+    Adapter { source: btleplug::Error },
+    #[snafu(display("scan on {} failed: {}", adapter, source))]
+    Scan { adapter: String, source: btleplug::Error },
+    #[snafu(display("event stream of {} closed", adapter))]
+    ScanClosed { adapter: String },
+    #[snafu(display("scanner stopped"))]
+    ScannerStopped,
+    #[snafu(display("device {} not found", id))]
+    DeviceNotFound { id: String },
+    #[snafu(display("GATT operation on {} failed: {}", id, source))]
+    Gatt { id: String, source: btleplug::Error },
+    #[snafu(display("characteristic {} not found on {}", uuid, id))]
+    CharacteristicNotFound { id: String, uuid: uuid::Uuid },
+    #[snafu(display("GATT operation on {} timed out", id))]
+    GattTimeout { id: String },
+    #[snafu(display("presence detector stopped"))]
+    DetectorClosed,
+    #[snafu(display("failed to lock session: {}", source))]
+    Lock { source: std::io::Error },
+    #[snafu(display("unsupported operating system"))]
+    UnsupportedPlatform,
+}
+
+/// 模型加载与推理错误
+#[derive(Snafu, Debug)]
+pub enum ModelError {
+    #[snafu(display("failed to read model {}: {}", path.display(), source))]
+    ModelRead { path: PathBuf, source: std::io::Error },
+    #[snafu(display("failed to load model: {}", source))]
+    ModelLoad { source: ort::Error },
+    #[snafu(display("model expects {} inputs, got {}", expected, actual))]
+    InferenceShape { expected: usize, actual: usize },
+    #[snafu(display("inference failed: {}", source))]
+    Inference { source: ort::Error },
+    #[snafu(display("unexpected model output: {}", reason))]
+    InvalidOutput { reason: String },
+}
+
+/// 配置文件错误
+#[derive(Snafu, Debug)]
+pub enum ConfigError {
+    #[snafu(display("failed to read {}: {}", path.display(), source))]
+    ConfigRead { path: PathBuf, source: std::io::Error },
+    #[snafu(display("invalid config {}: {}", path.display(), source))]
+    ConfigParse { path: PathBuf, source: serde_json::Error },
+    #[snafu(display("invalid {}: {}", field, reason))]
+    InvalidConfig { field: String, reason: InvalidReason },
+}
+
+/// 配置项无效的原因，`Display` 用于日志与命令行，界面见 `user_message`
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvalidReason {
+    /// 必须等于模型输入长度
+    ModelInput { len: usize },
+    Between { min: i64, max: i64 },
+    AtLeast { min: i64 },
+    AtMost { max: i64 },
+    /// 不能超过另一个配置项
+    NotAbove { field: &'static str },
+    /// 分位数需满足 0 < absent < present < 1
+    Quantiles,
+    /// 所选解锁策略需要该项
+    Required,
+    MinLength { min: usize },
+    SqliteOnly,
+    FileMissing { path: PathBuf },
+    /// 没有可写配置文件的目录
+    NoConfigDir,
+}
+
+impl std::fmt::Display for InvalidReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ModelInput { len } => write!(f, "must equal the model input length {}", len),
+            Self::Between { min, max } => write!(f, "must be between {} and {}", min, max),
+            Self::AtLeast { min } => write!(f, "must be at least {}", min),
+            Self::AtMost { max } => write!(f, "must be at most {}", max),
+            Self::NotAbove { field } => write!(f, "must not exceed {}", field),
+            Self::Quantiles => write!(
+                f,
+                "quantiles must satisfy 0 < absent_quantile < present_quantile < 1"
+            ),
+            Self::Required => write!(f, "is required by the selected unlock policy"),
+            Self::MinLength { min } => write!(f, "must be at least {} characters", min),
+            Self::SqliteOnly => write!(f, "only sqlite: URLs are supported"),
+            Self::FileMissing { path } => write!(f, "{} does not exist", path.display()),
+            Self::NoConfigDir => write!(f, "no config directory"),
+        }
+    }
+}
+
+impl InvalidReason {
+    pub fn user_message(&self) -> UserMessage {
+        match self {
+            Self::ModelInput { len } => {
+                UserMessage::new("config-reason-model-input").arg("len", *len as i64)
+            }
+            Self::Between { min, max } => UserMessage::new("config-reason-between")
+                .arg("min", *min)
+                .arg("max", *max),
+            Self::AtLeast { min } => UserMessage::new("config-reason-at-least").arg("min", *min),
+            Self::AtMost { max } => UserMessage::new("config-reason-at-most").arg("max", *max),
+            Self::NotAbove { field } => {
+                UserMessage::new("config-reason-not-above").arg("field", *field)
+            }
+            Self::Quantiles => UserMessage::new("config-reason-quantiles"),
+            Self::Required => UserMessage::new("config-reason-required"),
+            Self::MinLength { min } => {
+                UserMessage::new("config-reason-min-length").arg("min", *min as i64)
+            }
+            Self::SqliteOnly => UserMessage::new("config-reason-sqlite-only"),
+            Self::FileMissing { path } => UserMessage::new("config-reason-file-missing")
+                .arg("path", path.display().to_string()),
+            Self::NoConfigDir => UserMessage::new("config-reason-no-config-dir"),
+        }
+    }
+}
+
+/// 展示给用户的提示：Fluent key 与参数，由 `i18n` 按当前语言渲染，切换语言后随之更新
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserMessage {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, MessageArg)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageArg {
+    Text(String),
+    Number(i64),
+    /// 同样需要翻译的嵌套提示，如配置项无效的原因
+    Message(UserMessage),
+}
+
+impl UserMessage {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl Into<MessageArg>) -> Self {
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+impl From<String> for MessageArg {
+    fn from(v: String) -> Self {
+        Self::Text(v)
+    }
+}
+
+impl From<&str> for MessageArg {
+    fn from(v: &str) -> Self {
+        Self::Text(v.to_string())
+    }
+}
+
+impl From<i64> for MessageArg {
+    fn from(v: i64) -> Self {
+        Self::Number(v)
+    }
+}
+
+impl From<UserMessage> for MessageArg {
+    fn from(v: UserMessage) -> Self {
+        Self::Message(v)
+    }
+}
+
+/// webhook 与 MQTT 发布错误
+#[derive(Snafu, Debug)]
+pub enum PublishError {
+    #[snafu(display("invalid sink {}: {}", sink, reason))]
+    InvalidSink { sink: String, reason: String },
+    #[snafu(display("request to {} failed: {}", url, source))]
+    Http { url: String, source: reqwest::Error },
+    #[snafu(display("{} responded with status {}", url, status))]
+    HttpStatus { url: String, status: u16 },
+    #[snafu(display("not connected to {}", broker))]
+    MqttDisconnected { broker: String },
+    #[snafu(display("mqtt publish failed: {}", source))]
+    Mqtt { source: rumqttc::ClientError },
+    #[snafu(display("{} did not acknowledge the message", broker))]
+    MqttUnacknowledged { broker: String },
+}
+
+impl From<BleError> for AppError {
+    fn from(source: BleError) -> Self {
+        AppError::Ble { source }
+    }
+}
+
+impl From<ModelError> for AppError {
+    fn from(source: ModelError) -> Self {
+        AppError::Model { source }
+    }
+}
+
+impl From<ConfigError> for AppError {
+    fn from(source: ConfigError) -> Self {
+        AppError::Config { source }
+    }
+}
+
+impl From<PublishError> for AppError {
+    fn from(source: PublishError) -> Self {
+        AppError::Publish { source }
+    }
+}
+
+impl AppError {
+    /// 展示给用户的提示，通过 `Localize` 按界面语言渲染
+    pub fn user_message(&self) -> UserMessage {
+        match self {
+            AppError::Ble { source: BleError::AdapterUnavailable } => {
+                UserMessage::new("error-adapter-unavailable")
+            }
+            AppError::Ble { source: BleError::Adapter { .. } } => UserMessage::new("error-adapter"),
+            AppError::Ble { source: BleError::DeviceNotFound { id } } => {
+                UserMessage::new("error-device-not-found").arg("id", id.as_str())
+            }
+            AppError::Ble { source: BleError::Gatt { .. } } => UserMessage::new("error-gatt"),
+            AppError::Ble { source: BleError::UnsupportedPlatform } => {
+                UserMessage::new("error-unsupported-platform")
+            }
+            AppError::Ble { source } => {
+                UserMessage::new("error-ble").arg("error", source.to_string())
+            }
+            AppError::Model { source: ModelError::ModelRead { path, .. } } => {
+                UserMessage::new("error-model-read").arg("path", path.display().to_string())
+            }
+            AppError::Model { source: ModelError::ModelLoad { .. } } => {
+                UserMessage::new("error-model-load")
+            }
+            AppError::Model { source } => {
+                UserMessage::new("error-model").arg("error", source.to_string())
+            }
+            AppError::Config { source: ConfigError::ConfigRead { path, .. } } => {
+                UserMessage::new("error-config-read").arg("path", path.display().to_string())
+            }
+            AppError::Config { source: ConfigError::ConfigParse { path, source } } => {
+                UserMessage::new("error-config-parse")
+                    .arg("path", path.display().to_string())
+                    .arg("error", source.to_string())
+            }
+            AppError::Config { source: ConfigError::InvalidConfig { field, reason } } => {
+                UserMessage::new("error-config-invalid")
+                    .arg("field", field.as_str())
+                    .arg("reason", reason.user_message())
+            }
+            AppError::DbError { .. } => UserMessage::new("error-db"),
+            other => UserMessage::new("error-other").arg("error", other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_and_message() {
+        let e: AppError = ModelError::InferenceShape {
+            expected: 9,
+            actual: 3,
+        }
+        .into();
+        assert_eq!(e.to_string(), "model error: model expects 9 inputs, got 3");
+
+        let e: AppError = BleError::AdapterUnavailable.into();
+        assert_eq!(e.user_message(), UserMessage::new("error-adapter-unavailable"));
+
+        let source = serde_json::from_str::<u32>("x").unwrap_err();
+        let e: AppError = ConfigError::ConfigParse {
+            path: "config.json".into(),
+            source,
+        }
+        .into();
+        let message = e.user_message();
+        assert_eq!(message.key, "error-config-parse");
+        assert_eq!(message.args[0], ("path", MessageArg::from("config.json")));
+
+        let e: AppError = ConfigError::InvalidConfig {
+            field: "threshold".into(),
+            reason: InvalidReason::Between { min: -100, max: -30 },
+        }
+        .into();
+        assert_eq!(e.to_string(), "config error: invalid threshold: must be between -100 and -30");
+        assert_eq!(
+            e.user_message().args[1],
+            (
+                "reason",
+                MessageArg::Message(
+                    UserMessage::new("config-reason-between")
+                        .arg("min", -100i64)
+                        .arg("max", -30i64)
+                )
+            )
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! 领域类型的界面名称，`Display` 仍用于日志与命令行
+
+use crate::{
+    ble::{
+        adapter::AdapterState,
+        presence_state::{PresenceState, TransitionReason},
+        signal::SignalQuality,
+    },
+    domain::{entity::rssi_event::now_millis, value_objects::ModelResult},
+    errors::{AppError, MessageArg, UserMessage},
+    runtime::LockReason,
+    snooze::{Snooze, SnoozeReason},
+    t,
+    unlock::UnlockPolicy,
+};
+
+use super::{translate, FluentArgs, Locale};
+
+pub trait Localize {
+    fn localize(&self, locale: Locale) -> String;
+}
+
+impl Localize for ModelResult {
+    fn localize(&self, locale: Locale) -> String {
+        let key = match self {
+            Self::Stationary => "model-stationary",
+            Self::MovingAway => "model-moving-away",
+            Self::MovingCloser => "model-moving-closer",
+            Self::Unknown => "model-unknown",
+            Self::Unlabeled => "model-unlabeled",
+        };
+        t!(locale, key)
+    }
+}
+
+impl Localize for PresenceState {
+    fn localize(&self, locale: Locale) -> String {
+        let key = match self {
+            Self::Unknown => "presence-unknown",
+            Self::Present => "presence-present",
+            Self::Leaving => "presence-leaving",
+            Self::Absent => "presence-absent",
+            Self::Returning => "presence-returning",
+        };
+        t!(locale, key)
+    }
+}
+
+impl Localize for TransitionReason {
+    fn localize(&self, locale: Locale) -> String {
+        let dbm = |v: &f32| v.round() as i64;
+        match self {
+            Self::Strong { rssi, threshold } => t!(
+                locale,
+                "transition-strong",
+                rssi = dbm(rssi),
+                threshold = dbm(threshold)
+            ),
+            Self::Weak { rssi, threshold } => t!(
+                locale,
+                "transition-weak",
+                rssi = dbm(rssi),
+                threshold = dbm(threshold)
+            ),
+            Self::Quiet { secs, threshold } => t!(
+                locale,
+                "transition-quiet",
+                secs = *secs,
+                threshold = dbm(threshold)
+            ),
+            Self::Sustained { secs } => t!(locale, "transition-sustained", secs = *secs),
+        }
+    }
+}
+
+impl Localize for LockReason {
+    fn localize(&self, locale: Locale) -> String {
+        match self {
+            Self::Absent {
+                transition,
+                adapter: Some(state),
+            } => t!(
+                locale,
+                "lock-reason-adapter",
+                adapter = state.localize(locale),
+                reason = transition.localize(locale)
+            ),
+            Self::Absent { transition, .. } => transition.localize(locale),
+            Self::Verified {
+                policy: UnlockPolicy::Challenge,
+            } => t!(locale, "lock-reason-challenge"),
+            Self::Verified { .. } => t!(locale, "lock-reason-connected"),
+        }
+    }
+}
+
+impl Localize for AdapterState {
+    fn localize(&self, locale: Locale) -> String {
+        let key = match self {
+            Self::Missing => "adapter-missing",
+            Self::PoweredOff => "adapter-powered-off",
+            Self::Ready => "adapter-ready",
+        };
+        t!(locale, key)
+    }
+}
+
+impl Localize for SignalQuality {
+    fn localize(&self, locale: Locale) -> String {
+        let key = match self {
+            Self::Excellent => "signal-excellent",
+            Self::Good => "signal-good",
+            Self::Fair => "signal-fair",
+            Self::Weak => "signal-weak",
+            Self::Lost => "signal-lost",
+        };
+        t!(locale, key)
+    }
+}
+
+impl Localize for SnoozeReason {
+    fn localize(&self, locale: Locale) -> String {
+        match self {
+            Self::Snoozed {
+                snooze: Snooze::Until { until },
+            } => {
+                let minutes = (until - now_millis()).max(0) / 60_000 + 1;
+                t!(locale, "snooze-until", minutes = minutes)
+            }
+            Self::Snoozed {
+                snooze: Snooze::UntilUnlock,
+            } => t!(locale, "snooze-until-unlock"),
+            Self::Snoozed {
+                snooze: Snooze::Indefinite,
+            } => t!(locale, "snooze-indefinite"),
+            Self::QuietHours { window } => t!(
+                locale,
+                "snooze-quiet-hours",
+                start = window.start.as_str(),
+                end = window.end.as_str()
+            ),
+            Self::App { name } => t!(locale, "snooze-app", name = name.as_str()),
+            Self::Fullscreen => t!(locale, "snooze-fullscreen"),
+        }
+    }
+}
+
+impl Localize for UserMessage {
+    fn localize(&self, locale: Locale) -> String {
+        let mut args = FluentArgs::new();
+        for (name, value) in &self.args {
+            match value {
+                MessageArg::Text(text) => args.set(*name, text.clone()),
+                MessageArg::Number(n) => args.set(*name, *n),
+                MessageArg::Message(message) => args.set(*name, message.localize(locale)),
+            }
+        }
+        translate(locale, self.key, Some(&args))
+    }
+}
+
+impl Localize for AppError {
+    fn localize(&self, locale: Locale) -> String {
+        self.user_message().localize(locale)
+    }
+}
@@ -0,0 +1,215 @@
+//! 界面文本的多语言支持
+//!
+//! 文案放在 `locales/<语言>/main.ftl`（Fluent 格式），编译时嵌入。
+//! 界面通过 `use_locale` 取得当前语言，用 `t!` 宏取文本；缺失的条目退回中文，再退回 key 本身。
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use fluent_bundle::{concurrent::FluentBundle, FluentResource};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use unic_langid::LanguageIdentifier;
+
+use crate::infrastructure::json_store::{default_state_path, JsonStore};
+
+pub use fluent_bundle::FluentArgs;
+
+mod labels;
+
+pub use labels::Localize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::EnUs => "en-US",
+        }
+    }
+
+    /// 以该语言本身书写的名称，用于语言选择
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::ZhCn => "简体中文",
+            Self::EnUs => "English",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Self::ZhCn => include_str!("../../locales/zh-CN/main.ftl"),
+            Self::EnUs => include_str!("../../locales/en-US/main.ftl"),
+        }
+    }
+
+    /// 按 `LC_ALL`/`LC_MESSAGES`/`LANG` 推断，非中文环境使用英文
+    pub fn from_env() -> Self {
+        let lang = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|k| std::env::var(k).ok())
+            .find(|v| !v.is_empty());
+        match lang {
+            Some(l) if !l.starts_with("zh") && l != "C" && l != "POSIX" => Self::EnUs,
+            _ => Self::ZhCn,
+        }
+    }
+}
+
+type Bundle = FluentBundle<FluentResource>;
+
+fn bundles() -> &'static HashMap<Locale, Bundle> {
+    static BUNDLES: OnceLock<HashMap<Locale, Bundle>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {
+        Locale::ALL
+            .iter()
+            .map(|locale| {
+                let langid: LanguageIdentifier = locale.tag().parse().unwrap_or_default();
+                let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+                // 不插入双向隔离字符，否则界面上会出现不可见字符
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(locale.source().to_string()).unwrap_or_else(
+                    |(res, errors)| {
+                        warn!("Invalid entries in {} catalog: {:?}", locale.tag(), errors);
+                        res
+                    },
+                );
+                if let Err(errors) = bundle.add_resource(resource) {
+                    warn!(
+                        "Duplicate entries in {} catalog: {:?}",
+                        locale.tag(),
+                        errors
+                    );
+                }
+                (*locale, bundle)
+            })
+            .collect()
+    })
+}
+
+fn format_message(locale: Locale, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = bundles().get(&locale)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = vec![];
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        warn!("Failed to format {} in {}: {:?}", key, locale.tag(), errors);
+    }
+    Some(text.into_owned())
+}
+
+/// 取 `key` 对应的文本，通常通过 `t!` 调用
+pub fn translate(locale: Locale, key: &str, args: Option<&FluentArgs>) -> String {
+    format_message(locale, key, args)
+        .or_else(|| format_message(Locale::ZhCn, key, args))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// `t!(locale, "key")` 或 `t!(locale, "key", name = value, ...)`
+#[macro_export]
+macro_rules! t {
+    ($locale:expr, $key:expr $(,)?) => {
+        $crate::i18n::translate($locale, $key, None)
+    };
+    ($locale:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::i18n::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($locale, $key, Some(&args))
+    }};
+}
+
+/// 界面偏好，保存在 `$XDG_STATE_HOME/blue-lock/ui.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    /// 为空时按系统语言
+    pub locale: Option<Locale>,
+}
+
+fn ui_store() -> JsonStore<UiSettings> {
+    JsonStore::new(default_state_path("ui.json"))
+}
+
+/// 启动时使用的语言：已保存的设置优先，其次系统语言
+pub fn load_locale() -> Locale {
+    ui_store().load().locale.unwrap_or_else(Locale::from_env)
+}
+
+pub fn save_locale(locale: Locale) {
+    let store = ui_store();
+    let settings = UiSettings {
+        locale: Some(locale),
+        ..store.load()
+    };
+    if let Err(e) = store.save(&settings) {
+        warn!("Failed to save locale: {}", e);
+    }
+}
+
+/// 当前界面语言，由 `App` 提供
+pub fn use_locale() -> dioxus::prelude::Signal<Locale> {
+    dioxus::prelude::use_context()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 两份目录的 key 必须一致
+    #[test]
+    fn test_catalogs_complete() {
+        let keys = |locale: Locale| {
+            let resource = FluentResource::try_new(locale.source().to_string())
+                .unwrap_or_else(|(_, errors)| panic!("{}: {:?}", locale.tag(), errors));
+            let mut keys: Vec<String> = resource
+                .entries()
+                .filter_map(|e| match e {
+                    fluent_syntax::ast::Entry::Message(m) => Some(m.id.name.to_string()),
+                    _ => None,
+                })
+                .collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(Locale::ZhCn), keys(Locale::EnUs));
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(t!(Locale::EnUs, "menu-home"), "Home");
+        assert_eq!(t!(Locale::ZhCn, "menu-home"), "首页");
+        assert_eq!(t!(Locale::EnUs, "log-total", count = 3), "3 entries");
+        assert_eq!(t!(Locale::EnUs, "no-such-key"), "no-such-key");
+    }
+
+    #[test]
+    fn test_error_messages() {
+        use crate::errors::{AppError, ConfigError, InvalidReason};
+
+        let e: AppError = ConfigError::InvalidConfig {
+            field: "threshold".into(),
+            reason: InvalidReason::Between {
+                min: -100,
+                max: -30,
+            },
+        }
+        .into();
+        assert_eq!(
+            e.localize(Locale::EnUs),
+            "Invalid setting threshold: must be between -100 and -30"
+        );
+        assert_eq!(
+            e.localize(Locale::ZhCn),
+            "配置项 threshold 无效：应在 -100 到 -30 之间"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+use crate::errors::{AppError, AppResult};
+
+/// `$XDG_STATE_HOME/blue-lock/<file>`，没有时退回 `~/.local/state`，再退回当前目录
+pub fn default_state_path(file: &str) -> PathBuf {
+    let dir = std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))
+        .map(|d| d.join("blue-lock"))
+        .unwrap_or_default();
+    dir.join(file)
+}
+
+/// 以单个 JSON 文件保存的小块状态（如暂停状态、偏好设置）
+pub struct JsonStore<T> {
+    path: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> JsonStore<T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 文件不存在或内容损坏时返回默认值
+    pub fn load(&self) -> T {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return T::default(),
+            Err(e) => {
+                warn!("Failed to read {}: {}", self.path.display(), e);
+                return T::default();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring corrupt {}: {}", self.path.display(), e);
+            T::default()
+        })
+    }
+
+    /// 先写临时文件再改名，避免中途退出留下半个文件；
+    /// 配置中含有质询密钥与 MQTT 密码，文件仅限本用户读写
+    pub fn save(&self, value: &T) -> AppResult<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| AppError::IoError { source: e })?;
+        }
+        let content =
+            serde_json::to_vec_pretty(value).map_err(|e| AppError::InvalidData { source: e })?;
+        let tmp = self.path.with_extension("tmp");
+        write_private(&tmp, &content).map_err(|e| AppError::IoError { source: e })?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| AppError::IoError { source: e })
+    }
+}
+
+/// 以 0600 权限创建并写入文件
+#[cfg(unix)]
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    use std::{io::Write as _, os::unix::fs::OpenOptionsExt as _};
+
+    // 残留的临时文件可能带着旧权限，先删掉以便按新权限创建
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    std::fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entity::rssi_event::now_millis;
+
+    #[test]
+    fn test_round_trip_and_corrupt_file() {
+        let dir = std::env::temp_dir().join(format!("blue-lock-store-{}", now_millis()));
+        let store = JsonStore::<Vec<u32>>::new(dir.join("state.json"));
+        assert!(store.load().is_empty());
+
+        store.save(&vec![1, 2, 3]).unwrap();
+        assert_eq!(store.load(), vec![1, 2, 3]);
+
+        std::fs::write(store.path(), "{oops").unwrap();
+        assert!(store.load().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_file_is_private() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let dir = std::env::temp_dir().join(format!("blue-lock-private-{}", now_millis()));
+        let store = JsonStore::<Vec<u32>>::new(dir.join("config.json"));
+        // 已有的文件权限过宽，重写后收紧
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(store.path(), "[]").unwrap();
+        std::fs::set_permissions(store.path(), std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        store.save(&vec![1]).unwrap();
+        let mode = std::fs::metadata(store.path())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+use crate::domain::entity::rssi_event::now_millis;
+
+/// 内存中最多保留的日志条数
+const LOG_CAPACITY: usize = 5000;
+
+/// 一条结构化日志
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub seq: u64,
+    /// unix 毫秒
+    pub ts: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl LogRecord {
+    pub fn level(&self) -> tracing::Level {
+        self.level.parse().unwrap_or(tracing::Level::INFO)
+    }
+
+    /// 文本搜索，匹配消息、target 与字段
+    pub fn matches(&self, text: &str) -> bool {
+        text.is_empty()
+            || self.message.contains(text)
+            || self.target.contains(text)
+            || self
+                .fields
+                .iter()
+                .any(|(k, v)| k.contains(text) || v.contains(text))
+    }
+}
+
+/// 日志环形缓冲，同时广播新日志给 UI
+#[derive(Clone)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    capacity: usize,
+    seq: Arc<AtomicU64>,
+    tx: broadcast::Sender<LogRecord>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(1024);
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            seq: Arc::new(AtomicU64::new(0)),
+            tx,
+        }
+    }
+
+    /// 进程级缓冲，tracing 初始化早于 UI 启动
+    pub fn global() -> &'static LogBuffer {
+        static BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+        BUFFER.get_or_init(|| LogBuffer::new(LOG_CAPACITY))
+    }
+
+    pub fn push(&self, mut record: LogRecord) {
+        record.seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == self.capacity {
+                records.pop_front();
+            }
+            records.push_back(record.clone());
+        }
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.tx.send(record);
+    }
+
+    pub fn snapshot(&self) -> Vec<LogRecord> {
+        self.records
+            .lock()
+            .map(|r| r.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LogRecord> {
+        self.tx.subscribe()
+    }
+}
+
+/// 把 tracing 事件写入 `LogBuffer` 的 Layer
+pub struct LogLayer {
+    buffer: LogBuffer,
+}
+
+impl LogLayer {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        self.buffer.push(LogRecord {
+            seq: 0,
+            ts: now_millis(),
+            level: meta.level().to_string(),
+            target: meta.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_layer_captures_structured_fields() {
+        let buffer = LogBuffer::new(2);
+        let subscriber = tracing_subscriber::registry().with(LogLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(device_id = "abc", rssi = -60, "Processing sample");
+            tracing::warn!("second");
+            tracing::error!("third");
+        });
+
+        let records = buffer.snapshot();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "second");
+        assert_eq!(records[0].level(), tracing::Level::WARN);
+        assert_eq!(records[1].seq, 2);
+
+        let buffer = LogBuffer::new(10);
+        let subscriber = tracing_subscriber::registry().with(LogLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(device_id = "abc", rssi = -60, "Processing sample");
+        });
+        let record = &buffer.snapshot()[0];
+        assert_eq!(record.message, "Processing sample");
+        assert_eq!(record.fields["device_id"], "abc");
+        assert_eq!(record.fields["rssi"], "-60");
+        assert!(record.matches("abc"));
+        assert!(!record.matches("xyz"));
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use tracing::info;
+
+use crate::errors::{AppError, AppResult};
+
+/// 按顺序执行的建表/迁移脚本，下标 + 1 即为 `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    include_str!("../../migrations/0001_init.sql"),
+    include_str!("../../migrations/0002_sample_model.sql"),
+    include_str!("../../migrations/0003_unlabeled_encoding.sql"),
+    include_str!("../../migrations/0004_rssi_event.sql"),
+    include_str!("../../migrations/0005_trusted_device.sql"),
+    include_str!("../../migrations/0006_presence_span.sql"),
+    include_str!("../../migrations/0007_trusted_device_address.sql"),
+];
+
+/// 将数据库升级到最新版本
+pub async fn migrate(conn: &DatabaseConnection) -> AppResult<()> {
+    let current = user_version(conn).await?;
+
+    for (idx, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = idx as i32 + 1;
+        info!("Applying migration v{}", version);
+        conn.execute_unprepared(sql)
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        conn.execute_unprepared(&format!("PRAGMA user_version = {}", version))
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+    }
+    Ok(())
+}
+
+async fn user_version(conn: &DatabaseConnection) -> AppResult<i32> {
+    let row = conn
+        .query_one(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "PRAGMA user_version",
+        ))
+        .await
+        .map_err(|e| AppError::DbError { source: e })?;
+
+    match row {
+        Some(r) => r
+            .try_get::<i32>("", "user_version")
+            .map_err(|e| AppError::DbError { source: e }),
+        None => Ok(0),
+    }
+}
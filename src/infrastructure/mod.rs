@@ -5,8 +5,14 @@ use shaku::{Component, Interface};
 
 use crate::errors::AppResult;
 
+pub mod json_store;
+pub mod log_layer;
+pub mod migration;
 pub mod model;
+pub mod rssi_event_repo;
+pub mod rssi_event_writer;
 pub mod sample_repo;
+pub mod trusted_device_repo;
 
 #[async_trait::async_trait]
 pub trait IDbProvider: Interface {
@@ -2,4 +2,6 @@
 
 pub mod prelude;
 
+pub mod t_rssi_event;
 pub mod t_sample;
+pub mod t_trusted_device;
@@ -1,3 +1,5 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.14
 
+pub use super::t_rssi_event::Entity as TRssiEvent;
 pub use super::t_sample::Entity as TSample;
+pub use super::t_trusted_device::Entity as TTrustedDevice;
@@ -8,6 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub device: String,
+    pub model: String,
     #[sea_orm(column_type = "Double", nullable)]
     pub created_at: String,
     pub sample: String,
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+
+use sea_orm::{
+    ActiveValue, ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter,
+    QueryOrder, Statement,
+};
+use shaku::Component;
+
+use crate::{
+    domain::{
+        entity::rssi_event::{RssiBucket, RssiEvent},
+        repo::rssi_event_repo::IRssiEventRepo,
+    },
+    errors::{AppError, AppResult},
+};
+
+use super::{
+    model::t_rssi_event::{self, Entity as TRssiEventEntity},
+    IDbProvider,
+};
+
+#[derive(Component)]
+#[shaku(interface = IRssiEventRepo)]
+pub struct RssiEventRepo {
+    #[shaku(inject)]
+    db_provider: Arc<dyn IDbProvider>,
+}
+
+#[async_trait::async_trait]
+impl IRssiEventRepo for RssiEventRepo {
+    async fn insert_batch(&self, events: Vec<RssiEvent>) -> AppResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let conn = self.db_provider.get_connection();
+        let models = events.into_iter().map(|e| t_rssi_event::ActiveModel {
+            id: ActiveValue::NotSet,
+            device: ActiveValue::Set(e.device),
+            name: ActiveValue::Set(e.name),
+            rssi: ActiveValue::Set(e.rssi as i32),
+            ts: ActiveValue::Set(e.ts),
+        });
+        TRssiEventEntity::insert_many(models)
+            .exec(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(())
+    }
+
+    async fn load_range(&self, device: &str, from: i64, to: i64) -> AppResult<Vec<RssiEvent>> {
+        let conn = self.db_provider.get_connection();
+        let rows = TRssiEventEntity::find()
+            .filter(t_rssi_event::Column::Device.eq(device))
+            .filter(t_rssi_event::Column::Ts.between(from, to))
+            .order_by_asc(t_rssi_event::Column::Ts)
+            .order_by_asc(t_rssi_event::Column::Id)
+            .all(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|m| RssiEvent::new(m.device, m.name, m.rssi as i16, m.ts))
+            .collect())
+    }
+
+    async fn rssi_histogram(&self, device: &str, from: i64) -> AppResult<Vec<RssiBucket>> {
+        let conn = self.db_provider.get_connection();
+        let rows = conn
+            .query_all(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                r#"
+                    SELECT CAST(strftime('%H', e.ts / 1000, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+                           e.rssi, COUNT(*) AS count
+                    FROM t_rssi_event e
+                    WHERE e.device = $1 AND e.ts >= $2
+                      AND EXISTS (
+                          SELECT 1 FROM t_presence_span s
+                          WHERE s.device = e.device AND e.ts >= s.start_ts
+                            AND (s.end_ts IS NULL OR e.ts <= s.end_ts)
+                      )
+                    GROUP BY hour, e.rssi
+                "#,
+                vec![device.into(), from.into()],
+            ))
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+
+        rows.iter()
+            .map(|row| {
+                let get = |col| row.try_get::<i64>("", col);
+                Ok(RssiBucket {
+                    hour: get("hour")? as u32,
+                    rssi: get("rssi")? as i16,
+                    count: get("count")? as u64,
+                })
+            })
+            .collect::<Result<_, sea_orm::DbErr>>()
+            .map_err(|e| AppError::DbError { source: e })
+    }
+
+    async fn purge(&self, before: i64, max_rows: u64) -> AppResult<u64> {
+        let conn = self.db_provider.get_connection();
+        let by_age = conn
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                "DELETE FROM t_rssi_event WHERE ts < $1",
+                vec![before.into()],
+            ))
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        let by_rows = conn
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Sqlite,
+                r#"
+                    DELETE FROM t_rssi_event
+                    WHERE id <= (SELECT id FROM t_rssi_event ORDER BY id DESC LIMIT 1 OFFSET $1)
+                "#,
+                vec![(max_rows as i64).into()],
+            ))
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        conn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "DELETE FROM t_presence_span WHERE end_ts < $1",
+            vec![before.into()],
+        ))
+        .await
+        .map_err(|e| AppError::DbError { source: e })?;
+        Ok(by_age.rows_affected() + by_rows.rows_affected())
+    }
+
+    async fn begin_present(&self, device: &str, ts: i64) -> AppResult<()> {
+        self.end_present(Some(device), ts).await?;
+        let conn = self.db_provider.get_connection();
+        conn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "INSERT INTO t_presence_span (device, start_ts) VALUES ($1, $2)",
+            vec![device.into(), ts.into()],
+        ))
+        .await
+        .map_err(|e| AppError::DbError { source: e })?;
+        Ok(())
+    }
+
+    async fn end_present(&self, device: Option<&str>, ts: i64) -> AppResult<()> {
+        let conn = self.db_provider.get_connection();
+        conn.execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            r#"
+                UPDATE t_presence_span SET end_ts = MAX(start_ts, $1)
+                WHERE end_ts IS NULL AND ($2 IS NULL OR device = $2)
+            "#,
+            vec![ts.into(), device.map(str::to_string).into()],
+        ))
+        .await
+        .map_err(|e| AppError::DbError { source: e })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        di::Deps,
+        infrastructure::{migration::migrate, DbProvider, DbProviderParameters},
+    };
+    use chrono::{Local, TimeZone, Timelike};
+    use sea_orm::Database;
+    use shaku::HasComponent;
+
+    /// 已迁移的内存数据库
+    async fn memory_repo() -> Arc<dyn IRssiEventRepo> {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        migrate(&db).await.unwrap();
+        let deps = Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build();
+        deps.resolve()
+    }
+
+    fn event(device: &str, rssi: i16, ts: i64) -> RssiEvent {
+        RssiEvent::new(device.to_string(), "phone".to_string(), rssi, ts)
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_load_range() {
+        let repo = memory_repo().await;
+        repo.insert_batch(vec![]).await.unwrap();
+        repo.insert_batch(vec![
+            event("a", -60, 3),
+            event("a", -61, 1),
+            event("b", -70, 2),
+            event("a", -62, 2),
+            event("a", -63, 9),
+        ])
+        .await
+        .unwrap();
+
+        let events = repo.load_range("a", 1, 3).await.unwrap();
+        let ts: Vec<_> = events.iter().map(|e| (e.ts, e.rssi)).collect();
+        assert_eq!(ts, vec![(1, -61), (2, -62), (3, -60)]);
+        assert_eq!(events[0].name, "phone");
+        assert!(repo.load_range("c", 0, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_purge_by_age_and_rows() {
+        let repo = memory_repo().await;
+        repo.insert_batch((1..=10).map(|ts| event("a", -60, ts)).collect())
+            .await
+            .unwrap();
+
+        // 未超出行数上限时只按时间删除
+        assert_eq!(repo.purge(3, 100).await.unwrap(), 2);
+        // 先删除早于 4 的 1 条，再只保留最新的 5 条
+        assert_eq!(repo.purge(4, 5).await.unwrap(), 3);
+        let ts: Vec<_> = repo
+            .load_range("a", 0, 100)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.ts)
+            .collect();
+        assert_eq!(ts, vec![6, 7, 8, 9, 10]);
+        assert_eq!(repo.purge(0, 5).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rssi_histogram() {
+        let repo = memory_repo().await;
+        let day = 86_400_000;
+        let ts = 20 * day + 3_600_000 * 9;
+        repo.insert_batch(vec![
+            event("a", -60, ts),
+            event("a", -60, ts + 1000),
+            event("a", -70, ts + 2000),
+            // 不在场期间
+            event("a", -90, ts + 5000),
+            event("a", -60, ts + 3_600_000),
+            event("b", -60, ts),
+            // 早于统计起点
+            event("a", -50, ts - day),
+        ])
+        .await
+        .unwrap();
+        repo.begin_present("a", ts - day).await.unwrap();
+        repo.end_present(Some("a"), ts + 2000).await.unwrap();
+        repo.begin_present("a", ts + 3_600_000).await.unwrap();
+        repo.begin_present("b", ts).await.unwrap();
+
+        let hour = |ts: i64| Local.timestamp_millis_opt(ts).unwrap().hour();
+        let mut buckets = repo.rssi_histogram("a", ts - 1000).await.unwrap();
+        buckets.sort_by_key(|b| (b.hour, b.rssi));
+        let mut expected = vec![
+            RssiBucket {
+                hour: hour(ts),
+                rssi: -70,
+                count: 1,
+            },
+            RssiBucket {
+                hour: hour(ts),
+                rssi: -60,
+                count: 2,
+            },
+            RssiBucket {
+                hour: hour(ts + 3_600_000),
+                rssi: -60,
+                count: 1,
+            },
+        ];
+        expected.sort_by_key(|b| (b.hour, b.rssi));
+        assert_eq!(buckets, expected);
+        assert!(repo.rssi_histogram("c", 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_presence_spans() {
+        let repo = memory_repo().await;
+        repo.insert_batch(vec![
+            event("a", -60, 3),
+            event("a", -60, 7),
+            event("a", -60, 20),
+        ])
+        .await
+        .unwrap();
+        let count = |buckets: Vec<RssiBucket>| buckets.iter().map(|b| b.count).sum::<u64>();
+
+        // 没有在场时段时不学习
+        assert_eq!(count(repo.rssi_histogram("a", 0).await.unwrap()), 0);
+        repo.begin_present("a", 1).await.unwrap();
+        repo.begin_present("b", 1).await.unwrap();
+        repo.end_present(None, 5).await.unwrap();
+        assert_eq!(count(repo.rssi_histogram("a", 0).await.unwrap()), 1);
+
+        // 重新进入时先结束未结束的时段，重叠部分不重复计数
+        repo.begin_present("a", 10).await.unwrap();
+        repo.begin_present("a", 20).await.unwrap();
+        assert_eq!(count(repo.rssi_histogram("a", 0).await.unwrap()), 2);
+
+        // 过期的时段随记录一起清理，仍在进行的时段保留
+        repo.purge(6, 100).await.unwrap();
+        assert_eq!(count(repo.rssi_histogram("a", 0).await.unwrap()), 1);
+        repo.insert_batch(vec![event("a", -60, 40)]).await.unwrap();
+        repo.purge(25, 100).await.unwrap();
+        assert_eq!(count(repo.rssi_histogram("a", 0).await.unwrap()), 1);
+    }
+}
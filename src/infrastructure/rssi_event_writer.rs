@@ -0,0 +1,187 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+use crate::{
+    domain::{
+        entity::rssi_event::{now_millis, RssiEvent},
+        repo::rssi_event_repo::IRssiEventRepo,
+    },
+    dto::detection::AlgoConfig,
+};
+
+/// 不足一批时的最长缓冲时间
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// 执行保留策略的间隔
+const RETENTION_INTERVAL: Duration = Duration::from_secs(600);
+
+/// 缓冲写入原始 RSSI 记录，按 `AlgoConfig::batch_size` 批量落库
+pub struct RssiEventWriter {
+    repo: Arc<dyn IRssiEventRepo>,
+    buffer: Vec<RssiEvent>,
+    batch_size: usize,
+    /// 不足一批时的最长缓冲时间，默认 `FLUSH_INTERVAL`
+    flush_interval: Duration,
+    retention_secs: u64,
+    max_rows: u64,
+}
+
+impl RssiEventWriter {
+    /// 启动写入任务，返回供检测器使用的发送端；批量大小与保留策略随 `config` 更新
+    pub fn spawn(
+        repo: Arc<dyn IRssiEventRepo>,
+        config: watch::Receiver<AlgoConfig>,
+    ) -> mpsc::Sender<RssiEvent> {
+        let writer = Self::new(repo, &config.borrow());
+        let (tx, rx) = mpsc::channel(writer.batch_size * 4);
+        tokio::spawn(writer.run(rx, config));
+        tx
+    }
+
+    fn new(repo: Arc<dyn IRssiEventRepo>, config: &AlgoConfig) -> Self {
+        let batch_size = config.batch_size.max(1);
+        Self {
+            repo,
+            buffer: Vec::with_capacity(batch_size),
+            batch_size,
+            flush_interval: FLUSH_INTERVAL,
+            retention_secs: config.raw_retention_secs,
+            max_rows: config.raw_max_rows,
+        }
+    }
+
+    fn configure(&mut self, config: &AlgoConfig) {
+        self.batch_size = config.batch_size.max(1);
+        self.retention_secs = config.raw_retention_secs;
+        self.max_rows = config.raw_max_rows;
+    }
+
+    async fn run(
+        mut self,
+        mut rx: mpsc::Receiver<RssiEvent>,
+        mut config: watch::Receiver<AlgoConfig>,
+    ) {
+        let start = tokio::time::Instant::now() + self.flush_interval;
+        let mut flush_tick = tokio::time::interval_at(start, self.flush_interval);
+        let mut retention_tick = tokio::time::interval(RETENTION_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Some(event) => {
+                        self.buffer.push(event);
+                        if self.buffer.len() >= self.batch_size {
+                            self.flush().await;
+                        }
+                    }
+                    None => {
+                        // 发送端全部关闭，写完剩余数据后退出
+                        self.flush().await;
+                        break;
+                    }
+                },
+                _ = flush_tick.tick() => self.flush().await,
+                _ = retention_tick.tick() => self.apply_retention().await,
+                Ok(()) = config.changed() => {
+                    let config = config.borrow_and_update().clone();
+                    self.configure(&config);
+                    if self.buffer.len() >= self.batch_size {
+                        self.flush().await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let batch = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.batch_size));
+        let len = batch.len();
+        if let Err(e) = self.repo.insert_batch(batch).await {
+            warn!("Failed to write {} rssi events: {}", len, e);
+        }
+    }
+
+    async fn apply_retention(&self) {
+        let before = now_millis() - (self.retention_secs as i64) * 1000;
+        match self.repo.purge(before, self.max_rows).await {
+            Ok(0) => {}
+            Ok(n) => info!("Purged {} rssi events", n),
+            Err(e) => warn!("Failed to purge rssi events: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        di::Deps,
+        infrastructure::{migration::migrate, DbProvider, DbProviderParameters},
+    };
+    use sea_orm::Database;
+    use shaku::HasComponent;
+
+    async fn memory_repo() -> Arc<dyn IRssiEventRepo> {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        migrate(&db).await.unwrap();
+        let deps = Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build();
+        deps.resolve()
+    }
+
+    fn event(device: &str, rssi: i16, ts: i64) -> RssiEvent {
+        RssiEvent::new(device.to_string(), "phone".to_string(), rssi, ts)
+    }
+
+    fn config(batch_size: usize) -> AlgoConfig {
+        AlgoConfig {
+            batch_size,
+            ..Default::default()
+        }
+    }
+
+    async fn count(repo: &Arc<dyn IRssiEventRepo>) -> usize {
+        repo.load_range("a", 0, i64::MAX).await.unwrap().len()
+    }
+
+    #[tokio::test]
+    async fn test_flush_on_batch_and_shutdown() {
+        let repo = memory_repo().await;
+        let (_config_tx, config_rx) = watch::channel(config(3));
+        let (tx, rx) = mpsc::channel(16);
+        let task = tokio::spawn(RssiEventWriter::new(repo.clone(), &config(3)).run(rx, config_rx));
+
+        for ts in 0..4 {
+            tx.send(event("a", -60, ts)).await.unwrap();
+        }
+        // 满一批立即写入，剩余 1 条在关闭时写入
+        drop(tx);
+        task.await.unwrap();
+        assert_eq!(count(&repo).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_flush_on_tick() {
+        let repo = memory_repo().await;
+        let (_config_tx, config_rx) = watch::channel(config(50));
+        let (tx, rx) = mpsc::channel(16);
+        // 数据库读写走真实时钟，缩短刷新间隔而不是暂停时间
+        let mut writer = RssiEventWriter::new(repo.clone(), &config(50));
+        writer.flush_interval = Duration::from_millis(200);
+        tokio::spawn(writer.run(rx, config_rx));
+
+        tx.send(event("a", -60, 1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(count(&repo).await, 0);
+
+        // 不足一批，等到定时刷新后写入，发送端仍未关闭
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(count(&repo).await, 1);
+        drop(tx);
+    }
+}
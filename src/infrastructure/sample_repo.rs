@@ -41,6 +41,7 @@ impl ISampleRepo for SampleRepo {
         Ok(SampleAggregate::new(
             s.id as SampleID,
             s.device,
+            s.model,
             v,
             ModelResult::from(s.predict),
             ModelResult::from(s.actual),
@@ -49,10 +50,13 @@ impl ISampleRepo for SampleRepo {
     }
     async fn save(&self, aggregate: SampleAggregate) -> AppResult<()> {
         let conn = self.db_provier.get_connection();
+        let sample =
+            serde_json::to_string(&aggregate.data).map_err(|e| AppError::InvalidData { source: e })?;
         let mut m = t_sample::ActiveModel {
             id: ActiveValue::Set(aggregate.id as i32),
             device: ActiveValue::Set(aggregate.device),
-            sample: ActiveValue::Set(serde_json::to_string(&aggregate.data).unwrap()),
+            model: ActiveValue::Set(aggregate.model),
+            sample: ActiveValue::Set(sample),
             predict: ActiveValue::Set(aggregate.predict.into()),
             actual: ActiveValue::Set(aggregate.actual.into()),
             ..Default::default()
@@ -65,3 +69,124 @@ impl ISampleRepo for SampleRepo {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        di::Deps,
+        infrastructure::{migration::migrate, DbProviderParameters},
+    };
+    use sea_orm::{ConnectionTrait, Database, DatabaseConnection};
+    use shaku::HasComponent;
+
+    const ALL: [ModelResult; 5] = [
+        ModelResult::Stationary,
+        ModelResult::MovingAway,
+        ModelResult::MovingCloser,
+        ModelResult::Unknown,
+        ModelResult::Unlabeled,
+    ];
+
+    fn build_repo(db: DatabaseConnection) -> Arc<dyn ISampleRepo> {
+        let deps = Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build();
+        deps.resolve()
+    }
+
+    async fn memory_db() -> DatabaseConnection {
+        Database::connect("sqlite::memory:").await.unwrap()
+    }
+
+    fn sample(predict: ModelResult, actual: ModelResult) -> SampleAggregate {
+        SampleAggregate::new(
+            0,
+            "dev".to_string(),
+            "model".to_string(),
+            vec![-50.0; 9],
+            predict,
+            actual,
+            "".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_all_states() {
+        let db = memory_db().await;
+        migrate(&db).await.unwrap();
+        let repo = build_repo(db);
+
+        let mut id = 0;
+        for predict in ALL {
+            for actual in ALL {
+                repo.save(sample(predict, actual)).await.unwrap();
+                id += 1;
+                let s = repo.load(id).await.unwrap();
+                assert_eq!(s.predict, predict);
+                assert_eq!(s.actual, actual);
+                assert_eq!(s.data, vec![-50.0; 9]);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_label_round_trip() {
+        let db = memory_db().await;
+        migrate(&db).await.unwrap();
+        let repo = build_repo(db);
+
+        repo.save(sample(ModelResult::MovingCloser, ModelResult::Unlabeled))
+            .await
+            .unwrap();
+        let mut s = repo.load(1).await.unwrap();
+        assert_eq!(s.actual, ModelResult::Unlabeled);
+        assert!(!s.is_correct());
+
+        s.change_actual(ModelResult::MovingCloser);
+        repo.save(s).await.unwrap();
+        let s = repo.load(1).await.unwrap();
+        assert_eq!(s.actual, ModelResult::MovingCloser);
+        assert!(s.is_correct());
+    }
+
+    #[tokio::test]
+    async fn test_migration_fixes_legacy_rows() {
+        let db = memory_db().await;
+        // 模拟旧版本数据库：未标注行的 actual 被写成 3，模型未知的 predict 被写成 -1
+        db.execute_unprepared(include_str!("../../migrations/0001_init.sql"))
+            .await
+            .unwrap();
+        db.execute_unprepared(include_str!("../../migrations/0002_sample_model.sql"))
+            .await
+            .unwrap();
+        db.execute_unprepared(
+            r#"
+                PRAGMA user_version = 2;
+                INSERT INTO t_sample (device, sample, predict, actual) VALUES ('dev', '[]', 3, 3);
+                INSERT INTO t_sample (device, sample, predict, actual) VALUES ('dev', '[]', 0, 0);
+                INSERT INTO t_sample (device, sample) VALUES ('dev', '[]');
+                INSERT INTO t_sample (device, sample, predict, actual) VALUES ('dev', '[]', -1, 1);
+            "#,
+        )
+        .await
+        .unwrap();
+
+        migrate(&db).await.unwrap();
+        let repo = build_repo(db);
+
+        let s = repo.load(1).await.unwrap();
+        assert_eq!(s.predict, ModelResult::Unknown);
+        assert_eq!(s.actual, ModelResult::Unlabeled);
+        let s = repo.load(2).await.unwrap();
+        assert_eq!(s.predict, ModelResult::Stationary);
+        assert_eq!(s.actual, ModelResult::Stationary);
+        let s = repo.load(3).await.unwrap();
+        assert_eq!(s.predict, ModelResult::Unknown);
+        assert_eq!(s.actual, ModelResult::Unlabeled);
+        // 旧版本的 DetectionState::Unknown 预测
+        let s = repo.load(4).await.unwrap();
+        assert_eq!(s.predict, ModelResult::Unknown);
+        assert_eq!(s.actual, ModelResult::MovingAway);
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use sea_orm::{sea_query::OnConflict, ActiveValue, EntityTrait, QueryOrder};
+use shaku::Component;
+
+use crate::{
+    domain::{
+        entity::trusted_device::TrustedDevice, repo::trusted_device_repo::ITrustedDeviceRepo,
+    },
+    errors::{AppError, AppResult},
+};
+
+use super::{
+    model::t_trusted_device::{self, Entity as TTrustedDeviceEntity},
+    IDbProvider,
+};
+
+#[derive(Component)]
+#[shaku(interface = ITrustedDeviceRepo)]
+pub struct TrustedDeviceRepo {
+    #[shaku(inject)]
+    db_provider: Arc<dyn IDbProvider>,
+}
+
+#[async_trait::async_trait]
+impl ITrustedDeviceRepo for TrustedDeviceRepo {
+    async fn list(&self) -> AppResult<Vec<TrustedDevice>> {
+        let conn = self.db_provider.get_connection();
+        let rows = TTrustedDeviceEntity::find()
+            .order_by_asc(t_trusted_device::Column::CreatedAt)
+            .all(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(rows.into_iter().map(to_entity).collect())
+    }
+
+    async fn get(&self, fingerprint: &str) -> AppResult<Option<TrustedDevice>> {
+        let conn = self.db_provider.get_connection();
+        let row = TTrustedDeviceEntity::find_by_id(fingerprint.to_string())
+            .one(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(row.map(to_entity))
+    }
+
+    async fn add(&self, device: TrustedDevice) -> AppResult<()> {
+        let conn = self.db_provider.get_connection();
+        let mut update = vec![t_trusted_device::Column::Name];
+        if !device.address.is_empty() {
+            update.push(t_trusted_device::Column::Address);
+        }
+        let model = t_trusted_device::ActiveModel {
+            fingerprint: ActiveValue::Set(device.fingerprint),
+            name: ActiveValue::Set(device.name),
+            created_at: ActiveValue::NotSet,
+            address: ActiveValue::Set(device.address),
+        };
+        TTrustedDeviceEntity::insert(model)
+            .on_conflict(
+                OnConflict::column(t_trusted_device::Column::Fingerprint)
+                    .update_columns(update)
+                    .to_owned(),
+            )
+            .exec(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(())
+    }
+
+    async fn remove(&self, fingerprint: &str) -> AppResult<bool> {
+        let conn = self.db_provider.get_connection();
+        let res = TTrustedDeviceEntity::delete_by_id(fingerprint.to_string())
+            .exec(conn.as_ref())
+            .await
+            .map_err(|e| AppError::DbError { source: e })?;
+        Ok(res.rows_affected > 0)
+    }
+}
+
+fn to_entity(m: t_trusted_device::Model) -> TrustedDevice {
+    TrustedDevice {
+        fingerprint: m.fingerprint,
+        name: m.name,
+        created_at: m.created_at,
+        address: m.address,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        di::Deps,
+        infrastructure::{migration::migrate, DbProvider, DbProviderParameters},
+    };
+    use sea_orm::Database;
+    use shaku::HasComponent;
+
+    #[tokio::test]
+    async fn test_trust_and_untrust() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        migrate(&db).await.unwrap();
+        let deps = Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build();
+        let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+
+        repo.add(TrustedDevice::new("aa".into(), "phone".into())).await.unwrap();
+        repo.add(TrustedDevice::new("aa".into(), "iPhone".into())).await.unwrap();
+        let list = repo.list().await.unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].name, "iPhone");
+
+        assert!(repo.remove("aa").await.unwrap());
+        assert!(!repo.remove("aa").await.unwrap());
+        assert!(repo.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_address_binding() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        migrate(&db).await.unwrap();
+        let deps = Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build();
+        let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+
+        let device = TrustedDevice::new("aa".into(), "iPhone".into());
+        repo.add(device.clone().with_address("AA:BB:CC:DD:EE:FF".into()))
+            .await
+            .unwrap();
+        // 未扫描到设备时重新添加不清除已绑定的地址
+        repo.add(device.clone()).await.unwrap();
+        let bound = repo.get("aa").await.unwrap().unwrap();
+        assert_eq!(bound.address, "AA:BB:CC:DD:EE:FF");
+
+        repo.add(device.with_address("11:22:33:44:55:66".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get("aa").await.unwrap().unwrap().address,
+            "11:22:33:44:55:66"
+        );
+        assert_eq!(repo.get("bb").await.unwrap(), None);
+    }
+}
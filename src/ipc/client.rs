@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader, Lines},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+};
+
+use crate::{
+    ble::adapter::{AdapterInfo, AdapterPreference},
+    domain::value_objects::ModelResult,
+    errors::{AppError, AppResult},
+    runtime::{RuntimeEvent, RuntimeStatus},
+};
+
+use super::{
+    default_socket_path,
+    protocol::{Request, Response},
+};
+
+/// 运行中检测器的控制客户端，供 CLI 与界面使用
+pub struct IpcClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+}
+
+impl IpcClient {
+    pub async fn connect(path: impl AsRef<Path>) -> AppResult<Self> {
+        let stream = UnixStream::connect(path)
+            .await
+            .map_err(|e| AppError::IoError { source: e })?;
+        let (reader, writer) = stream.into_split();
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+            writer,
+        })
+    }
+
+    pub async fn connect_default() -> AppResult<Self> {
+        Self::connect(default_socket_path()).await
+    }
+
+    pub async fn request(&mut self, req: &Request) -> AppResult<Response> {
+        let mut line = serde_json::to_vec(req).map_err(|e| AppError::InvalidData { source: e })?;
+        line.push(b'\n');
+        self.writer
+            .write_all(&line)
+            .await
+            .map_err(|e| AppError::IoError { source: e })?;
+        match self.read().await? {
+            Some(Response::Error { message }) => Err(AppError::ProcessingError { r: message }),
+            Some(resp) => Ok(resp),
+            None => Err(AppError::ProcessingError {
+                r: "connection closed".to_string(),
+            }),
+        }
+    }
+
+    pub async fn status(&mut self) -> AppResult<RuntimeStatus> {
+        match self.request(&Request::Status).await? {
+            Response::Status(status) => Ok(status),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn pause(&mut self) -> AppResult<()> {
+        self.expect_ok(&Request::Pause).await
+    }
+
+    pub async fn resume(&mut self) -> AppResult<()> {
+        self.expect_ok(&Request::Resume).await
+    }
+
+    /// `minutes` 为空时暂停到下次解锁
+    pub async fn snooze(&mut self, minutes: Option<u64>) -> AppResult<()> {
+        self.expect_ok(&Request::Snooze {
+            minutes,
+            until_unlock: minutes.is_none(),
+        })
+        .await
+    }
+
+    pub async fn set_target(&mut self, target: String) -> AppResult<()> {
+        self.expect_ok(&Request::SetTarget { target }).await
+    }
+
+    pub async fn adapters(&mut self) -> AppResult<(Vec<AdapterInfo>, AdapterPreference)> {
+        match self.request(&Request::Adapters).await? {
+            Response::Adapters {
+                adapters,
+                preference,
+            } => Ok((adapters, preference)),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn set_adapter(&mut self, preference: AdapterPreference) -> AppResult<()> {
+        self.expect_ok(&Request::SetAdapter { preference }).await
+    }
+
+    /// 返回被标注的样本 id
+    pub async fn label(&mut self, id: Option<i32>, label: ModelResult) -> AppResult<i32> {
+        match self.request(&Request::Label { id, label }).await? {
+            Response::Labeled { id } => Ok(id),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// 订阅事件流，之后该连接不能再发送其它请求
+    pub async fn subscribe(mut self) -> AppResult<EventStream> {
+        self.expect_ok(&Request::Subscribe).await?;
+        Ok(EventStream { client: self })
+    }
+
+    async fn expect_ok(&mut self, req: &Request) -> AppResult<()> {
+        match self.request(req).await? {
+            Response::Ok => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    async fn read(&mut self) -> AppResult<Option<Response>> {
+        let Some(line) = self
+            .lines
+            .next_line()
+            .await
+            .map_err(|e| AppError::IoError { source: e })?
+        else {
+            return Ok(None);
+        };
+        serde_json::from_str(&line)
+            .map(Some)
+            .map_err(|e| AppError::InvalidData { source: e })
+    }
+}
+
+pub struct EventStream {
+    client: IpcClient,
+}
+
+impl EventStream {
+    /// 连接关闭时返回 `None`
+    pub async fn next(&mut self) -> AppResult<Option<RuntimeEvent>> {
+        loop {
+            match self.client.read().await? {
+                Some(Response::Event { event }) => return Ok(Some(event)),
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+fn unexpected(resp: Response) -> AppError {
+    AppError::ProcessingError {
+        r: format!("unexpected response: {:?}", resp),
+    }
+}
@@ -0,0 +1,27 @@
+//! 本地控制接口
+//!
+//! 运行中的检测器在 Unix socket 上监听，请求与响应都是单行 JSON（JSON Lines），
+//! 脚本可直接用 `socat` 调用，Rust 侧使用 `client::IpcClient`：
+//!
+//! ```sh
+//! echo '{"cmd":"status"}' | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/blue-lock.sock
+//! ```
+
+use std::path::PathBuf;
+
+pub mod protocol;
+#[cfg(unix)]
+pub mod client;
+#[cfg(unix)]
+pub mod server;
+
+/// `$XDG_RUNTIME_DIR/blue-lock.sock`，没有时放在临时目录并带上用户名
+pub fn default_socket_path() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("blue-lock.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+            std::env::temp_dir().join(format!("blue-lock-{}.sock", user))
+        }
+    }
+}
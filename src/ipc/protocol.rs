@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ble::adapter::{AdapterInfo, AdapterPreference},
+    domain::value_objects::ModelResult,
+    runtime::{RuntimeEvent, RuntimeStatus},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    Status,
+    /// 暂停自动锁屏，直到 `Resume`
+    Pause,
+    Resume,
+    /// 暂停 `minutes` 分钟，或直到下次解锁会话
+    Snooze {
+        #[serde(default)]
+        minutes: Option<u64>,
+        #[serde(default)]
+        until_unlock: bool,
+    },
+    SetTarget { target: String },
+    /// 列出适配器及当前偏好
+    Adapters,
+    SetAdapter { preference: AdapterPreference },
+    /// 标注样本，`id` 为空时标注目标设备最新的一条
+    Label {
+        #[serde(default)]
+        id: Option<i32>,
+        label: ModelResult,
+    },
+    /// 订阅后该连接只推送 `Response::Event`
+    Subscribe,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Status(RuntimeStatus),
+    Labeled { id: i32 },
+    Adapters {
+        adapters: Vec<AdapterInfo>,
+        preference: AdapterPreference,
+    },
+    Event { event: RuntimeEvent },
+    Error { message: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_format() {
+        let req: Request = serde_json::from_str(r#"{"cmd":"status"}"#).unwrap();
+        assert_eq!(req, Request::Status);
+        let req: Request = serde_json::from_str(r#"{"cmd":"snooze","minutes":15}"#).unwrap();
+        assert_eq!(
+            req,
+            Request::Snooze {
+                minutes: Some(15),
+                until_unlock: false
+            }
+        );
+        let req: Request = serde_json::from_str(r#"{"cmd":"label","label":2}"#).unwrap();
+        assert_eq!(
+            req,
+            Request::Label {
+                id: None,
+                label: ModelResult::MovingCloser
+            }
+        );
+        let req = Request::SetTarget {
+            target: "5d964bc66dbc1093".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"cmd":"set_target","target":"5d964bc66dbc1093"}"#
+        );
+
+        let resp = Response::Event {
+            event: RuntimeEvent::Prediction {
+                ts: 1,
+                result: ModelResult::MovingAway,
+            },
+        };
+        let line = serde_json::to_string(&resp).unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"event","event":{"type":"prediction","ts":1,"result":1}}"#
+        );
+        assert!(matches!(
+            serde_json::from_str::<Response>(&line).unwrap(),
+            Response::Event {
+                event: RuntimeEvent::Prediction { ts: 1, .. }
+            }
+        ));
+    }
+}
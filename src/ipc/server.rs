@@ -0,0 +1,307 @@
+use std::{
+    os::unix::fs::{DirBuilderExt as _, PermissionsExt as _},
+    path::Path,
+    sync::{Arc, Weak},
+};
+
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader},
+    net::{unix::OwnedWriteHalf, UnixListener, UnixStream},
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    domain::entity::rssi_event::now_millis,
+    runtime::{Runtime, RuntimeEvent},
+    snooze::Snooze,
+};
+
+use super::protocol::{Request, Response};
+
+/// 控制请求的处理方，由 `Runtime` 实现
+#[async_trait::async_trait]
+pub trait IpcHandler: Send + Sync + 'static {
+    async fn dispatch(&self, req: Request) -> Response;
+
+    /// `Subscribe` 请求的事件流
+    fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent>;
+}
+
+/// 在 `path` 上监听控制请求，已有实例在监听时返回 `AddrInUse`；`token` 取消后关闭连接并删除 socket
+pub fn serve<H: IpcHandler>(
+    runtime: Arc<H>,
+    path: &Path,
+    token: CancellationToken,
+) -> std::io::Result<JoinHandle<()>> {
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                "another instance is running",
+            ));
+        }
+        // 上次异常退出留下的 socket
+        std::fs::remove_file(path)?;
+    }
+    let listener = bind_private(path)?;
+    info!("IPC listening on {}", path.display());
+
+    // 持有弱引用，避免连接阻止运行时释放
+    let runtime = Arc::downgrade(&runtime);
+    let path = path.to_path_buf();
+    Ok(tokio::spawn(async move {
+        loop {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = token.cancelled() => break,
+            };
+            match accepted {
+                Ok((stream, _)) => {
+                    let runtime = runtime.clone();
+                    let token = token.clone();
+                    tokio::spawn(async move {
+                        tokio::select! {
+                            res = handle(runtime, stream) => if let Err(e) = res {
+                                warn!("IPC connection error: {}", e);
+                            },
+                            _ = token.cancelled() => {}
+                        }
+                    });
+                }
+                Err(e) => {
+                    warn!("IPC accept failed: {}", e);
+                    break;
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+        info!("IPC server stopped");
+    }))
+}
+
+/// 只允许当前用户访问的 socket：先在 0700 的临时目录中绑定并改为 0600，再移到 `path`，
+/// 避免 socket 在改权限前以默认 umask 暴露
+fn bind_private(path: &Path) -> std::io::Result<UnixListener> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let private = dir.join(format!(
+        ".blue-lock-{}-{}",
+        std::process::id(),
+        now_millis()
+    ));
+    std::fs::DirBuilder::new().mode(0o700).create(&private)?;
+    let staged = private.join("sock");
+    let res = UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&private);
+    res
+}
+
+async fn handle<H: IpcHandler>(runtime: Weak<H>, stream: UnixStream) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(rt) = runtime.upgrade() else {
+            break;
+        };
+        let req = match serde_json::from_str::<Request>(&line) {
+            Ok(req) => req,
+            Err(e) => {
+                let message = format!("invalid request: {}", e);
+                write_line(&mut writer, &Response::Error { message }).await?;
+                continue;
+            }
+        };
+        if req == Request::Subscribe {
+            let rx = rt.subscribe();
+            drop(rt);
+            write_line(&mut writer, &Response::Ok).await?;
+            return stream_events(rx, writer).await;
+        }
+        let resp = rt.dispatch(req).await;
+        write_line(&mut writer, &resp).await?;
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl IpcHandler for Runtime {
+    async fn dispatch(&self, req: Request) -> Response {
+        match req {
+            Request::Status => Response::Status(self.status().await),
+            Request::Pause => {
+                self.set_snooze(Some(Snooze::Indefinite));
+                Response::Ok
+            }
+            Request::Resume => {
+                self.set_snooze(None);
+                Response::Ok
+            }
+            Request::Snooze {
+                minutes,
+                until_unlock,
+            } => match (minutes, until_unlock) {
+                (_, true) => {
+                    self.set_snooze(Some(Snooze::UntilUnlock));
+                    Response::Ok
+                }
+                (Some(m), false) if m > 0 => {
+                    self.set_snooze(Some(Snooze::for_minutes(m)));
+                    Response::Ok
+                }
+                _ => Response::Error {
+                    message: "minutes or until_unlock required".to_string(),
+                },
+            },
+            Request::SetTarget { target } => {
+                self.set_target(target);
+                Response::Ok
+            }
+            Request::Adapters => {
+                let adapters = self.adapters();
+                Response::Adapters {
+                    adapters: adapters.list(),
+                    preference: adapters.preference(),
+                }
+            }
+            Request::SetAdapter { preference } => {
+                self.adapters().set_preference(preference);
+                Response::Ok
+            }
+            Request::Label { id, label } => match self.label_sample(id, label).await {
+                Ok(id) => Response::Labeled { id },
+                Err(e) => Response::Error {
+                    message: e.to_string(),
+                },
+            },
+            // 在 `handle` 中处理
+            Request::Subscribe => Response::Ok,
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+        Runtime::subscribe(self)
+    }
+}
+
+async fn stream_events(
+    mut rx: broadcast::Receiver<RuntimeEvent>,
+    mut writer: OwnedWriteHalf,
+) -> std::io::Result<()> {
+    loop {
+        match rx.recv().await {
+            Ok(event) => write_line(&mut writer, &Response::Event { event }).await?,
+            Err(RecvError::Lagged(n)) => warn!("IPC subscriber lagged, {} events dropped", n),
+            Err(RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn write_line(writer: &mut OwnedWriteHalf, resp: &Response) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(resp).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::fs::FileTypeExt as _, sync::Mutex};
+
+    use super::*;
+    use crate::{domain::value_objects::ModelResult, ipc::client::IpcClient};
+
+    /// 记录收到的请求，不依赖蓝牙与数据库
+    struct FakeHandler {
+        requests: Mutex<Vec<Request>>,
+        events: broadcast::Sender<RuntimeEvent>,
+    }
+
+    #[async_trait::async_trait]
+    impl IpcHandler for FakeHandler {
+        async fn dispatch(&self, req: Request) -> Response {
+            self.requests.lock().unwrap().push(req.clone());
+            match req {
+                Request::Label { .. } => Response::Labeled { id: 7 },
+                Request::Snooze {
+                    minutes: Some(0), ..
+                } => Response::Error {
+                    message: "minutes or until_unlock required".to_string(),
+                },
+                _ => Response::Ok,
+            }
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+            self.events.subscribe()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("blue-lock-ipc-{}", now_millis()));
+        let path = dir.join("blue-lock.sock");
+        let handler = Arc::new(FakeHandler {
+            requests: Mutex::new(Vec::new()),
+            events: broadcast::channel(16).0,
+        });
+        let token = CancellationToken::new();
+        let server = serve(handler.clone(), &path, token.clone()).unwrap();
+
+        let meta = std::fs::metadata(&path).unwrap();
+        assert!(meta.file_type().is_socket());
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+        // 临时目录已清理，只剩 socket
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let err = serve(handler.clone(), &path, token.clone()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        let mut client = IpcClient::connect(&path).await.unwrap();
+        client.pause().await.unwrap();
+        client.set_target("fp".to_string()).await.unwrap();
+        assert_eq!(
+            client.label(None, ModelResult::MovingCloser).await.unwrap(),
+            7
+        );
+        assert!(client.snooze(Some(0)).await.is_err());
+        assert_eq!(
+            *handler.requests.lock().unwrap(),
+            vec![
+                Request::Pause,
+                Request::SetTarget {
+                    target: "fp".to_string()
+                },
+                Request::Label {
+                    id: None,
+                    label: ModelResult::MovingCloser
+                },
+                Request::Snooze {
+                    minutes: Some(0),
+                    until_unlock: false
+                },
+            ]
+        );
+
+        let mut events = client.subscribe().await.unwrap();
+        handler.events.send(RuntimeEvent::ConfigChanged).unwrap();
+        assert!(matches!(
+            events.next().await.unwrap(),
+            Some(RuntimeEvent::ConfigChanged)
+        ));
+
+        token.cancel();
+        server.await.unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
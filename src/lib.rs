@@ -7,4 +7,12 @@ pub mod errors;
 pub mod di;
 pub mod application;
 pub mod domain;
-pub mod infrastructure;
\ No newline at end of file
+pub mod infrastructure;
+pub mod runtime;
+pub mod ipc;
+pub mod snooze;
+pub mod supervisor;
+pub mod i18n;
+pub mod notify;
+pub mod publish;
+pub mod unlock;
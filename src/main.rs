@@ -1,88 +1,228 @@
 #![allow(non_snake_case)]
 
-use std::sync::Arc;
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
 
-use btleplug::{
-    api::{Central, Manager as _, ScanFilter},
-    platform::Manager,
+use dioxus::{
+    desktop::{
+        tao::event::{Event, WindowEvent},
+        use_wry_event_handler,
+    },
+    prelude::*,
 };
-use dioxus::prelude::*;
 use dioxus_demo::{
-    ble::presence_detector::PresenceDetector,
+    ble::{adapter::AdapterState, scanner::DeviceScanner},
+    components::{rssi_chart::RssiHistory, tray::use_tray},
     di::Deps,
-    errors::AppResult,
-    infrastructure::{DbProvider, DbProviderParameters},
+    dto::detection::SignalConfig,
+    errors::{AppError, AppResult, UserMessage},
+    i18n::{load_locale, use_locale, Localize as _},
+    infrastructure::log_layer::{LogBuffer, LogLayer},
     routes::Route,
+    runtime::{Runtime, RuntimeConfig, RuntimeEvent},
+    t,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use tracing_subscriber::{
+    filter::LevelFilter, layer::SubscriberExt as _, util::SubscriberInitExt as _, EnvFilter,
+    Layer as _,
 };
-use sea_orm::Database;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+
+/// 启动参数中的配置，供界面读取
+static CONFIG: OnceLock<RuntimeConfig> = OnceLock::new();
+/// 配置读取失败时在界面上提示，而不是直接退出
+static CONFIG_ERROR: OnceLock<UserMessage> = OnceLock::new();
 
 fn main() -> AppResult<()> {
     // Init logger
     // dioxus_logger::init(Level::INFO).expect("failed to init logger");
 
-    tracing_subscriber::fmt()
-        .json() // 启用 JSON 格式
-        .with_ansi(false)
-        .with_env_filter(EnvFilter::new("info")) // 日志级别
+    // 控制台日志级别可用 RUST_LOG 覆盖，日志页面按最低可选的 TRACE 收集
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .json() // 启用 JSON 格式
+                .with_ansi(false)
+                .with_filter(env_filter),
+        )
+        .with(LogLayer::new(LogBuffer::global().clone()).with_filter(LevelFilter::TRACE)) // 日志页面
         .init();
+
+    // blue-lock [--headless] [--config <file>]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let headless = args.iter().any(|a| a == "--headless");
+    let config_path = args
+        .iter()
+        .position(|a| a == "--config")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+    let config = RuntimeConfig::load(config_path.as_deref());
+
+    if headless {
+        return run_headless(config?);
+    }
+    match config {
+        Ok(config) => {
+            let _ = CONFIG.set(config);
+        }
+        Err(e) => {
+            error!("Failed to load config: {}", e);
+            let _ = CONFIG_ERROR.set(e.user_message());
+        }
+    }
     launch(App);
     Ok(())
 }
 
+/// 无界面运行，收到 Ctrl-C 或 SIGTERM 后退出
+fn run_headless(config: RuntimeConfig) -> AppResult<()> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::IoError { source: e })?
+        .block_on(async {
+            let runtime = Runtime::start(config).await?;
+            info!("Running headless");
+            shutdown_signal().await;
+            runtime.shutdown().await;
+            Ok(())
+        })
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// 在事件循环线程中同步等待运行时关闭
+fn shutdown_blocking(runtime: Arc<Runtime>) {
+    let handle = tokio::runtime::Handle::try_current().ok();
+    // 换一个线程阻塞，避免在异步上下文中直接 block_on
+    let joined = std::thread::spawn(move || match handle {
+        Some(handle) => handle.block_on(runtime.shutdown()),
+        None => match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt.block_on(runtime.shutdown()),
+            Err(e) => warn!("Failed to create shutdown runtime: {}", e),
+        },
+    })
+    .join();
+    if joined.is_err() {
+        warn!("Runtime shutdown panicked");
+    }
+}
+
 #[component]
 fn App() -> Element {
-    let samples_signal = use_signal(|| Vec::<f32>::new());
+    let mut samples_signal = use_signal(Vec::<f32>::new);
     let mut deps = use_signal::<Option<Arc<Deps>>>(|| None);
+    let mut history = use_signal(RssiHistory::default);
+    let mut scanner = use_signal::<Option<Arc<DeviceScanner>>>(|| None);
+    let mut runtime = use_signal::<Option<Arc<Runtime>>>(|| None);
+    let mut algo_config = use_signal(|| CONFIG.get().cloned().unwrap_or_default().algo);
+    let signal_config = use_signal(SignalConfig::default);
+    let mut adapter_state = use_signal(AdapterState::default);
+    let mut startup_error = use_signal(|| CONFIG_ERROR.get().cloned());
+    let locale = use_signal(load_locale);
 
+    // 界面只是运行时的一个订阅者
+    use_future(move || async move {
+        if startup_error.peek().is_some() {
+            return;
+        }
+        let config = CONFIG.get().cloned().unwrap_or_default();
+        let rt = match Runtime::start(config).await {
+            Ok(rt) => rt,
+            Err(e) => {
+                error!("Failed to start runtime: {}", e);
+                startup_error.set(Some(e.user_message()));
+                return;
+            }
+        };
+        deps.set(Some(rt.deps()));
+        scanner.set(Some(rt.scanner()));
+        let mut rx = rt.subscribe();
+        adapter_state.set(rt.adapter_state());
+        runtime.set(Some(rt.clone()));
 
-    use_future({
-        let mut signal = samples_signal.clone();
-        // 不要 let sample_rx = ... 再 move！
-        move || async move {
-            let db = Database::connect("sqlite:sample.db")
-                .await
-                .expect("Database connection failed");
-
-            let dps = Deps::builder()
-                .with_component_parameters::<DbProvider>(DbProviderParameters {
-                    conn: Arc::new(db),
-                })
-                .build();
-
-            deps.set(Some(Arc::new(dps)));
-
-            let (sample_tx, mut sample_rx) = tokio::sync::mpsc::channel::<Vec<f32>>(100);
-            let target = "5d964bc66dbc1093";
-            let dector = PresenceDetector::new(Default::default(), sample_tx)
-                .await
-                .unwrap();
-            let manager = Manager::new().await.unwrap();
-            let adapter = manager
-                .adapters()
-                .await
-                .unwrap()
-                .into_iter()
-                .next()
-                .unwrap();
-            tokio::spawn(async move {
-                adapter.start_scan(ScanFilter::default()).await.unwrap();
-                dector.start_detection(adapter, target).await.unwrap();
-            });
-
-            while let Some(samples) = sample_rx.recv().await {
-                info!("received samples: {:?}", samples);
-                signal.set(samples);
+        loop {
+            match rx.recv().await {
+                Ok(RuntimeEvent::Rssi(event)) => history.write().push_rssi(&event),
+                Ok(RuntimeEvent::Window { data }) => samples_signal.set(data),
+                Ok(RuntimeEvent::Prediction { ts, result }) => {
+                    history.write().push_prediction(ts, result)
+                }
+                Ok(RuntimeEvent::Lock { ts, locked, .. }) => history.write().push_lock(ts, locked),
+                Ok(RuntimeEvent::Adapter { state }) => adapter_state.set(state),
+                Ok(RuntimeEvent::ConfigChanged) => algo_config.set(rt.config().algo),
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
             }
         }
     });
 
-    use_context_provider(|| samples_signal.clone());
-    use_context_provider(|| deps.clone());
+    // 关闭窗口时停止扫描并等待后台任务退出，否则适配器会一直处于扫描状态
+    use_wry_event_handler(move |event, _| {
+        if let Event::WindowEvent {
+            event: WindowEvent::CloseRequested | WindowEvent::Destroyed,
+            ..
+        } = event
+        {
+            if let Some(rt) = runtime.take() {
+                shutdown_blocking(rt);
+            }
+        }
+    });
+
+    use_context_provider(|| samples_signal);
+    use_context_provider(|| deps);
+    use_context_provider(|| history);
+    use_context_provider(|| scanner);
+    use_context_provider(|| runtime);
+    use_context_provider(|| algo_config);
+    use_context_provider(|| signal_config);
+    use_context_provider(|| adapter_state);
+    use_context_provider(|| locale);
+    use_tray(runtime, locale);
+
+    if let Some(message) = startup_error.read().clone() {
+        return rsx! {
+            style { {include_str!("../assets/tailwind.css")} }
+            StartupError { message }
+        };
+    }
 
     rsx! {
         style { {include_str!("../assets/tailwind.css")} }
         Router::<Route> {}
     }
 }
+
+/// 启动失败时代替主界面显示
+#[component]
+fn StartupError(message: UserMessage) -> Element {
+    let l = use_locale()();
+    rsx! {
+        div { class: "min-h-screen bg-gradient-to-tr from-blue-50 to-teal-50 flex items-center justify-center",
+            div { class: "bg-white/90 rounded-2xl shadow-xl p-8 max-w-xl",
+                h1 { class: "text-xl font-semibold text-red-700 mb-4", {t!(l, "startup-failed")} }
+                p { class: "text-gray-700 mb-4", {message.localize(l)} }
+                p { class: "text-sm text-gray-500", {t!(l, "startup-failed-hint")} }
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use notify_rust::Timeout;
+
+use crate::errors::{AppError, AppResult};
+
+use super::{Notification, NotificationKind, Notifier};
+
+/// 通过系统通知中心发送（Linux 为 D-Bus，macOS 为通知中心，Windows 为 Toast）
+pub struct DesktopNotifier {
+    app_name: String,
+}
+
+impl Default for DesktopNotifier {
+    fn default() -> Self {
+        Self {
+            app_name: "Blue Lock".to_string(),
+        }
+    }
+}
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, notification: &Notification) -> AppResult<()> {
+        // 警告类通知停留更久
+        let timeout = match notification.kind {
+            NotificationKind::LowConfidence { .. } | NotificationKind::AdapterLost { .. } => {
+                Timeout::Milliseconds(15_000)
+            }
+            _ => Timeout::Default,
+        };
+        notify_rust::Notification::new()
+            .appname(&self.app_name)
+            .summary(&notification.title)
+            .body(&notification.body)
+            .timeout(timeout)
+            .show()
+            .map_err(|e| AppError::Notify { source: e })?;
+        Ok(())
+    }
+}
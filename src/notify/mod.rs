@@ -0,0 +1,373 @@
+//! 桌面通知
+//!
+//! 根据运行时事件判断何时提醒（目标离开/回来、已锁屏、长时间低置信度、蓝牙不可用），
+//! 发送方式由 `Notifier` 决定，测试中使用 `RecordingNotifier` 代替系统通知。
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::{broadcast, broadcast::error::RecvError, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    ble::adapter::AdapterState,
+    domain::value_objects::ModelResult,
+    errors::AppResult,
+    i18n::{load_locale, Locale, Localize as _},
+    runtime::{LockReason, RuntimeConfig, RuntimeEvent},
+    t,
+};
+
+pub mod desktop;
+
+/// 连续无法判断这么久后提醒
+const LOW_CONFIDENCE_MS: i64 = 5 * 60_000;
+
+/// 需要提醒用户的情况
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationKind {
+    /// 目标设备超时未出现
+    Lost,
+    /// 目标设备离开后重新出现
+    Found,
+    Locked {
+        reason: LockReason,
+    },
+    /// 模型连续输出 `Unknown`
+    LowConfidence {
+        minutes: i64,
+    },
+    /// 蓝牙关闭或适配器拔出
+    AdapterLost {
+        state: AdapterState,
+    },
+    /// 后台任务异常退出，正在重启
+    TaskFailed {
+        task: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, locale: Locale) -> Self {
+        let (title, body) = match &kind {
+            NotificationKind::Lost => (
+                t!(locale, "notify-lost-title"),
+                t!(locale, "notify-lost-body"),
+            ),
+            NotificationKind::Found => (
+                t!(locale, "notify-found-title"),
+                t!(locale, "notify-found-body"),
+            ),
+            NotificationKind::Locked { reason } => {
+                (t!(locale, "notify-locked-title"), reason.localize(locale))
+            }
+            NotificationKind::LowConfidence { minutes } => (
+                t!(locale, "notify-low-confidence-title"),
+                t!(locale, "notify-low-confidence-body", minutes = *minutes),
+            ),
+            NotificationKind::AdapterLost { state } => (
+                t!(locale, "notify-adapter-title"),
+                t!(
+                    locale,
+                    "notify-adapter-body",
+                    state = state.localize(locale)
+                ),
+            ),
+            NotificationKind::TaskFailed { task } => (
+                t!(locale, "notify-task-failed-title"),
+                t!(locale, "notify-task-failed-body", task = task.clone()),
+            ),
+        };
+        Self { kind, title, body }
+    }
+}
+
+/// 通知的发送方式
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: &Notification) -> AppResult<()>;
+}
+
+/// 只记录不发送，用于测试
+#[derive(Default)]
+pub struct RecordingNotifier {
+    sent: Mutex<Vec<Notification>>,
+}
+
+impl RecordingNotifier {
+    pub fn sent(&self) -> Vec<Notification> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl Notifier for RecordingNotifier {
+    fn notify(&self, notification: &Notification) -> AppResult<()> {
+        self.sent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(notification.clone());
+        Ok(())
+    }
+}
+
+/// 由事件流判断是否需要提醒，同一状态只提醒一次
+#[derive(Debug, Default)]
+pub struct NotificationRules {
+    present: Option<bool>,
+    unknown_since: Option<i64>,
+    warned_low_confidence: bool,
+    adapter: Option<AdapterState>,
+    /// 已提醒过的任务，反复重启时只提醒一次
+    failed: HashSet<String>,
+}
+
+impl NotificationRules {
+    pub fn on_event(&mut self, event: &RuntimeEvent) -> Option<NotificationKind> {
+        match event {
+            RuntimeEvent::Presence { present, .. } => {
+                let previous = self.present.replace(*present);
+                match (previous, *present) {
+                    (Some(false), true) => Some(NotificationKind::Found),
+                    (p, false) if p != Some(false) => Some(NotificationKind::Lost),
+                    _ => None,
+                }
+            }
+            RuntimeEvent::Lock {
+                locked: true,
+                reason,
+                ..
+            } => Some(NotificationKind::Locked { reason: *reason }),
+            RuntimeEvent::Prediction {
+                ts,
+                result: ModelResult::Unknown,
+            } => {
+                let since = *self.unknown_since.get_or_insert(*ts);
+                if self.warned_low_confidence || ts - since < LOW_CONFIDENCE_MS {
+                    return None;
+                }
+                self.warned_low_confidence = true;
+                Some(NotificationKind::LowConfidence {
+                    minutes: LOW_CONFIDENCE_MS / 60_000,
+                })
+            }
+            RuntimeEvent::Prediction { .. } => {
+                self.unknown_since = None;
+                self.warned_low_confidence = false;
+                None
+            }
+            RuntimeEvent::Adapter { state } => {
+                let previous = self.adapter.replace(*state);
+                (*state != AdapterState::Ready && previous != Some(*state))
+                    .then_some(NotificationKind::AdapterLost { state: *state })
+            }
+            RuntimeEvent::TaskFailed { task, .. } => self
+                .failed
+                .insert(task.clone())
+                .then(|| NotificationKind::TaskFailed { task: task.clone() }),
+            // 切换目标后重新判断
+            RuntimeEvent::TargetChanged { .. } => {
+                *self = Self {
+                    adapter: self.adapter,
+                    failed: std::mem::take(&mut self.failed),
+                    ..Self::default()
+                };
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 订阅运行时事件并发送通知，`RuntimeConfig::notifications` 关闭时只跟踪状态
+pub async fn notify_task(
+    mut rx: broadcast::Receiver<RuntimeEvent>,
+    config: watch::Receiver<RuntimeConfig>,
+    notifier: Arc<dyn Notifier>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    let mut rules = NotificationRules::default();
+    loop {
+        let event = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            event = rx.recv() => match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+        let Some(kind) = rules.on_event(&event) else {
+            continue;
+        };
+        if !config.borrow().notifications {
+            continue;
+        }
+        let notification = Notification::new(kind, load_locale());
+        info!("Notify: {}", notification.title);
+        let notifier = notifier.clone();
+        let res = tokio::task::spawn_blocking(move || notifier.notify(&notification)).await;
+        match res {
+            Ok(Err(e)) => warn!("Failed to send notification: {}", e),
+            Err(e) => warn!("Notification task panicked: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ble::presence_state::TransitionReason;
+
+    fn timeout() -> LockReason {
+        LockReason::Absent {
+            transition: TransitionReason::Quiet {
+                secs: 15,
+                threshold: -74.6,
+            },
+            adapter: None,
+        }
+    }
+
+    fn prediction(ts: i64, result: ModelResult) -> RuntimeEvent {
+        RuntimeEvent::Prediction { ts, result }
+    }
+
+    #[test]
+    fn test_rules() {
+        let mut rules = NotificationRules::default();
+        let presence = |present| RuntimeEvent::Presence { ts: 0, present };
+
+        assert_eq!(rules.on_event(&presence(true)), None);
+        assert_eq!(
+            rules.on_event(&presence(false)),
+            Some(NotificationKind::Lost)
+        );
+        assert_eq!(rules.on_event(&presence(false)), None);
+        assert_eq!(
+            rules.on_event(&presence(true)),
+            Some(NotificationKind::Found)
+        );
+
+        // 5 分钟内全是 Unknown 只提醒一次，出现有效结果后重新计时
+        assert_eq!(rules.on_event(&prediction(0, ModelResult::Unknown)), None);
+        assert_eq!(
+            rules.on_event(&prediction(LOW_CONFIDENCE_MS - 1, ModelResult::Unknown)),
+            None
+        );
+        assert_eq!(
+            rules.on_event(&prediction(LOW_CONFIDENCE_MS, ModelResult::Unknown)),
+            Some(NotificationKind::LowConfidence { minutes: 5 })
+        );
+        assert_eq!(
+            rules.on_event(&prediction(LOW_CONFIDENCE_MS * 2, ModelResult::Unknown)),
+            None
+        );
+        rules.on_event(&prediction(LOW_CONFIDENCE_MS * 3, ModelResult::Stationary));
+        assert_eq!(
+            rules.on_event(&prediction(LOW_CONFIDENCE_MS * 4, ModelResult::Unknown)),
+            None
+        );
+
+        let adapter = |state| RuntimeEvent::Adapter { state };
+        assert_eq!(rules.on_event(&adapter(AdapterState::Ready)), None);
+        assert_eq!(
+            rules.on_event(&adapter(AdapterState::PoweredOff)),
+            Some(NotificationKind::AdapterLost {
+                state: AdapterState::PoweredOff
+            })
+        );
+        assert_eq!(rules.on_event(&adapter(AdapterState::PoweredOff)), None);
+
+        // 反复重启的任务只提醒一次
+        let failed = || RuntimeEvent::TaskFailed {
+            task: "presence".into(),
+            error: "boom".into(),
+        };
+        assert_eq!(
+            rules.on_event(&failed()),
+            Some(NotificationKind::TaskFailed {
+                task: "presence".into()
+            })
+        );
+        assert_eq!(rules.on_event(&failed()), None);
+    }
+
+    #[tokio::test]
+    async fn test_notify_task_with_fake_sink() {
+        let (event_tx, _) = broadcast::channel(16);
+        let (config_tx, config_rx) = watch::channel(RuntimeConfig::default());
+        let notifier = Arc::new(RecordingNotifier::default());
+        let token = CancellationToken::new();
+        let task = tokio::spawn(notify_task(
+            event_tx.subscribe(),
+            config_rx,
+            notifier.clone(),
+            token.clone(),
+        ));
+
+        event_tx
+            .send(RuntimeEvent::Presence {
+                ts: 1,
+                present: false,
+            })
+            .unwrap();
+        event_tx
+            .send(RuntimeEvent::Lock {
+                ts: 2,
+                locked: true,
+                reason: timeout(),
+            })
+            .unwrap();
+        // 关闭通知后不再发送
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        config_tx.send_modify(|c| c.notifications = false);
+        event_tx
+            .send(RuntimeEvent::Presence {
+                ts: 3,
+                present: true,
+            })
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        token.cancel();
+        task.await.unwrap().unwrap();
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].kind, NotificationKind::Lost);
+        assert_eq!(sent[1].kind, NotificationKind::Locked { reason: timeout() });
+    }
+
+    #[test]
+    fn test_locked_reason_localized() {
+        let body =
+            |reason, locale| Notification::new(NotificationKind::Locked { reason }, locale).body;
+        assert_eq!(
+            body(timeout(), Locale::ZhCn),
+            "15 秒未收到不低于离开阈值 -75 dBm 的信号"
+        );
+        assert_eq!(
+            body(timeout(), Locale::EnUs),
+            "No signal at or above the away threshold -75 dBm for 15 s"
+        );
+        let LockReason::Absent { transition, .. } = timeout() else {
+            unreachable!()
+        };
+        let reason = LockReason::Absent {
+            transition,
+            adapter: Some(AdapterState::PoweredOff),
+        };
+        assert_eq!(
+            body(reason, Locale::EnUs),
+            "Bluetooth is off; No signal at or above the away threshold -75 dBm for 15 s"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{Local, TimeZone as _};
+use dioxus::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    ble::{
+        company_ids::{company_name, to_hex},
+        scanner::{decode_device_key, DeviceScanner, GattService, ScanUpdate, ScannedDevice},
+        signal::SignalReading,
+    },
+    dto::detection::SignalConfig,
+    errors::{AppError, UserMessage},
+    i18n::{use_locale, Localize as _},
+    routes::Route,
+    t,
+};
+
+/// 本地时间的年-月-日 时:分:秒
+fn format_time(ts: i64) -> String {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+#[component]
+pub fn DeviceDetail(id: String) -> Element {
+    let scanner: Signal<Option<Arc<DeviceScanner>>> = use_context();
+    let signal_config: Signal<SignalConfig> = use_context();
+    let l = use_locale()();
+    let device_id = use_memo(use_reactive!(|id| decode_device_key(&id).unwrap_or_default()));
+    let mut device = use_signal(|| None::<ScannedDevice>);
+    let mut gatt = use_signal(|| None::<Result<Vec<GattService>, UserMessage>>);
+    let mut connecting = use_signal(|| false);
+
+    // 跟随扫描服务实时刷新
+    use_future(move || async move {
+        let scanner = loop {
+            if let Some(s) = scanner.peek().clone() {
+                break s;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+        let mut rx = scanner.subscribe();
+        device.set(scanner.get(&device_id.peek()));
+        loop {
+            match rx.recv().await {
+                Ok(ScanUpdate::Upsert(d)) if d.id == *device_id.peek() => device.set(Some(d)),
+                Ok(ScanUpdate::Removed(id)) if id == *device_id.peek() => device.set(None),
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => device.set(scanner.get(&device_id.peek())),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let on_connect = move |_| {
+        let Some(scanner) = scanner.peek().clone() else {
+            return;
+        };
+        connecting.set(true);
+        spawn(async move {
+            let id = device_id.peek().clone();
+            let res = scanner
+                .inspect_gatt(&id)
+                .await
+                .map_err(|e| AppError::from(e).user_message());
+            gatt.set(Some(res));
+            connecting.set(false);
+        });
+    };
+
+    let device = device.read();
+    let gatt = gatt.read();
+    let reading = device
+        .as_ref()
+        .and_then(|d| SignalReading::assess(&d.rssi_history, d.tx_power, &signal_config.read()));
+
+    rsx! {
+        div { class: "p-4 bg-gray-100 min-h-screen w-full flex flex-col gap-4",
+            div { class: "flex items-center gap-3",
+                Link { to: Route::Device {}, class: "btn btn-sm btn-ghost", {t!(l, "common-back")} }
+                h1 { class: "text-2xl font-bold truncate",
+                    {device.as_ref().and_then(|d| d.name.clone()).unwrap_or_else(|| t!(l, "device-unknown"))}
+                }
+            }
+
+            match device.as_ref() {
+                None => rsx! {
+                    div { class: "text-gray-400 text-center py-12", {t!(l, "device-out-of-range")} }
+                },
+                Some(d) => rsx! {
+                    // --- 基本信息 ---
+                    div { class: "card bg-base-100 shadow rounded-2xl",
+                        div { class: "card-body p-5 grid grid-cols-2 gap-x-8 gap-y-2 text-sm",
+                            span { class: "text-gray-400", "ID" }
+                            span { class: "font-mono break-all", "{d.id}" }
+                            span { class: "text-gray-400", {t!(l, "device-address")} }
+                            span { class: "font-mono", "{d.address}" }
+                            span { class: "text-gray-400", {t!(l, "device-fingerprint")} }
+                            span { class: "font-mono", {d.fingerprint.clone().unwrap_or_else(|| "-".to_string())} }
+                            span { class: "text-gray-400", "RSSI" }
+                            span { {d.rssi.map(|r| format!("{} dBm", r)).unwrap_or_else(|| "-".to_string())} }
+                            span { class: "text-gray-400", {t!(l, "device-signal-quality")} }
+                            span {
+                                {reading.map(|r| format!("{} {}% {}", r.quality.localize(l), r.percent, r.trend.arrow())).unwrap_or_else(|| "-".to_string())}
+                            }
+                            span { class: "text-gray-400", {t!(l, "device-distance")} }
+                            span { {reading.and_then(|r| r.distance).map(|m| format!("≈{:.1} m", m)).unwrap_or_else(|| "-".to_string())} }
+                            span { class: "text-gray-400", {t!(l, "device-tx-power")} }
+                            span { {d.tx_power.map(|p| format!("{} dBm", p)).unwrap_or_else(|| "-".to_string())} }
+                            span { class: "text-gray-400", {t!(l, "device-seen")} }
+                            span { class: "font-mono", "{format_time(d.first_seen)} / {format_time(d.last_seen)}" }
+                        }
+                    }
+
+                    // --- 厂商数据 ---
+                    div { class: "card bg-base-100 shadow rounded-2xl",
+                        div { class: "card-body p-5",
+                            h2 { class: "font-bold mb-2", {t!(l, "device-manufacturer-data")} }
+                            if d.manufacturer_data.is_empty() {
+                                span { class: "text-gray-400 text-sm", {t!(l, "common-none")} }
+                            }
+                            for (cid, data) in d.manufacturer_data.iter() {
+                                div { class: "flex gap-3 text-sm items-start",
+                                    span { class: "font-mono text-gray-500 shrink-0", "0x{cid:04X}" }
+                                    span { class: "font-semibold shrink-0", {company_name(*cid).map(str::to_string).unwrap_or_else(|| t!(l, "device-unknown-vendor"))} }
+                                    span { class: "font-mono break-all text-gray-700", "{to_hex(data)}" }
+                                }
+                            }
+                        }
+                    }
+
+                    // --- 服务 ---
+                    div { class: "card bg-base-100 shadow rounded-2xl",
+                        div { class: "card-body p-5",
+                            h2 { class: "font-bold mb-2", {t!(l, "device-services")} }
+                            if d.services.is_empty() {
+                                span { class: "text-gray-400 text-sm", {t!(l, "common-none")} }
+                            }
+                            for uuid in d.services.iter() {
+                                span { class: "font-mono text-sm", "{uuid}" }
+                            }
+                            h2 { class: "font-bold mt-4 mb-2", {t!(l, "device-service-data")} }
+                            if d.service_data.is_empty() {
+                                span { class: "text-gray-400 text-sm", {t!(l, "common-none")} }
+                            }
+                            for (uuid, data) in d.service_data.iter() {
+                                div { class: "flex gap-3 text-sm items-start",
+                                    span { class: "font-mono shrink-0", "{uuid}" }
+                                    span { class: "font-mono break-all text-gray-700", "{to_hex(data)}" }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+
+            // --- GATT ---
+            div { class: "card bg-base-100 shadow rounded-2xl",
+                div { class: "card-body p-5",
+                    div { class: "flex justify-between items-center mb-2",
+                        h2 { class: "font-bold", {t!(l, "device-gatt")} }
+                        button {
+                            class: "btn btn-sm btn-primary",
+                            disabled: *connecting.read() || device.is_none(),
+                            onclick: on_connect,
+                            if *connecting.read() {
+                                {t!(l, "device-connecting")}
+                            } else {
+                                {t!(l, "device-connect")}
+                            }
+                        }
+                    }
+                    match gatt.as_ref() {
+                        None => rsx! {
+                            span { class: "text-gray-400 text-sm", {t!(l, "device-gatt-hint")} }
+                        },
+                        Some(Err(e)) => rsx! {
+                            div { class: "text-red-700 bg-red-50 p-3 rounded-xl text-sm", {t!(l, "device-connect-failed", error = e.localize(l))} }
+                        },
+                        Some(Ok(services)) => rsx! {
+                            for s in services.iter() {
+                                div { class: "mb-3",
+                                    div { class: "font-mono text-sm font-semibold",
+                                        "{s.uuid}"
+                                        if s.primary { span { class: "badge badge-sm ml-2", "primary" } }
+                                    }
+                                    for c in s.characteristics.iter() {
+                                        div { class: "font-mono text-xs text-gray-600 ml-4",
+                                            "{c.uuid}  {c.properties}"
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
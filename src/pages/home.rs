@@ -1,51 +1,67 @@
-use std::{borrow::Borrow, ops::Deref, sync::Arc};
+use std::{sync::Arc, time::Duration};
 
 use dioxus::prelude::*;
-use shaku::HasComponent;
-use tokio::sync::mpsc::Receiver;
-use tracing::info;
 
 use crate::{
-    application::{command::CreateSampleCommand, sample_service::ISampleService},
-    ble::model::Model,
-    di::Deps,
+    ble::{baseline::Thresholds, presence_state::PresenceState},
+    components::{
+        adapter_picker::AdapterPicker,
+        adapter_status::AdapterStatus,
+        rssi_chart::{RssiChart, RssiHistory},
+        snooze_panel::SnoozePanel,
+    },
+    dto::detection::AlgoConfig,
+    i18n::{use_locale, Localize as _},
+    runtime::Runtime,
 };
 
 #[component]
 pub fn Home() -> Element {
-    use std::ops::Deref;
     let sample: Signal<Vec<f32>> = use_context();
-    let mut infer_res = use_signal(String::new);
-    let model = Model::new(include_bytes!("../../ai/hybrid_model.onnx")).unwrap();
+    let history: Signal<RssiHistory> = use_context();
+    let config: Signal<AlgoConfig> = use_context();
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let mut thresholds = use_signal(|| None::<Thresholds>);
+    let mut presence = use_signal(PresenceState::default);
+    let l = use_locale()();
 
-    let dps: Signal<Option<Arc<Deps>>> = use_context();
-
-    use_effect(move || {
-        let r = sample.read().deref().clone();
-        if let Ok(res) = model.inference(r.clone()) {
-            infer_res.set(res.to_string());
-            let dps = dps.read().deref().clone();
-            if let Some(deps) = dps {
-                tokio::spawn(async move {
-                    let sample_srv: Arc<dyn ISampleService> = Deps::resolve(deps.as_ref());
-                    sample_srv
-                        .create_sample(CreateSampleCommand {
-                            device: "test".to_string(),
-                            sample: r,
-                            predict: res.into(),
-                        })
-                        .await
-                        .unwrap();
-                });
+    // 在场状态与学习到的阈值随时间变化，定时刷新
+    use_future(move || async move {
+        loop {
+            let rt = runtime.peek().clone();
+            if let Some(rt) = rt {
+                let target = rt.target();
+                thresholds.set((!target.is_empty()).then(|| rt.thresholds(&target)));
+                presence.set(rt.presence_state());
             }
+            tokio::time::sleep(Duration::from_secs(2)).await;
         }
     });
+    let (threshold, absent) = match *thresholds.read() {
+        Some(t) => (t.present, Some(t.absent)),
+        None => (config.read().threshold, None),
+    };
+
+    // 推理由运行时完成，这里只展示最近一次预测
+    let infer_res = history
+        .read()
+        .predictions
+        .back()
+        .map(|p| p.result.localize(l))
+        .unwrap_or_default();
 
+    let presence = presence.read().localize(l);
     let sample_vec = sample.read();
 
     rsx! {
         div { class: "min-h-screen bg-gradient-to-tr from-blue-50 to-teal-50 flex items-center justify-center",
-            div { class: "bg-white/90 rounded-2xl shadow-xl p-8  max-w-full",
+            div { class: "bg-white/90 rounded-2xl shadow-xl p-8 w-full max-w-4xl",
+                AdapterStatus {}
+                AdapterPicker {}
+                SnoozePanel {}
+                div { class: "mb-6",
+                    RssiChart { history, threshold, absent }
+                }
                 div { class: "mb-6 flex flex-wrap gap-2",
                     for (i, x) in sample_vec.iter().enumerate() {
                         div {
@@ -60,11 +76,12 @@ pub fn Home() -> Element {
                     class: "mt-4 p-4 rounded-xl border border-teal-100 bg-teal-50/60 flex items-center gap-3",
                     // 可配icon
                     div {
-                        class: "w-full flex justify-center",
+                        class: "w-full flex justify-center items-baseline gap-4",
                         span {
                             class: "text-4xl font-semibold text-cyan-800",
-                            "{infer_res.read()}"
+                            "{infer_res}"
                         }
+                        span { class: "text-lg text-gray-500", "{presence}" }
                     }
                 }
             }
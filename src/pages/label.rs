@@ -1,6 +1,9 @@
 use crate::{
-    application::{sample_service::ISampleService, view::SampleView},
+    application::{command::LabelSampleCommand, sample_service::ISampleService, view::SampleView},
     di::Deps,
+    domain::value_objects::ModelResult,
+    i18n::{use_locale, Localize as _},
+    t,
 };
 use dioxus::prelude::*;
 use shaku::HasComponent;
@@ -11,8 +14,9 @@ pub fn Label() -> Element {
     use std::ops::Deref;
 
     let dps: Signal<Option<Arc<Deps>>> = use_context();
+    let l = use_locale()();
     let samples = use_signal(|| Vec::<SampleView>::new());
-    let selected_labels = use_signal(|| Vec::<Option<String>>::new());
+    let selected_labels = use_signal(|| Vec::<Option<ModelResult>>::new());
     let loading = use_signal(|| false);
     let error = use_signal(|| None::<String>);
 
@@ -33,7 +37,7 @@ pub fn Label() -> Element {
                         selected_labels.set(vec![None; new_samples.len()]);
                         samples.set(new_samples);
                     }
-                    Err(e) => error.set(Some(format!("获取失败: {:?}", e))),
+                    Err(e) => error.set(Some(t!(l, "label-fetch-failed", error = format!("{:?}", e)))),
                 }
             }
             loading.set(false);
@@ -48,13 +52,26 @@ pub fn Label() -> Element {
         }
     });
 
-    // 只做高亮，后续补充标注上传
+    // 高亮并保存人工标注
     let mut on_click_label = {
         let mut selected_labels = selected_labels.clone();
-        move |idx: usize, label: &'static str| {
+        let mut error = error.clone();
+        move |idx: usize, label: ModelResult| {
             let mut labels = selected_labels.read().clone();
-            labels[idx] = Some(label.to_string());
+            labels[idx] = Some(label);
+            let actual = i32::from(label);
             selected_labels.set(labels);
+
+            let id = samples.read()[idx].id;
+            let deps = dps.read().deref().clone();
+            if let Some(deps) = deps {
+                spawn(async move {
+                    let svc: Arc<dyn ISampleService> = deps.resolve();
+                    if let Err(e) = svc.label_sample(LabelSampleCommand { id, actual }).await {
+                        error.set(Some(t!(l, "label-save-failed", error = format!("{:?}", e))));
+                    }
+                });
+            }
         }
     };
 
@@ -66,7 +83,7 @@ pub fn Label() -> Element {
         div { class: "min-h-screen bg-gradient-to-br from-cyan-50 to-sky-100 flex flex-col items-center",
             div { class: "w-full max-w-3xl mt-12 mb-8 flex flex-col gap-6",
                 div { class: "flex justify-between items-center bg-white/90 rounded-3xl shadow-md p-6 mb-6",
-                    h1 { class: "text-2xl font-bold text-cyan-700 tracking-wide", "批量样本标注" }
+                    h1 { class: "text-2xl font-bold text-cyan-700 tracking-wide", {t!(l, "label-title")} }
                 }
                 if *loading {
                     div { class: "flex flex-col items-center gap-2 justify-center my-12 py-12",
@@ -77,19 +94,19 @@ pub fn Label() -> Element {
                                 stroke_linecap: "round", stroke_linejoin: "round"
                             }
                         }
-                        span { class: "text-sky-700 text-lg tracking-wide", "加载/处理中 ..." }
+                        span { class: "text-sky-700 text-lg tracking-wide", {t!(l, "common-loading")} }
                     }
                 } else if let Some(msg) = &*error {
-                    div { class: "text-red-700 text-base text-center bg-red-50 p-3 rounded-xl shadow font-semibold", "出错：{msg}" }
+                    div { class: "text-red-700 text-base text-center bg-red-50 p-3 rounded-xl shadow font-semibold", {t!(l, "common-error", error = msg.as_str())} }
                 } else if samples.is_empty() {
-                    div { class: "text-gray-400 text-center text-lg py-12", "没有可标注样本" }
+                    div { class: "text-gray-400 text-center text-lg py-12", {t!(l, "label-empty")} }
                 } else {
                     for (idx, s) in samples.iter().enumerate() {
                         div { class: "mb-10 bg-white/90 rounded-xl shadow-lg p-7 transition-shadow hover:shadow-2xl",
                             div {
                                 class: "flex justify-between items-center mb-1 text-xs text-gray-400",
-                                div { "设备 {s.device}" }
-                                div { "创建: {s.created_at}" }
+                                div { {t!(l, "label-device", device = s.device.as_str())} }
+                                div { {t!(l, "label-created", created = s.created_at.as_str())} }
                             }
                             div { class: "font-mono text-base mb-1 text-cyan-900 tracking-wide", "ID: {s.id}" }
                             div { class: "flex flex-wrap gap-2 mb-3",
@@ -98,24 +115,24 @@ pub fn Label() -> Element {
                                 }
                             }
                             div { class: "flex flex-row gap-4 mt-4 mb-1 justify-center items-center",
-                                for (btn_label, color) in [("靠近", "#0ea5e9"), ("远离", "#f59e42"), ("静止", "#16a34a")] {
+                                for (result, color) in [(ModelResult::MovingCloser, "#0ea5e9"), (ModelResult::MovingAway, "#f59e42"), (ModelResult::Stationary, "#16a34a")] {
                                     button {
                                         class: "rounded-xl px-7 py-2 text-base font-bold border-2 border-transparent shadow hover:border-cyan-500 hover:bg-cyan-50
                                                 transition-colors outline-none focus:ring-2 focus:ring-cyan-400
                                                 ",
-                                        style: if selected_labels.get(idx).and_then(|v| v.as_deref()) == Some(btn_label) {
+                                        style: if selected_labels.get(idx).copied().flatten() == Some(result) {
                                             format!("background:{color};color:white;border-color:{color};")
                                         } else {
                                             "".to_string()
                                         },
                                         disabled: *loading,
-                                        onclick: move |_| on_click_label(idx, btn_label),
-                                        "{btn_label}"
+                                        onclick: move |_| on_click_label(idx, result),
+                                        {result.localize(l)}
                                     }
                                 }
                             }
                             if let Some(label) = &selected_labels[idx] {
-                                div { class: "mt-3 text-cyan-700 text-lg text-center font-semibold", "人工标注: {label}" }
+                                div { class: "mt-3 text-cyan-700 text-lg text-center font-semibold", {t!(l, "label-selected", label = label.localize(l))} }
                             }
                         }
                     }
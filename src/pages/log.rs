@@ -1,17 +1,180 @@
+use chrono::{Local, TimeZone as _};
 use dioxus::prelude::*;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::Level;
 
+use crate::{
+    domain::entity::rssi_event::now_millis,
+    i18n::use_locale,
+    infrastructure::log_layer::{LogBuffer, LogRecord},
+    t,
+};
+
+/// 页面最多保留的日志条数
+const MAX_RECORDS: usize = 2000;
+/// 最多渲染的条数
+const MAX_RENDERED: usize = 500;
+
+fn level_class(level: Level) -> &'static str {
+    match level {
+        Level::ERROR => "bg-red-100 text-red-700",
+        Level::WARN => "bg-amber-100 text-amber-700",
+        Level::INFO => "bg-sky-100 text-sky-700",
+        Level::DEBUG => "bg-gray-100 text-gray-600",
+        Level::TRACE => "bg-gray-50 text-gray-400",
+    }
+}
+
+/// 本地时间的时:分:秒.毫秒
+fn format_time(ts: i64) -> String {
+    Local
+        .timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
+
+/// 导出为 JSONL，返回文件路径
+fn export(records: &[LogRecord]) -> std::io::Result<String> {
+    let path = format!("blue-lock-log-{}.jsonl", now_millis());
+    let mut content = String::new();
+    for r in records {
+        content.push_str(&serde_json::to_string(r).map_err(std::io::Error::other)?);
+        content.push('\n');
+    }
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[component]
 pub fn Log() -> Element {
-    rsx!(
-        div {
-            class: "flex flex-col items-center justify-center h-screen",
-            div {
-                class: "text-4xl font-bold mb-4",
-                "Welcome to the Log Page!"
+    let mut records = use_signal(|| LogBuffer::global().snapshot());
+    let mut paused = use_signal(|| false);
+    let mut level = use_signal(|| Level::INFO);
+    let mut target = use_signal(String::new);
+    let mut search = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+    let l = use_locale()();
+
+    // 实时订阅
+    use_future(move || async move {
+        let mut rx = LogBuffer::global().subscribe();
+        loop {
+            match rx.recv().await {
+                Ok(record) => {
+                    if *paused.peek() {
+                        continue;
+                    }
+                    let mut records = records.write();
+                    if records.len() >= MAX_RECORDS {
+                        records.remove(0);
+                    }
+                    records.push(record);
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
             }
-            div {
-                class: "text-xl",
-                "This is where you can view your log."
+        }
+    });
+
+    let filtered: Vec<LogRecord> = {
+        let level = *level.read();
+        let target = target.read();
+        let search = search.read();
+        records
+            .read()
+            .iter()
+            .filter(|r| r.level() <= level)
+            .filter(|r| target.is_empty() || r.target.contains(target.as_str()))
+            .filter(|r| r.matches(search.as_str()))
+            .cloned()
+            .collect()
+    };
+    let total = filtered.len();
+
+    rsx! {
+        div { class: "h-full flex flex-col bg-gray-50 p-4 gap-3",
+            // --- 工具栏 ---
+            div { class: "flex flex-wrap gap-3 items-center bg-white rounded-xl shadow p-3",
+                h1 { class: "text-xl font-bold mr-2", {t!(l, "log-title")} }
+                select {
+                    class: "select select-sm select-bordered",
+                    value: "{level}",
+                    onchange: move |e| {
+                        if let Ok(lv) = e.value().parse() {
+                            level.set(lv);
+                        }
+                    },
+                    for lv in [Level::TRACE, Level::DEBUG, Level::INFO, Level::WARN, Level::ERROR] {
+                        option { value: "{lv}", "{lv}" }
+                    }
+                }
+                input {
+                    class: "input input-sm input-bordered w-40",
+                    placeholder: "target",
+                    value: "{target}",
+                    oninput: move |e| target.set(e.value()),
+                }
+                input {
+                    class: "input input-sm input-bordered flex-1 min-w-40",
+                    placeholder: t!(l, "log-search"),
+                    value: "{search}",
+                    oninput: move |e| search.set(e.value()),
+                }
+                button {
+                    class: "btn btn-sm",
+                    onclick: move |_| {
+                        let resume = *paused.read();
+                        if resume {
+                            // 恢复时补齐暂停期间的日志
+                            records.set(LogBuffer::global().snapshot());
+                        }
+                        paused.set(!resume);
+                    },
+                    if *paused.read() {
+                        {t!(l, "log-resume")}
+                    } else {
+                        {t!(l, "log-pause")}
+                    }
+                }
+                button {
+                    class: "btn btn-sm",
+                    onclick: move |_| records.set(Vec::new()),
+                    {t!(l, "log-clear")}
+                }
+                button {
+                    class: "btn btn-sm btn-primary",
+                    onclick: {
+                        let filtered = filtered.clone();
+                        move |_| {
+                            status.set(Some(match export(&filtered) {
+                                Ok(path) => t!(l, "log-exported", path = path),
+                                Err(e) => t!(l, "log-export-failed", error = e.to_string()),
+                            }));
+                        }
+                    },
+                    {t!(l, "log-export")}
+                }
+                span { class: "text-xs text-gray-400", {t!(l, "log-total", count = total)} }
+            }
+            if let Some(msg) = &*status.read() {
+                div { class: "text-sm text-gray-600 px-2", "{msg}" }
+            }
+
+            // --- 日志列表（最新在上）---
+            div { class: "flex-1 overflow-auto bg-white rounded-xl shadow font-mono text-xs",
+                for r in filtered.iter().rev().take(MAX_RENDERED) {
+                    div { key: "{r.seq}", class: "flex gap-2 px-3 py-1 border-b border-gray-100 items-start",
+                        span { class: "text-gray-400 shrink-0", "{format_time(r.ts)}" }
+                        span { class: "px-1 rounded shrink-0 {level_class(r.level())}", "{r.level}" }
+                        span { class: "text-gray-500 shrink-0", "{r.target}" }
+                        span { class: "text-gray-800 break-all", "{r.message}" }
+                        for (k, v) in r.fields.iter() {
+                            span { class: "text-cyan-700 break-all", "{k}={v}" }
+                        }
+                    }
+                }
             }
         }
-    )
-}
\ No newline at end of file
+    }
+}
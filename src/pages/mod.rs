@@ -1,8 +1,11 @@
 pub mod home;
 pub mod device;
+pub mod device_detail;
 pub mod about;
 pub mod prelude;
 pub mod side_bar;
 pub mod layout;
 pub mod log;
-pub mod label;
\ No newline at end of file
+pub mod label;
+pub mod stats;
+pub mod settings;
@@ -1,6 +1,9 @@
 pub use crate::pages::home::Home;
 pub use crate::pages::about::About;
 pub use crate::pages::device::Device;
+pub use crate::pages::device_detail::DeviceDetail;
 pub use crate::pages::layout::Layout;
 pub use crate::pages::log::Log;
-pub use crate::pages::label::Label;
\ No newline at end of file
+pub use crate::pages::label::Label;
+pub use crate::pages::stats::Stats;
+pub use crate::pages::settings::Settings;
@@ -0,0 +1,573 @@
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use dioxus::prelude::*;
+use shaku::HasComponent;
+
+use crate::{
+    ble::{
+        adapter::AdapterLostPolicy,
+        baseline::{ThresholdOverride, ThresholdSource, Thresholds},
+    },
+    di::Deps,
+    domain::{
+        entity::trusted_device::TrustedDevice, repo::trusted_device_repo::ITrustedDeviceRepo,
+    },
+    errors::UserMessage,
+    i18n::{save_locale, use_locale, Locale, Localize as _},
+    runtime::{Runtime, RuntimeConfig},
+    t,
+    unlock::UnlockPolicy,
+};
+
+const DAY_SECS: u64 = 24 * 3600;
+
+/// 保存结果提示
+#[derive(Clone, PartialEq)]
+enum SaveStatus {
+    Saved,
+    /// 需要重启才能生效的字段
+    Restart(String),
+    Failed(UserMessage),
+}
+
+#[component]
+pub fn Settings() -> Element {
+    let mut locale = use_locale();
+    let l = locale();
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let mut draft = use_signal(|| None::<RuntimeConfig>);
+    let mut model_version = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+    let mut status = use_signal(|| None::<SaveStatus>);
+
+    // 等运行时启动后取当前配置作为草稿
+    use_future(move || async move {
+        let rt = loop {
+            if let Some(rt) = runtime.peek().clone() {
+                break rt;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+        draft.set(Some(rt.config()));
+        model_version.set(rt.model_version());
+    });
+
+    let on_save = move |_| {
+        let (Some(rt), Some(config)) = (runtime.peek().clone(), draft.peek().clone()) else {
+            return;
+        };
+        saving.set(true);
+        status.set(None);
+        spawn(async move {
+            let res = rt.apply_config(config).await;
+            status.set(Some(match res {
+                Ok(fields) if fields.is_empty() => SaveStatus::Saved,
+                Ok(fields) => SaveStatus::Restart(fields.join(", ")),
+                Err(e) => SaveStatus::Failed(e.user_message()),
+            }));
+            model_version.set(rt.model_version());
+            saving.set(false);
+        });
+    };
+
+    let form = draft.read().clone();
+
+    rsx! {
+        div { class: "min-h-screen bg-gradient-to-br from-cyan-50 to-sky-100 flex flex-col items-center",
+            div { class: "w-full max-w-3xl mt-12 mb-8 flex flex-col gap-6",
+                div { class: "bg-white/90 rounded-3xl shadow-md p-6",
+                    h1 { class: "text-2xl font-bold text-cyan-700 tracking-wide", {t!(l, "settings-title")} }
+                }
+                div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+                    h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-general")} }
+                    label { class: "flex items-center justify-between gap-4",
+                        span { class: "text-gray-600", {t!(l, "settings-language")} }
+                        select {
+                            class: "select select-sm select-bordered",
+                            onchange: move |e| {
+                                let tag = e.value();
+                                if let Some(next) = Locale::ALL.into_iter().find(|l| l.tag() == tag) {
+                                    locale.set(next);
+                                    save_locale(next);
+                                }
+                            },
+                            for option_locale in Locale::ALL {
+                                option {
+                                    key: "{option_locale.tag()}",
+                                    value: option_locale.tag(),
+                                    selected: option_locale == l,
+                                    "{option_locale.native_name()}"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                match form {
+                    None => rsx! {
+                        div { class: "text-gray-400 text-center py-12", {t!(l, "common-loading")} }
+                    },
+                    Some(config) => rsx! {
+                        // --- 检测参数 ---
+                        div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+                            h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-detection")} }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-window-size")} }
+                                input {
+                                    r#type: "number",
+                                    class: "input input-sm input-bordered w-40 text-right",
+                                    value: "{config.algo.window_size}",
+                                    disabled: true,
+                                }
+                            }
+                            span { class: "text-sm text-gray-400", {t!(l, "settings-window-size-hint")} }
+                            NumberField {
+                                label: t!(l, "settings-threshold"),
+                                value: config.algo.threshold.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.threshold = v, draft),
+                            }
+                            NumberField {
+                                label: t!(l, "settings-timeout"),
+                                value: config.algo.timeout_secs.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.timeout_secs = v, draft),
+                            }
+                            NumberField {
+                                label: t!(l, "settings-leave-dwell"),
+                                value: config.algo.leave_dwell_secs.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.leave_dwell_secs = v, draft),
+                            }
+                            NumberField {
+                                label: t!(l, "settings-return-dwell"),
+                                value: config.algo.return_dwell_secs.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.return_dwell_secs = v, draft),
+                            }
+                            NumberField {
+                                label: t!(l, "settings-batch-size"),
+                                value: config.algo.batch_size.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.batch_size = v, draft),
+                            }
+                        }
+
+                        // --- 锁屏策略 ---
+                        div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+                            h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-lock")} }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-auto-lock")} }
+                                input {
+                                    r#type: "checkbox",
+                                    class: "toggle toggle-sm",
+                                    checked: config.auto_lock,
+                                    onchange: move |e| {
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.auto_lock = e.checked();
+                                        }
+                                    },
+                                }
+                            }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-notifications")} }
+                                input {
+                                    r#type: "checkbox",
+                                    class: "toggle toggle-sm",
+                                    checked: config.notifications,
+                                    onchange: move |e| {
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.notifications = e.checked();
+                                        }
+                                    },
+                                }
+                            }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-adapter-lost")} }
+                                select {
+                                    class: "select select-sm select-bordered",
+                                    onchange: move |e| {
+                                        let policy = match e.value().as_str() {
+                                            "fail_open" => AdapterLostPolicy::FailOpen,
+                                            _ => AdapterLostPolicy::FailClosed,
+                                        };
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.adapter_lost = policy;
+                                        }
+                                    },
+                                    option {
+                                        value: "fail_closed",
+                                        selected: config.adapter_lost == AdapterLostPolicy::FailClosed,
+                                        {t!(l, "settings-adapter-lost-closed")}
+                                    }
+                                    option {
+                                        value: "fail_open",
+                                        selected: config.adapter_lost == AdapterLostPolicy::FailOpen,
+                                        {t!(l, "settings-adapter-lost-open")}
+                                    }
+                                }
+                            }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-unlock")} }
+                                select {
+                                    class: "select select-sm select-bordered",
+                                    onchange: move |e| {
+                                        let policy = match e.value().as_str() {
+                                            "connected" => UnlockPolicy::Connected,
+                                            "challenge" => UnlockPolicy::Challenge,
+                                            _ => UnlockPolicy::LockOnly,
+                                        };
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.unlock = policy;
+                                        }
+                                    },
+                                    option {
+                                        value: "lock_only",
+                                        selected: config.unlock == UnlockPolicy::LockOnly,
+                                        {t!(l, "settings-unlock-lock-only")}
+                                    }
+                                    option {
+                                        value: "connected",
+                                        selected: config.unlock == UnlockPolicy::Connected,
+                                        {t!(l, "settings-unlock-connected")}
+                                    }
+                                    option {
+                                        value: "challenge",
+                                        selected: config.unlock == UnlockPolicy::Challenge,
+                                        {t!(l, "settings-unlock-challenge")}
+                                    }
+                                }
+                            }
+                            if config.unlock != UnlockPolicy::LockOnly {
+                                span { class: "text-xs text-gray-400", {t!(l, "settings-unlock-hint")} }
+                            }
+                        }
+
+                        // --- 模型 ---
+                        div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+                            h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-model")} }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-model-path")} }
+                                input {
+                                    class: "input input-sm input-bordered w-80 font-mono",
+                                    placeholder: t!(l, "settings-model-builtin"),
+                                    value: config.model_path.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+                                    onchange: move |e| {
+                                        let path = e.value().trim().to_string();
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.model_path = Some(PathBuf::from(path)).filter(|p| !p.as_os_str().is_empty());
+                                        }
+                                    },
+                                }
+                            }
+                            span { class: "text-sm text-gray-400",
+                                {t!(l, "settings-model-version", version = model_version.read().as_str())}
+                            }
+                        }
+
+                        // --- 存储 ---
+                        div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+                            h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-storage")} }
+                            label { class: "flex items-center justify-between gap-4",
+                                span { class: "text-gray-600", {t!(l, "settings-db-url")} }
+                                input {
+                                    class: "input input-sm input-bordered w-80 font-mono",
+                                    value: config.db_url.clone(),
+                                    onchange: move |e| {
+                                        if let Some(c) = draft.write().as_mut() {
+                                            c.db_url = e.value().trim().to_string();
+                                        }
+                                    },
+                                }
+                            }
+                            span { class: "text-sm text-gray-400", {t!(l, "settings-db-url-hint")} }
+                            NumberField {
+                                label: t!(l, "settings-retention-days"),
+                                value: (config.algo.raw_retention_secs / DAY_SECS).to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v: u64| c.algo.raw_retention_secs = v * DAY_SECS, draft),
+                            }
+                            NumberField {
+                                label: t!(l, "settings-max-rows"),
+                                value: config.algo.raw_max_rows.to_string(),
+                                onchange: move |v: String| parse_into(&v, |c, v| c.algo.raw_max_rows = v, draft),
+                            }
+                        }
+
+                        div { class: "flex items-center gap-4",
+                            button {
+                                class: "btn btn-primary",
+                                disabled: *saving.read(),
+                                onclick: on_save,
+                                {t!(l, "settings-save")}
+                            }
+                            match status.read().clone() {
+                                None => rsx! {},
+                                Some(SaveStatus::Saved) => rsx! {
+                                    span { class: "text-green-700 text-sm", {t!(l, "settings-saved")} }
+                                },
+                                Some(SaveStatus::Restart(fields)) => rsx! {
+                                    span { class: "text-amber-700 text-sm", {t!(l, "settings-restart-required", fields = fields)} }
+                                },
+                                Some(SaveStatus::Failed(error)) => rsx! {
+                                    span { class: "text-red-700 text-sm", {t!(l, "settings-save-failed", error = error.localize(l))} }
+                                },
+                            }
+                        }
+
+                        TrustedDevices {
+                            target: config.target.clone(),
+                            on_target: move |fingerprint: String| {
+                                if let Some(c) = draft.write().as_mut() {
+                                    c.target = fingerprint.clone();
+                                }
+                                if let Some(rt) = runtime.peek().as_ref() {
+                                    rt.set_target(fingerprint);
+                                }
+                            },
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// 解析输入写入草稿，解析失败时忽略，取值范围在保存时统一校验
+fn parse_into<T: FromStr>(
+    value: &str,
+    set: impl FnOnce(&mut RuntimeConfig, T),
+    mut draft: Signal<Option<RuntimeConfig>>,
+) {
+    let Ok(v) = value.trim().parse() else {
+        return;
+    };
+    if let Some(c) = draft.write().as_mut() {
+        set(c, v);
+    }
+}
+
+#[component]
+fn NumberField(label: String, value: String, onchange: EventHandler<String>) -> Element {
+    rsx! {
+        label { class: "flex items-center justify-between gap-4",
+            span { class: "text-gray-600", "{label}" }
+            input {
+                r#type: "number",
+                class: "input input-sm input-bordered w-40 text-right",
+                value: "{value}",
+                onchange: move |e| onchange.call(e.value()),
+            }
+        }
+    }
+}
+
+/// 受信任设备列表，可增删并设为当前目标
+#[component]
+fn TrustedDevices(target: String, on_target: EventHandler<String>) -> Element {
+    let deps: Signal<Option<Arc<Deps>>> = use_context();
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let l = use_locale()();
+    let mut fingerprint = use_signal(String::new);
+    let mut name = use_signal(String::new);
+    let mut error = use_signal(|| None::<UserMessage>);
+
+    let mut devices = use_resource(move || async move {
+        let Some(deps) = deps.read().clone() else {
+            return Vec::new();
+        };
+        let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+        match repo.list().await {
+            Ok(list) => list,
+            Err(e) => {
+                error.set(Some(e.user_message()));
+                Vec::new()
+            }
+        }
+    });
+
+    let on_add = move |_| {
+        let fp = fingerprint.peek().trim().to_string();
+        if fp.is_empty() {
+            return;
+        }
+        let Some(deps) = deps.peek().clone() else {
+            return;
+        };
+        // 绑定此刻扫描到的设备，靠近解锁只验证这台设备
+        let address = runtime
+            .peek()
+            .as_ref()
+            .and_then(|rt| rt.scanner().find_by_fingerprint(&fp))
+            .map(|d| d.id)
+            .unwrap_or_default();
+        let device = TrustedDevice::new(fp, name.peek().trim().to_string()).with_address(address);
+        spawn(async move {
+            let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+            match repo.add(device).await {
+                Ok(()) => {
+                    fingerprint.set(String::new());
+                    name.set(String::new());
+                    error.set(None);
+                    devices.restart();
+                }
+                Err(e) => error.set(Some(e.user_message())),
+            }
+        });
+    };
+
+    let on_remove = move |fp: String| {
+        let Some(deps) = deps.peek().clone() else {
+            return;
+        };
+        spawn(async move {
+            let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+            if let Err(e) = repo.remove(&fp).await {
+                error.set(Some(e.user_message()));
+            }
+            devices.restart();
+        });
+    };
+
+    let list = devices.read().clone().unwrap_or_default();
+
+    rsx! {
+        div { class: "bg-white/90 rounded-3xl shadow-md p-6 flex flex-col gap-4",
+            h2 { class: "text-lg font-semibold text-gray-700", {t!(l, "settings-trusted")} }
+            if list.is_empty() {
+                span { class: "text-gray-400 text-sm", {t!(l, "settings-trusted-empty")} }
+            }
+            for d in list {
+                div { key: "{d.fingerprint}", class: "flex items-center gap-3 text-sm",
+                    span { class: "font-mono truncate", "{d.fingerprint}" }
+                    span { class: "text-gray-500 truncate mr-auto", "{d.name}" }
+                    if d.address.is_empty() {
+                        span { class: "badge badge-sm badge-warning", title: t!(l, "settings-trusted-unbound-hint"), {t!(l, "settings-trusted-unbound")} }
+                    } else {
+                        span { class: "font-mono text-gray-400", "{d.address}" }
+                    }
+                    ThresholdEditor { fingerprint: d.fingerprint.clone() }
+                    if d.fingerprint == target {
+                        span { class: "badge badge-sm badge-info", {t!(l, "settings-trusted-current")} }
+                    } else {
+                        button {
+                            class: "btn btn-xs",
+                            onclick: {
+                                let fp = d.fingerprint.clone();
+                                move |_| on_target.call(fp.clone())
+                            },
+                            {t!(l, "settings-trusted-set-target")}
+                        }
+                    }
+                    button {
+                        class: "btn btn-xs btn-ghost text-red-600",
+                        onclick: {
+                            let fp = d.fingerprint.clone();
+                            move |_| on_remove(fp.clone())
+                        },
+                        {t!(l, "settings-trusted-remove")}
+                    }
+                }
+            }
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "input input-sm input-bordered flex-1 font-mono",
+                    placeholder: t!(l, "settings-trusted-fingerprint"),
+                    value: "{fingerprint}",
+                    oninput: move |e| fingerprint.set(e.value()),
+                }
+                input {
+                    class: "input input-sm input-bordered w-40",
+                    placeholder: t!(l, "settings-trusted-name"),
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                button { class: "btn btn-sm", onclick: on_add, {t!(l, "settings-trusted-add")} }
+            }
+            if let Some(e) = error.read().as_ref() {
+                div { class: "text-red-700 bg-red-50 p-3 rounded-xl text-sm", {t!(l, "common-error", error = e.localize(l))} }
+            }
+        }
+    }
+}
+
+/// 设备学习到的在场/离开阈值，可手动覆盖
+#[component]
+fn ThresholdEditor(fingerprint: String) -> Element {
+    let runtime: Signal<Option<Arc<Runtime>>> = use_context();
+    let l = use_locale()();
+    let mut learned = use_signal(|| None::<Thresholds>);
+    let mut present = use_signal(String::new);
+    let mut absent = use_signal(String::new);
+    let mut error = use_signal(|| None::<UserMessage>);
+    let device = use_signal(|| fingerprint);
+
+    let mut refresh = use_future(move || async move {
+        let rt = loop {
+            if let Some(rt) = runtime.peek().clone() {
+                break rt;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        };
+        let fp = device.peek().clone();
+        let o = rt.threshold_override(&fp);
+        present.set(o.present.map(|v| v.to_string()).unwrap_or_default());
+        absent.set(o.absent.map(|v| v.to_string()).unwrap_or_default());
+        learned.set(Some(rt.thresholds(&fp)));
+    });
+
+    let mut apply = move |value: ThresholdOverride| {
+        let Some(rt) = runtime.peek().clone() else {
+            return;
+        };
+        match rt.set_threshold_override(&device.peek(), value) {
+            Ok(()) => error.set(None),
+            Err(e) => error.set(Some(e.user_message())),
+        }
+        refresh.restart();
+    };
+    let on_apply = move |_| {
+        let parse = |s: &str| s.trim().parse::<f32>().ok();
+        let value = ThresholdOverride {
+            present: parse(&present.peek()),
+            absent: parse(&absent.peek()),
+        };
+        apply(value);
+    };
+
+    let learned = *learned.read();
+    let source = match learned.map(|t| t.source) {
+        Some(ThresholdSource::Hourly { samples }) => {
+            t!(l, "settings-threshold-hourly", samples = samples)
+        }
+        Some(ThresholdSource::Daily { samples }) => {
+            t!(l, "settings-threshold-daily", samples = samples)
+        }
+        Some(ThresholdSource::Override) => t!(l, "settings-threshold-override"),
+        Some(ThresholdSource::Default) | None => t!(l, "settings-threshold-default"),
+    };
+    let (learned_present, learned_absent) = learned
+        .map(|t| (format!("{:.0}", t.present), format!("{:.0}", t.absent)))
+        .unwrap_or_default();
+
+    rsx! {
+        div { class: "flex items-center gap-1 text-xs text-gray-500",
+            span { title: source.clone(), "{learned_present} / {learned_absent} dBm" }
+            input {
+                class: "input input-xs input-bordered w-16",
+                placeholder: t!(l, "settings-threshold-present"),
+                value: "{present}",
+                oninput: move |e| present.set(e.value()),
+            }
+            input {
+                class: "input input-xs input-bordered w-16",
+                placeholder: t!(l, "settings-threshold-absent"),
+                value: "{absent}",
+                oninput: move |e| absent.set(e.value()),
+            }
+            button { class: "btn btn-xs", onclick: on_apply, {t!(l, "settings-threshold-apply")} }
+            button {
+                class: "btn btn-xs btn-ghost",
+                onclick: move |_| apply(ThresholdOverride::default()),
+                {t!(l, "settings-threshold-clear")}
+            }
+            if let Some(e) = error.read().as_ref() {
+                span { class: "text-red-600", {e.localize(l)} }
+            }
+        }
+    }
+}
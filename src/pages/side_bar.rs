@@ -2,35 +2,48 @@ use dioxus::prelude::*;
 
 use crate::{
     components::menu::{Menu, MenuItem},
+    i18n::use_locale,
     routes::Route,
+    t,
 };
 
 #[component]
 pub fn Sidebar(current_route: Route) -> Element {
+    let l = use_locale()();
     let MENU_ITEMS: Vec<MenuItem> = vec![
         MenuItem {
-            name: "首页".to_owned(),
+            name: t!(l, "menu-home"),
             icon: "home".to_owned(),
             route: Route::Home,
         },
         MenuItem {
-            name: "标注".to_owned(),
+            name: t!(l, "menu-label"),
             icon: "home".to_owned(),
             route: Route::Label,
         },
+        MenuItem {
+            name: t!(l, "menu-stats"),
+            icon: "chart".to_owned(),
+            route: Route::Stats,
+        },
 
         MenuItem {
-            name: "设备".to_owned(),
+            name: t!(l, "menu-device"),
             icon: "device".to_owned(),
             route: Route::Device,
         },
         MenuItem {
-            name: "日志".to_owned(),
+            name: t!(l, "menu-log"),
             icon: "log".to_owned(),
             route: Route::Log,
         },
         MenuItem {
-            name: "关于".to_owned(),
+            name: t!(l, "menu-settings"),
+            icon: "settings".to_owned(),
+            route: Route::Settings,
+        },
+        MenuItem {
+            name: t!(l, "menu-about"),
             icon: "info".to_owned(),
             route: Route::About,
         },
@@ -45,7 +58,7 @@ pub fn Sidebar(current_route: Route) -> Element {
         rsx! {
             aside { 
                 class: "w-32 h-full bg-base-300 flex flex-col items-center",  // 修改这里
-                h1 { class: "text-xl font-bold mb-4", "BLE Unlock" }
+                h1 { class: "text-xl font-bold mb-4", {t!(l, "app-name")} }
                 Menu { items, current_route: current_route.clone() }
             }
         }
@@ -0,0 +1,210 @@
+use crate::{
+    application::{
+        stats_service::{IStatsService, StatsFilter},
+        view::AccuracyReportView,
+    },
+    di::Deps,
+    domain::value_objects::ModelResult,
+    i18n::{use_locale, Locale, Localize as _},
+    t,
+};
+use dioxus::prelude::*;
+use shaku::HasComponent;
+use std::sync::Arc;
+
+/// “未记录模型”选项的取值，与表示全部模型的空值区分
+const UNRECORDED_MODEL: &str = "__unrecorded__";
+
+fn label_name(label: i32, locale: Locale) -> String {
+    ModelResult::from(label).localize(locale)
+}
+
+fn percent(v: Option<f32>) -> String {
+    v.map(|v| format!("{:.1}%", v * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[component]
+pub fn Stats() -> Element {
+    use std::ops::Deref;
+
+    let dps: Signal<Option<Arc<Deps>>> = use_context();
+    let mut device = use_signal(|| None::<String>);
+    let mut model = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+    let l = use_locale()();
+
+    // 过滤选项
+    let options = use_resource(move || async move {
+        let deps = dps.read().deref().clone()?;
+        let svc: Arc<dyn IStatsService> = deps.resolve();
+        let devices = svc.list_devices().await.unwrap_or_default();
+        let models = svc.list_models().await.unwrap_or_default();
+        Some((devices, models))
+    });
+
+    // 过滤条件变化时重新统计
+    let report = use_resource(move || async move {
+        let filter = StatsFilter {
+            device: device.read().clone(),
+            model: model.read().clone(),
+        };
+        let deps = dps.read().deref().clone()?;
+        let svc: Arc<dyn IStatsService> = deps.resolve();
+        match svc.accuracy_report(filter).await {
+            Ok(r) => {
+                error.set(None);
+                Some(r)
+            }
+            Err(e) => {
+                error.set(Some(t!(l, "stats-failed", error = format!("{:?}", e))));
+                None
+            }
+        }
+    });
+
+    let (devices, models) = options
+        .read()
+        .clone()
+        .flatten()
+        .unwrap_or_default();
+    let report: AccuracyReportView = report.read().clone().flatten().unwrap_or_default();
+    let max_cell = report.matrix.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+    rsx! {
+        div { class: "min-h-screen bg-gradient-to-br from-cyan-50 to-sky-100 flex flex-col items-center",
+            div { class: "w-full max-w-4xl mt-12 mb-8 flex flex-col gap-6",
+                div { class: "flex justify-between items-center bg-white/90 rounded-3xl shadow-md p-6",
+                    h1 { class: "text-2xl font-bold text-cyan-700 tracking-wide", {t!(l, "stats-title")} }
+                    div { class: "flex gap-3",
+                        select {
+                            class: "select select-sm select-bordered",
+                            onchange: move |e| {
+                                let v = e.value();
+                                device.set(if v.is_empty() { None } else { Some(v) });
+                            },
+                            option { value: "", {t!(l, "stats-all-devices")} }
+                            for d in devices.iter() {
+                                option { value: "{d}", "{d}" }
+                            }
+                        }
+                        select {
+                            class: "select select-sm select-bordered",
+                            onchange: move |e| {
+                                let v = e.value();
+                                model.set(match v.as_str() {
+                                    "" => None,
+                                    UNRECORDED_MODEL => Some(String::new()),
+                                    _ => Some(v),
+                                });
+                            },
+                            option { value: "", {t!(l, "stats-all-models")} }
+                            for m in models.iter() {
+                                if m.is_empty() {
+                                    option { value: UNRECORDED_MODEL, {t!(l, "stats-model-unrecorded")} }
+                                } else {
+                                    option { value: "{m}", "{m}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(msg) = &*error.read() {
+                    div { class: "text-red-700 text-base text-center bg-red-50 p-3 rounded-xl shadow font-semibold", {t!(l, "common-error", error = msg.as_str())} }
+                }
+
+                // 总体
+                div { class: "grid grid-cols-3 gap-4",
+                    div { class: "bg-white/90 rounded-xl shadow p-5 flex flex-col items-center",
+                        span { class: "text-xs text-gray-400", {t!(l, "stats-accuracy")} }
+                        span { class: "text-3xl font-bold text-cyan-800", "{percent(report.accuracy)}" }
+                    }
+                    div { class: "bg-white/90 rounded-xl shadow p-5 flex flex-col items-center",
+                        span { class: "text-xs text-gray-400", {t!(l, "stats-labeled")} }
+                        span { class: "text-3xl font-bold text-cyan-800", "{report.total}" }
+                    }
+                    div { class: "bg-white/90 rounded-xl shadow p-5 flex flex-col items-center",
+                        span { class: "text-xs text-gray-400", {t!(l, "stats-correct")} }
+                        span { class: "text-3xl font-bold text-cyan-800", "{report.correct}" }
+                    }
+                }
+
+                // 混淆矩阵
+                div { class: "bg-white/90 rounded-xl shadow-lg p-6",
+                    h2 { class: "text-lg font-bold text-cyan-700 mb-3", {t!(l, "stats-confusion")} }
+                    table { class: "table table-sm text-center",
+                        thead {
+                            tr {
+                                th {}
+                                for label in report.labels.iter() {
+                                    th { {label_name(*label, l)} }
+                                }
+                            }
+                        }
+                        tbody {
+                            for (row, actual) in report.matrix.iter().zip(report.labels.iter()) {
+                                tr {
+                                    th { {label_name(*actual, l)} }
+                                    for (cell, predict) in row.iter().zip(report.labels.iter()) {
+                                        td {
+                                            class: if actual == predict { "font-bold text-cyan-900" } else { "text-gray-600" },
+                                            style: format!("background: rgba(14,165,233,{:.2});", *cell as f32 / max_cell as f32 * 0.5),
+                                            "{cell}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // 各类别指标
+                div { class: "bg-white/90 rounded-xl shadow-lg p-6",
+                    h2 { class: "text-lg font-bold text-cyan-700 mb-3", {t!(l, "stats-per-class")} }
+                    table { class: "table table-sm",
+                        thead {
+                            tr {
+                                th { {t!(l, "stats-class")} }
+                                th { {t!(l, "stats-support")} }
+                                th { {t!(l, "stats-predicted")} }
+                                th { {t!(l, "stats-precision")} }
+                                th { {t!(l, "stats-recall")} }
+                            }
+                        }
+                        tbody {
+                            for c in report.classes.iter() {
+                                tr {
+                                    td { {label_name(c.label, l)} }
+                                    td { "{c.support}" }
+                                    td { "{c.predicted}" }
+                                    td { "{percent(c.precision)}" }
+                                    td { "{percent(c.recall)}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // 准确率随时间变化
+                div { class: "bg-white/90 rounded-xl shadow-lg p-6",
+                    h2 { class: "text-lg font-bold text-cyan-700 mb-3", {t!(l, "stats-daily")} }
+                    if report.timeline.is_empty() {
+                        div { class: "text-gray-400 text-center py-6", {t!(l, "stats-no-labels")} }
+                    }
+                    for p in report.timeline.iter() {
+                        div { class: "flex items-center gap-3 mb-2",
+                            span { class: "w-28 text-xs text-gray-500 font-mono", "{p.day}" }
+                            progress {
+                                class: "progress progress-primary flex-1 h-2",
+                                max: "100",
+                                value: "{p.accuracy.unwrap_or(0.0) * 100.0}",
+                            }
+                            span { class: "w-32 text-xs text-gray-600 text-right", "{percent(p.accuracy)} ({p.correct}/{p.total})" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! 把在场、状态变化与锁屏事件发布到 HTTP webhook 和 MQTT，供灯光、Home Assistant 等联动
+//!
+//! 每个出口一个任务，发送失败按 `RetryConfig` 退避重试，仍失败则进入离线队列，
+//! 恢复后按顺序补发。
+
+use std::{sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    errors::AppResult, infrastructure::json_store::default_state_path, runtime::RuntimeEvent,
+    supervisor::Supervisor,
+};
+
+pub mod mqtt;
+pub mod queue;
+pub mod template;
+pub mod webhook;
+
+use queue::OfflineQueue;
+
+/// 离线队列补发间隔
+const DRAIN_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublishConfig {
+    pub webhooks: Vec<webhook::WebhookConfig>,
+    pub mqtt: Vec<mqtt::MqttConfig>,
+    pub retry: RetryConfig,
+    /// 每个出口离线队列的最大条数
+    pub max_queue: usize,
+}
+
+impl Default for PublishConfig {
+    fn default() -> Self {
+        Self {
+            webhooks: Vec::new(),
+            mqtt: Vec::new(),
+            retry: RetryConfig::default(),
+            max_queue: 1000,
+        }
+    }
+}
+
+impl PublishConfig {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty() && self.mqtt.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 包含第一次在内的尝试次数
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// 第 `attempt` 次失败后的等待时间
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ms = self
+            .initial_backoff_ms
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_backoff_ms);
+        Duration::from_millis(ms)
+    }
+}
+
+/// 对外发布的事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// 目标离开或回来
+    Presence,
+    /// 在场状态机的状态变化
+    State,
+    Lock,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Presence => "presence",
+            Self::State => "state",
+            Self::Lock => "lock",
+        }
+    }
+}
+
+/// 待发布的消息，`context` 为模板可引用的字段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub kind: EventKind,
+    pub ts: i64,
+    pub context: Value,
+}
+
+impl Message {
+    fn new(kind: EventKind, ts: i64, target: &str, fields: Value) -> Self {
+        let time = chrono::DateTime::from_timestamp_millis(ts)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        let mut context = json!({
+            "kind": kind.as_str(),
+            "ts": ts,
+            "time": time,
+            "target": target,
+        });
+        if let (Some(context), Value::Object(fields)) = (context.as_object_mut(), fields) {
+            context.extend(fields);
+        }
+        Self { kind, ts, context }
+    }
+}
+
+/// 消息出口
+#[async_trait::async_trait]
+pub trait EventSink: Send + Sync {
+    /// 日志与离线队列文件名中使用
+    fn name(&self) -> String;
+    /// 为空表示接收所有类型
+    fn events(&self) -> &[EventKind];
+    async fn publish(&self, message: &Message) -> AppResult<()>;
+}
+
+/// 把运行时事件转换为对外消息，`state` 为在场状态机的每次转换
+#[derive(Debug, Clone, Copy)]
+pub struct EventMapper;
+
+impl EventMapper {
+    pub fn map(&self, event: &RuntimeEvent, target: &str) -> Option<Message> {
+        match event {
+            RuntimeEvent::Presence { ts, present } => Some(Message::new(
+                EventKind::Presence,
+                *ts,
+                target,
+                json!({ "present": present }),
+            )),
+            RuntimeEvent::Transition {
+                ts,
+                from,
+                to,
+                reason,
+            } => Some(Message::new(
+                EventKind::State,
+                *ts,
+                target,
+                json!({ "state": to, "from": from, "reason": reason }),
+            )),
+            RuntimeEvent::Lock { ts, locked, reason } => Some(Message::new(
+                EventKind::Lock,
+                *ts,
+                target,
+                json!({ "locked": locked, "reason": reason }),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// 按配置创建出口并启动发布任务，没有配置出口时什么都不做
+pub fn start(
+    config: &PublishConfig,
+    events: &broadcast::Sender<RuntimeEvent>,
+    target: watch::Receiver<String>,
+    supervisor: &Supervisor,
+) {
+    let mut sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    for c in &config.webhooks {
+        match webhook::WebhookSink::new(c.clone()) {
+            Ok(sink) => sinks.push(Arc::new(sink)),
+            Err(e) => warn!("Skipping webhook {}: {}", c.url, e),
+        }
+    }
+    for c in &config.mqtt {
+        sinks.push(Arc::new(mqtt::MqttSink::start(c.clone(), supervisor)));
+    }
+    if sinks.is_empty() {
+        return;
+    }
+
+    let mut senders = Vec::new();
+    for sink in sinks {
+        let (tx, rx) = mpsc::channel(64);
+        let path = default_state_path(&format!("publish-{}.json", file_name(&sink.name())));
+        let (max_queue, retry) = (config.max_queue, config.retry.clone());
+        info!("Publishing events to {}", sink.name());
+        // 重启后从状态文件读回离线队列
+        supervisor.spawn_with("publish-sink", rx, {
+            let sink = sink.clone();
+            move |mut rx, token| async move {
+                let queue = OfflineQueue::new(Some(path), max_queue);
+                run_sink(sink, &mut rx, queue, retry, token).await;
+                Ok(())
+            }
+        });
+        senders.push((sink, tx));
+    }
+    let events = events.clone();
+    supervisor.spawn("publish", move |token| {
+        let (events, target, senders) = (events.subscribe(), target.clone(), senders.clone());
+        async move {
+            dispatch(events, target, senders, token).await;
+            Ok(())
+        }
+    });
+}
+
+/// 出口名转为可用作文件名的形式
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// 把事件分发给各出口，出口处理不过来时丢弃并记录
+pub async fn dispatch(
+    mut rx: broadcast::Receiver<RuntimeEvent>,
+    target: watch::Receiver<String>,
+    sinks: Vec<(Arc<dyn EventSink>, mpsc::Sender<Message>)>,
+    token: CancellationToken,
+) {
+    let mapper = EventMapper;
+    loop {
+        let event = tokio::select! {
+            _ = token.cancelled() => break,
+            event = rx.recv() => match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(n)) => {
+                    warn!("Publisher lagged, skipped {} events", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        let target = target.borrow().clone();
+        let Some(message) = mapper.map(&event, &target) else {
+            continue;
+        };
+        for (sink, tx) in &sinks {
+            let events = sink.events();
+            if !events.is_empty() && !events.contains(&message.kind) {
+                continue;
+            }
+            if tx.try_send(message.clone()).is_err() {
+                warn!("Publish sink {} is busy, dropping message", sink.name());
+            }
+        }
+    }
+}
+
+/// 单个出口：先补发离线队列，再发送新消息，保持顺序
+pub async fn run_sink(
+    sink: Arc<dyn EventSink>,
+    rx: &mut mpsc::Receiver<Message>,
+    mut queue: OfflineQueue,
+    retry: RetryConfig,
+    token: CancellationToken,
+) {
+    let mut tick = tokio::time::interval(DRAIN_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            message = rx.recv() => match message {
+                Some(message) => queue.push(message),
+                None => break,
+            },
+            _ = tick.tick() => {}
+        }
+        if queue.is_empty() {
+            continue;
+        }
+        drain(sink.as_ref(), &mut queue, &retry, &token).await;
+        // 只在队列有变化时落盘，消息发出即出队时不写文件
+        queue.save();
+    }
+    queue.save();
+}
+
+/// 依次发送队首消息，遇到发送失败时停下，等下次补发
+async fn drain(
+    sink: &dyn EventSink,
+    queue: &mut OfflineQueue,
+    retry: &RetryConfig,
+    token: &CancellationToken,
+) {
+    while let Some(message) = queue.front() {
+        match send_with_retry(sink, message, retry, token).await {
+            Ok(()) => {
+                queue.pop();
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to publish to {}, {} messages queued: {}",
+                    sink.name(),
+                    queue.len(),
+                    e
+                );
+                return;
+            }
+        }
+    }
+}
+
+async fn send_with_retry(
+    sink: &dyn EventSink,
+    message: &Message,
+    retry: &RetryConfig,
+    token: &CancellationToken,
+) -> AppResult<()> {
+    let mut attempt = 0;
+    loop {
+        let err = match sink.publish(message).await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        attempt += 1;
+        if attempt >= retry.max_attempts.max(1) || token.is_cancelled() {
+            return Err(err);
+        }
+        tokio::select! {
+            _ = token.cancelled() => return Err(err),
+            _ = tokio::time::sleep(retry.backoff(attempt - 1)) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ble::presence_state::{PresenceState, TransitionReason},
+        domain::value_objects::ModelResult,
+    };
+
+    #[test]
+    fn test_mapper() {
+        let mapper = EventMapper;
+        let m = mapper
+            .map(
+                &RuntimeEvent::Transition {
+                    ts: 1,
+                    from: PresenceState::Leaving,
+                    to: PresenceState::Absent,
+                    reason: TransitionReason::Quiet {
+                        secs: 15,
+                        threshold: -75.0,
+                    },
+                },
+                "abc",
+            )
+            .unwrap();
+        assert_eq!(m.kind, EventKind::State);
+        assert_eq!(m.context["state"], "absent");
+        assert_eq!(m.context["from"], "leaving");
+        assert_eq!(m.context["reason"]["kind"], "quiet");
+        assert_eq!(m.context["target"], "abc");
+        // 模型预测不对外发布
+        assert!(mapper
+            .map(
+                &RuntimeEvent::Prediction {
+                    ts: 2,
+                    result: ModelResult::Stationary
+                },
+                "abc"
+            )
+            .is_none());
+
+        let m = mapper
+            .map(
+                &RuntimeEvent::Presence {
+                    ts: 4,
+                    present: false,
+                },
+                "abc",
+            )
+            .unwrap();
+        assert_eq!(m.context["present"], false);
+        assert_eq!(m.context["kind"], "presence");
+    }
+
+    #[test]
+    fn test_backoff() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.backoff(0), Duration::from_millis(500));
+        assert_eq!(retry.backoff(2), Duration::from_millis(2000));
+        assert_eq!(retry.backoff(30), Duration::from_millis(30_000));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Outgoing, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    errors::{AppResult, PublishError},
+    supervisor::Supervisor,
+};
+
+use super::{template::render, EventKind, EventSink, Message};
+
+/// 断线后重连的间隔
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// 等待发出与确认的时间，超时视为发送失败
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 连接任务观察到的发送进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// 消息已写出，附带报文 ID
+    Sent(u16),
+    /// QoS 1 的 PubAck 或 QoS 2 的 PubComp
+    Acked(u16),
+    Disconnected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 主题模板
+    pub topic: String,
+    /// 消息模板，为空时发送整个上下文的 JSON
+    pub payload: Option<String>,
+    /// 0、1 或 2
+    pub qos: u8,
+    pub retain: bool,
+    pub events: Vec<EventKind>,
+    pub keep_alive_secs: u64,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "blue-lock".to_string(),
+            username: None,
+            password: None,
+            topic: "blue-lock/{{target}}/{{kind}}".to_string(),
+            payload: None,
+            qos: 1,
+            retain: false,
+            events: Vec::new(),
+            keep_alive_secs: 30,
+        }
+    }
+}
+
+/// MQTT 出口，连接由后台任务维护，断线期间发布失败并进入离线队列。
+/// QoS 1/2 等到 broker 确认才算发送成功，确认前断线的消息留在离线队列中重发（至少一次，可能重复）；
+/// QoS 0 写出即算成功，写出后丢失的消息无法补发（至多一次）
+pub struct MqttSink {
+    config: MqttConfig,
+    client: AsyncClient,
+    connected: Arc<AtomicBool>,
+    /// `run_sink` 逐条发送，同一时间只有一条消息在等待确认
+    deliveries: Mutex<mpsc::UnboundedReceiver<Delivery>>,
+}
+
+impl MqttSink {
+    /// 创建客户端并在 `supervisor` 下维持连接
+    pub fn start(config: MqttConfig, supervisor: &Supervisor) -> Self {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(config.keep_alive_secs.max(5)));
+        if let (Some(user), Some(pass)) = (&config.username, &config.password) {
+            options.set_credentials(user, pass);
+        }
+        let (client, eventloop) = AsyncClient::new(options, 64);
+        let (delivery_tx, delivery_rx) = mpsc::unbounded_channel();
+        let sink = Self {
+            config,
+            client,
+            connected: Arc::new(AtomicBool::new(false)),
+            deliveries: Mutex::new(delivery_rx),
+        };
+        let (connected, name) = (sink.connected.clone(), sink.name());
+        supervisor.spawn_with("mqtt", eventloop, move |mut eventloop, token| async move {
+            poll(&mut eventloop, connected, delivery_tx, name, token).await;
+            Ok(())
+        });
+        sink
+    }
+
+    fn qos(&self) -> QoS {
+        match self.config.qos {
+            0 => QoS::AtMostOnce,
+            2 => QoS::ExactlyOnce,
+            _ => QoS::AtLeastOnce,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSink for MqttSink {
+    /// 包含 client_id，同一 broker 上的多个出口使用各自的离线队列
+    fn name(&self) -> String {
+        format!(
+            "mqtt://{}@{}:{}",
+            self.config.client_id, self.config.host, self.config.port
+        )
+    }
+
+    fn events(&self) -> &[EventKind] {
+        &self.config.events
+    }
+
+    async fn publish(&self, message: &Message) -> AppResult<()> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(PublishError::MqttDisconnected {
+                broker: self.name(),
+            }
+            .into());
+        }
+        let topic = render(&self.config.topic, &message.context);
+        let payload = match &self.config.payload {
+            Some(template) => render(template, &message.context),
+            None => message.context.to_string(),
+        };
+        let mut deliveries = self.deliveries.lock().await;
+        // 丢弃上一条超时消息迟到的进度
+        while deliveries.try_recv().is_ok() {}
+        // 只是放入客户端的请求队列，要等连接任务写出并收到确认
+        self.client
+            .publish(topic, self.qos(), self.config.retain, payload)
+            .await
+            .map_err(|e| PublishError::Mqtt { source: e })?;
+        let delivered =
+            tokio::time::timeout(ACK_TIMEOUT, wait_delivery(&mut deliveries, self.qos()))
+                .await
+                .unwrap_or(false);
+        if !delivered {
+            return Err(PublishError::MqttUnacknowledged {
+                broker: self.name(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// 等待刚放入队列的消息写出（QoS 0）或被确认（QoS 1/2），断线时返回 `false`
+async fn wait_delivery(deliveries: &mut mpsc::UnboundedReceiver<Delivery>, qos: QoS) -> bool {
+    let mut pkid = None;
+    while let Some(delivery) = deliveries.recv().await {
+        match delivery {
+            Delivery::Sent(_) if qos == QoS::AtMostOnce => return true,
+            Delivery::Sent(id) if pkid.is_none() => pkid = Some(id),
+            Delivery::Acked(id) if pkid == Some(id) => return true,
+            Delivery::Disconnected => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 驱动连接并记录连接状态，出错后等待片刻自动重连
+async fn poll(
+    eventloop: &mut EventLoop,
+    connected: Arc<AtomicBool>,
+    deliveries: mpsc::UnboundedSender<Delivery>,
+    name: String,
+    token: CancellationToken,
+) {
+    loop {
+        let event = tokio::select! {
+            _ = token.cancelled() => break,
+            event = eventloop.poll() => event,
+        };
+        match event {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                info!("Connected to {}", name);
+                connected.store(true, Ordering::SeqCst);
+            }
+            Ok(Event::Outgoing(Outgoing::Publish(pkid))) => {
+                let _ = deliveries.send(Delivery::Sent(pkid));
+            }
+            Ok(Event::Incoming(Packet::PubAck(ack))) => {
+                let _ = deliveries.send(Delivery::Acked(ack.pkid));
+            }
+            Ok(Event::Incoming(Packet::PubComp(comp))) => {
+                let _ = deliveries.send(Delivery::Acked(comp.pkid));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let _ = deliveries.send(Delivery::Disconnected);
+                if connected.swap(false, Ordering::SeqCst) {
+                    warn!("Disconnected from {}: {}", name, e);
+                }
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn deliver(qos: QoS, progress: &[Delivery]) -> bool {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        for d in progress {
+            tx.send(*d).unwrap();
+        }
+        drop(tx);
+        wait_delivery(&mut rx, qos).await
+    }
+
+    #[tokio::test]
+    async fn test_wait_delivery() {
+        use Delivery::*;
+        assert!(deliver(QoS::AtLeastOnce, &[Sent(3), Acked(2), Acked(3)]).await);
+        // 写出后、确认前断线，消息留在离线队列
+        assert!(!deliver(QoS::AtLeastOnce, &[Sent(3), Disconnected, Acked(3)]).await);
+        assert!(!deliver(QoS::ExactlyOnce, &[Sent(4)]).await);
+        assert!(deliver(QoS::AtMostOnce, &[Sent(0)]).await);
+        assert!(!deliver(QoS::AtMostOnce, &[Disconnected]).await);
+    }
+}
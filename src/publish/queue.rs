@@ -0,0 +1,126 @@
+use std::{collections::VecDeque, path::PathBuf};
+
+use tracing::warn;
+
+use crate::infrastructure::json_store::JsonStore;
+
+use super::Message;
+
+/// 发送失败的消息按顺序排队，保存到状态文件，重启后继续补发；超出上限时丢弃最旧的
+pub struct OfflineQueue {
+    store: Option<JsonStore<VecDeque<Message>>>,
+    messages: VecDeque<Message>,
+    capacity: usize,
+    /// 上次保存后是否有变化
+    dirty: bool,
+    /// 状态文件中是否没有消息
+    saved_empty: bool,
+}
+
+impl OfflineQueue {
+    /// `path` 为空时只保存在内存中
+    pub fn new(path: Option<PathBuf>, capacity: usize) -> Self {
+        let store = path.map(JsonStore::new);
+        let messages: VecDeque<Message> = store.as_ref().map(JsonStore::load).unwrap_or_default();
+        let mut queue = Self {
+            saved_empty: messages.is_empty(),
+            messages,
+            store,
+            capacity: capacity.max(1),
+            dirty: false,
+        };
+        queue.truncate();
+        queue
+    }
+
+    pub fn push(&mut self, message: Message) {
+        self.dirty = true;
+        self.messages.push_back(message);
+        self.truncate();
+    }
+
+    pub fn front(&self) -> Option<&Message> {
+        self.messages.front()
+    }
+
+    pub fn pop(&mut self) -> Option<Message> {
+        self.dirty = true;
+        self.messages.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// 有变化时写入状态文件；发出即出队的消息不落盘
+    pub fn save(&mut self) {
+        if !self.dirty || (self.saved_empty && self.messages.is_empty()) {
+            self.dirty = false;
+            return;
+        }
+        let Some(store) = &self.store else {
+            return;
+        };
+        match store.save(&self.messages) {
+            Ok(()) => {
+                self.dirty = false;
+                self.saved_empty = self.messages.is_empty();
+            }
+            Err(e) => warn!("Failed to save publish queue: {}", e),
+        }
+    }
+
+    fn truncate(&mut self) {
+        let overflow = self.messages.len().saturating_sub(self.capacity);
+        if overflow > 0 {
+            warn!("Publish queue full, dropping {} oldest messages", overflow);
+            self.messages.drain(..overflow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{domain::entity::rssi_event::now_millis, publish::EventKind};
+
+    fn message(ts: i64) -> Message {
+        Message {
+            kind: EventKind::Lock,
+            ts,
+            context: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_save_only_on_change() {
+        let path = std::env::temp_dir().join(format!("blue-lock-queue-{}.json", now_millis()));
+        let mut queue = OfflineQueue::new(Some(path.clone()), 10);
+
+        // 发出即出队，不写文件
+        queue.push(message(1));
+        queue.pop();
+        queue.save();
+        assert!(!path.exists());
+
+        queue.push(message(2));
+        queue.save();
+        assert_eq!(OfflineQueue::new(Some(path.clone()), 10).len(), 1);
+        std::fs::remove_file(&path).unwrap();
+        // 没有变化时不再写入
+        queue.save();
+        assert!(!path.exists());
+
+        // 补发成功后清空状态文件
+        queue.pop();
+        queue.save();
+        assert!(OfflineQueue::new(Some(path.clone()), 10).is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
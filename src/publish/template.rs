@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+/// 以 `{{name}}` 引用上下文字段，字符串按原样替换，其他值按 JSON 输出；
+/// 未知字段替换为空，`{{json}}` 输出整个上下文
+pub fn render(template: &str, context: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        match name {
+            "json" => out.push_str(&context.to_string()),
+            name => match context.get(name) {
+                Some(Value::String(s)) => out.push_str(s),
+                Some(Value::Null) | None => {}
+                Some(v) => out.push_str(&v.to_string()),
+            },
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let context = json!({ "kind": "presence", "present": true, "target": "abc" });
+        assert_eq!(
+            render("blue-lock/{{ target }}/{{kind}}", &context),
+            "blue-lock/abc/presence"
+        );
+        assert_eq!(
+            render(r#"{"on":{{present}},"x":"{{missing}}"}"#, &context),
+            r#"{"on":true,"x":""}"#
+        );
+        assert_eq!(render("{{json}}", &json!({ "a": 1 })), r#"{"a":1}"#);
+        assert_eq!(render("open {{ end", &context), "open {{ end");
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, time::Duration};
+
+use reqwest::{header::CONTENT_TYPE, Method};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{AppResult, PublishError};
+
+use super::{template::render, EventKind, EventSink, Message};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// 可使用模板字段，如 `http://ha.local/api/webhook/{{target}}`
+    pub url: String,
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    /// 请求体模板，为空时发送整个上下文的 JSON
+    pub body: Option<String>,
+    pub content_type: String,
+    /// 只发送这些类型，为空表示全部
+    pub events: Vec<EventKind>,
+    pub timeout_secs: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            method: "POST".to_string(),
+            headers: HashMap::new(),
+            body: None,
+            content_type: "application/json".to_string(),
+            events: Vec::new(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// HTTP webhook，非 2xx 响应视为失败
+pub struct WebhookSink {
+    config: WebhookConfig,
+    method: Method,
+    client: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Result<Self, PublishError> {
+        let method = Method::from_bytes(config.method.to_uppercase().as_bytes()).map_err(|_| {
+            PublishError::InvalidSink {
+                sink: config.url.clone(),
+                reason: format!("unsupported method {}", config.method),
+            }
+        })?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+            .map_err(|e| PublishError::Http {
+                url: config.url.clone(),
+                source: e,
+            })?;
+        Ok(Self {
+            config,
+            method,
+            client,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSink for WebhookSink {
+    fn name(&self) -> String {
+        self.config.url.clone()
+    }
+
+    fn events(&self) -> &[EventKind] {
+        &self.config.events
+    }
+
+    async fn publish(&self, message: &Message) -> AppResult<()> {
+        let url = render(&self.config.url, &message.context);
+        let body = match &self.config.body {
+            Some(template) => render(template, &message.context),
+            None => message.context.to_string(),
+        };
+        let mut request = self
+            .client
+            .request(self.method.clone(), &url)
+            .header(CONTENT_TYPE, &self.config.content_type)
+            .body(body);
+        for (name, value) in &self.config.headers {
+            request = request.header(name, render(value, &message.context));
+        }
+        let response = request.send().await.map_err(|e| PublishError::Http {
+            url: url.clone(),
+            source: e,
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PublishError::HttpStatus {
+                url,
+                status: status.as_u16(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -9,10 +9,16 @@ pub enum Route {
     Home,
     #[route("/device")]
     Device,
+    #[route("/device/:id")]
+    DeviceDetail { id: String },
     #[route("/about")]
     About,
     #[route("/log")]
     Log,
     #[route("/label")]
     Label,
+    #[route("/stats")]
+    Stats,
+    #[route("/settings")]
+    Settings,
 }
@@ -0,0 +1,1231 @@
+//! 核心运行时：数据库、扫描、检测、推理与锁屏
+//!
+//! 桌面界面与无界面守护进程（`--headless`）共用同一套流程，界面只订阅 `RuntimeEvent`。
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use sea_orm::Database;
+use serde::{Deserialize, Serialize};
+use shaku::HasComponent;
+use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    application::{
+        command::{CreateSampleCommand, LabelSampleCommand},
+        query::ListSamplesQuery,
+        sample_service::ISampleService,
+    },
+    ble::{
+        adapter::{AdapterLostPolicy, AdapterManager, AdapterState},
+        baseline::{BaselineManager, ThresholdOverride, Thresholds},
+        model::Model,
+        presence_detector::{DeviceStatus, PresenceDetector},
+        presence_state::{PresenceMachine, PresenceState, PresenceTiming, TransitionReason},
+        scanner::DeviceScanner,
+        service::{lock_system, unlock_system},
+        session::SessionRecorder,
+    },
+    di::Deps,
+    domain::{
+        entity::rssi_event::{now_millis, RssiEvent},
+        repo::{rssi_event_repo::IRssiEventRepo, trusted_device_repo::ITrustedDeviceRepo},
+        value_objects::ModelResult,
+    },
+    dto::detection::AlgoConfig,
+    errors::{AppError, AppResult, ConfigError, InvalidReason, ModelError},
+    infrastructure::{
+        json_store::JsonStore, migration::migrate, rssi_event_writer::RssiEventWriter, DbProvider,
+        DbProviderParameters,
+    },
+    notify::{desktop::DesktopNotifier, notify_task, Notifier},
+    publish::{self, PublishConfig},
+    snooze::{Snooze, SnoozeConfig, SnoozeManager, SnoozeReason},
+    supervisor::{Supervisor, TaskFailure},
+    unlock::{ProximityConfig, ProximityEvidence, UnlockDecision, UnlockGate, UnlockPolicy},
+};
+
+/// 内置模型
+const BUILTIN_MODEL: &[u8] = include_bytes!("../ai/hybrid_model.onnx");
+/// 关闭时等待后台任务退出的时间
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+/// 离开时因暂停跳过锁屏后，重新判断暂停是否结束的间隔
+const SNOOZE_RECHECK_MS: i64 = 15_000;
+/// 从数据库重新学习阈值的间隔
+const BASELINE_REBUILD_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 运行时配置，默认从 `~/.config/blue-lock/config.json` 读取
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    pub db_url: String,
+    /// 目标设备指纹，为空时使用受信任设备列表中的第一个
+    pub target: String,
+    /// 模型文件，为空时使用内置模型
+    pub model_path: Option<PathBuf>,
+    /// 会话录制文件
+    pub record_path: Option<PathBuf>,
+    /// 目标超时未出现时锁屏
+    pub auto_lock: bool,
+    /// 本地控制接口的 Unix socket，缺省见 `ipc::default_socket_path`
+    pub ipc_socket: Option<PathBuf>,
+    pub algo: AlgoConfig,
+    /// 免打扰时段与应用/全屏检测
+    pub snooze: SnoozeConfig,
+    /// 蓝牙关闭或适配器拔出时是否照常超时锁屏
+    pub adapter_lost: AdapterLostPolicy,
+    pub unlock: UnlockPolicy,
+    /// 靠近解锁需满足的条件
+    pub proximity: ProximityConfig,
+    /// 目标离开/回来、锁屏和异常时发送桌面通知
+    pub notifications: bool,
+    /// 事件发布到 webhook 与 MQTT
+    pub publish: PublishConfig,
+    /// 读取配置的文件，设置页保存时写回
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            db_url: "sqlite:sample.db?mode=rwc".to_string(),
+            target: String::new(),
+            model_path: None,
+            record_path: std::env::var_os("BLUE_LOCK_RECORD").map(PathBuf::from),
+            auto_lock: true,
+            ipc_socket: None,
+            algo: AlgoConfig::default(),
+            snooze: SnoozeConfig::default(),
+            adapter_lost: AdapterLostPolicy::default(),
+            unlock: UnlockPolicy::default(),
+            proximity: ProximityConfig::default(),
+            notifications: true,
+            publish: PublishConfig::default(),
+            path: None,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// `BLUE_LOCK_CONFIG` 优先，其次 `$XDG_CONFIG_HOME/blue-lock/config.json`
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("BLUE_LOCK_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        let dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(dir.join("blue-lock").join("config.json"))
+    }
+
+    /// 读取配置文件，未指定路径且默认文件不存在时使用默认配置
+    pub fn load(path: Option<&Path>) -> AppResult<Self> {
+        let path = match path {
+            Some(p) => p.to_path_buf(),
+            None => match Self::default_path() {
+                Some(p) if p.exists() => p,
+                path => return Ok(Self { path, ..Self::default() }),
+            },
+        };
+        let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::ConfigRead {
+            path: path.clone(),
+            source: e,
+        })?;
+        info!("Loaded config from {}", path.display());
+        let mut config: Self = serde_json::from_str(&content).map_err(|e| {
+            ConfigError::ConfigParse {
+                path: path.clone(),
+                source: e,
+            }
+        })?;
+        config.path = Some(path);
+        Ok(config)
+    }
+
+    /// 写回 `path`，没有时写到默认位置
+    pub fn save(&self) -> AppResult<()> {
+        let path = self
+            .path
+            .clone()
+            .or_else(Self::default_path)
+            .ok_or_else(|| ConfigError::InvalidConfig {
+                field: "path".to_string(),
+                reason: InvalidReason::NoConfigDir,
+            })?;
+        JsonStore::new(path).save(self)
+    }
+
+    /// 检查取值范围，设置页保存前调用
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |field: &str, reason: InvalidReason| {
+            Err(ConfigError::InvalidConfig {
+                field: field.to_string(),
+                reason,
+            })
+        };
+        let algo = &self.algo;
+        if algo.window_size != Model::INPUT_LEN {
+            return invalid(
+                "window_size",
+                InvalidReason::ModelInput {
+                    len: Model::INPUT_LEN,
+                },
+            );
+        }
+        if !(-100.0..=-30.0).contains(&algo.threshold) {
+            return invalid(
+                "threshold",
+                InvalidReason::Between {
+                    min: -100,
+                    max: -30,
+                },
+            );
+        }
+        if !(3..=3600).contains(&algo.timeout_secs) {
+            return invalid("timeout_secs", InvalidReason::Between { min: 3, max: 3600 });
+        }
+        if algo.leave_dwell_secs < 1 {
+            return invalid("leave_dwell_secs", InvalidReason::AtLeast { min: 1 });
+        }
+        if algo.leave_dwell_secs > algo.timeout_secs {
+            return invalid(
+                "leave_dwell_secs",
+                InvalidReason::NotAbove {
+                    field: "timeout_secs",
+                },
+            );
+        }
+        if algo.return_dwell_secs > 60 {
+            return invalid("return_dwell_secs", InvalidReason::AtMost { max: 60 });
+        }
+        if !(1..=10_000).contains(&algo.batch_size) {
+            return invalid(
+                "batch_size",
+                InvalidReason::Between {
+                    min: 1,
+                    max: 10_000,
+                },
+            );
+        }
+        if algo.raw_retention_secs < 3600 {
+            return invalid("raw_retention_secs", InvalidReason::AtLeast { min: 3600 });
+        }
+        if algo.raw_max_rows < 1000 {
+            return invalid("raw_max_rows", InvalidReason::AtLeast { min: 1000 });
+        }
+        let baseline = &algo.baseline;
+        if !(0.0 < baseline.absent_quantile
+            && baseline.absent_quantile < baseline.present_quantile
+            && baseline.present_quantile < 1.0)
+        {
+            return invalid("baseline", InvalidReason::Quantiles);
+        }
+        // 超出保留期的记录已被清理，学不到
+        if baseline.history_days.saturating_mul(24 * 3600) > algo.raw_retention_secs {
+            return invalid(
+                "baseline.history_days",
+                InvalidReason::NotAbove {
+                    field: "raw_retention_secs",
+                },
+            );
+        }
+        let proximity = &self.proximity;
+        if proximity.min_closer < 1 {
+            return invalid("proximity.min_closer", InvalidReason::AtLeast { min: 1 });
+        }
+        if !(100..=10_000).contains(&proximity.max_rssi_age_ms) {
+            return invalid(
+                "proximity.max_rssi_age_ms",
+                InvalidReason::Between {
+                    min: 100,
+                    max: 10_000,
+                },
+            );
+        }
+        if self.unlock == UnlockPolicy::Challenge {
+            match &proximity.challenge {
+                None => return invalid("proximity.challenge", InvalidReason::Required),
+                Some(c) if c.secret.len() < 16 => {
+                    return invalid(
+                        "proximity.challenge.secret",
+                        InvalidReason::MinLength { min: 16 },
+                    )
+                }
+                Some(_) => {}
+            }
+        }
+        if !self.db_url.starts_with("sqlite:") {
+            return invalid("db_url", InvalidReason::SqliteOnly);
+        }
+        if let Some(p) = self.model_path.as_ref().filter(|p| !p.exists()) {
+            return invalid("model_path", InvalidReason::FileMissing { path: p.clone() });
+        }
+        Ok(())
+    }
+
+    /// 与 `other` 相比，需要重启才能生效的字段
+    pub fn restart_required(&self, other: &Self) -> Vec<&'static str> {
+        let mut fields = vec![];
+        if self.db_url != other.db_url {
+            fields.push("db_url");
+        }
+        if self.record_path != other.record_path {
+            fields.push("record_path");
+        }
+        if self.ipc_socket != other.ipc_socket {
+            fields.push("ipc_socket");
+        }
+        fields
+    }
+}
+
+/// 锁屏/解锁的原因，`Display` 用于日志与录制，界面与通知见 `Localize`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LockReason {
+    /// 目标离开，蓝牙不可用时附带适配器状态
+    Absent {
+        transition: TransitionReason,
+        adapter: Option<AdapterState>,
+    },
+    /// 目标靠近并通过 `policy` 对应的设备验证
+    Verified { policy: UnlockPolicy },
+}
+
+impl std::fmt::Display for LockReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Absent {
+                transition,
+                adapter: Some(state),
+            } => write!(f, "{}，{}", state, transition),
+            Self::Absent { transition, .. } => write!(f, "{}", transition),
+            Self::Verified {
+                policy: UnlockPolicy::Challenge,
+            } => write!(f, "目标设备靠近并通过质询应答验证"),
+            Self::Verified { .. } => write!(f, "目标设备靠近并通过设备连接验证"),
+        }
+    }
+}
+
+/// 推送给界面、IPC 等订阅者的运行时事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuntimeEvent {
+    /// 目标设备的原始广播
+    Rssi(RssiEvent),
+    /// 送入模型的窗口
+    Window { data: Vec<f32> },
+    Prediction { ts: i64, result: ModelResult },
+    Lock { ts: i64, locked: bool, reason: LockReason },
+    /// 目标离开（`present = false`）或离开后确认回来
+    Presence { ts: i64, present: bool },
+    /// 在场状态机的每次转换
+    Transition { ts: i64, from: PresenceState, to: PresenceState, reason: TransitionReason },
+    /// 手动暂停变化，`None` 表示已恢复
+    Snooze { snooze: Option<Snooze> },
+    TargetChanged { target: String },
+    /// 适配器插拔或蓝牙开关
+    Adapter { state: AdapterState },
+    /// 设置已更新，新值通过 `Runtime::config` 读取
+    ConfigChanged,
+    /// 后台任务异常退出，随后按退避重启
+    TaskFailed {
+        task: String,
+        error: String,
+    },
+}
+
+/// 运行时状态快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeStatus {
+    pub target: String,
+    /// 当前不锁屏的原因，为空表示自动锁屏生效中
+    pub snooze: Option<SnoozeReason>,
+    pub adapter: AdapterState,
+    pub model: String,
+    #[serde(default)]
+    pub presence: PresenceState,
+    /// 目标设备当前小时的在场/离开阈值
+    #[serde(default)]
+    pub thresholds: Option<Thresholds>,
+    /// 以设备指纹为键
+    pub devices: HashMap<String, DeviceStatus>,
+}
+
+pub struct Runtime {
+    config_tx: watch::Sender<RuntimeConfig>,
+    algo_tx: watch::Sender<AlgoConfig>,
+    model_tx: watch::Sender<Arc<Model>>,
+    target_tx: watch::Sender<String>,
+    presence_tx: watch::Sender<PresenceState>,
+    snooze: Arc<SnoozeManager>,
+    deps: Arc<Deps>,
+    adapters: Arc<AdapterManager>,
+    scanner: Arc<DeviceScanner>,
+    detector: Arc<PresenceDetector>,
+    baseline: Arc<BaselineManager>,
+    recorder: Option<SessionRecorder>,
+    event_tx: broadcast::Sender<RuntimeEvent>,
+    supervisor: Supervisor,
+    /// 发布任务随设置重建，由 `supervisor` 的子级持有
+    publisher: tokio::sync::Mutex<Supervisor>,
+}
+
+impl Runtime {
+    pub async fn start(config: RuntimeConfig) -> AppResult<Arc<Self>> {
+        let deps = connect(&config.db_url).await?;
+        let target = resolve_target(&config, &deps).await?;
+
+        let model = load_model(config.model_path.as_deref())?;
+        info!("Using model {}", model.version());
+        let (model_tx, _) = watch::channel(Arc::new(model));
+        let (algo_tx, _) = watch::channel(config.algo.clone());
+        let (config_tx, _) = watch::channel(config.clone());
+
+        let (event_tx, _) = broadcast::channel(256);
+        let (target_tx, _) = watch::channel(target.clone());
+        let (presence_tx, _) = watch::channel(PresenceState::default());
+        let snooze = Arc::new(SnoozeManager::load(config.snooze.clone()));
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>(100);
+        let detector = Arc::new(PresenceDetector::new(config.algo.clone(), sample_tx).await?);
+
+        // 原始广播流落库
+        let rssi_repo: Arc<dyn IRssiEventRepo> = deps.resolve();
+        detector
+            .add_raw_sink(RssiEventWriter::spawn(rssi_repo, algo_tx.subscribe()))
+            .await;
+
+        // 自适应阈值只从在场期间的读数学习，由 presence_task 记录在场时段并实时修正
+        let baseline = Arc::new(BaselineManager::new(deps.resolve(), None));
+
+        // 在线检测与界面曲线
+        let (raw_tx, raw_rx) = mpsc::channel(256);
+        detector.add_raw_sink(raw_tx).await;
+
+        let recorder = match &config.record_path {
+            Some(path) => {
+                let r = SessionRecorder::create(path).await?;
+                info!("Recording session to {}", path.display());
+                detector.add_raw_sink(r.raw_sink()).await;
+                Some(r)
+            }
+            None => None,
+        };
+
+        // 没有适配器时照常启动，等待插入或开启蓝牙
+        let supervisor = Supervisor::default();
+        supervisor.spawn("failures", {
+            let (failures, event_tx) = (supervisor.subscribe_failures(), event_tx.clone());
+            move |token| failure_task(failures.resubscribe(), event_tx.clone(), token)
+        });
+        let adapters = AdapterManager::start(&supervisor, None).await?;
+        // 扫描只启动一次，检测器消费扫描器合并后的 RSSI 广播
+        let scanner = DeviceScanner::start(adapters.subscribe(), &supervisor);
+
+        supervisor.spawn("detection", {
+            let (detector, scanner) = (detector.clone(), scanner.clone());
+            let target = target_tx.subscribe();
+            move |token| {
+                let (detector, target) = (detector.clone(), target.clone());
+                let rssi_rx = scanner.subscribe_rssi();
+                async move {
+                    detector
+                        .start_detection(rssi_rx, target, token)
+                        .await
+                        .map_err(AppError::from)
+                }
+            }
+        });
+        // 以下两个任务独占 channel 接收端，重启后由新实例接着读
+        supervisor.spawn_with("presence", raw_rx, {
+            let (config, target) = (config_tx.subscribe(), target_tx.subscribe());
+            let adapter = adapters.subscribe_state();
+            let (baseline, scanner) = (baseline.clone(), scanner.clone());
+            let trusted: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+            let (presence_tx, snooze) = (presence_tx.clone(), snooze.clone());
+            let (recorder, event_tx) = (recorder.clone(), event_tx.clone());
+            move |mut raw_rx, token| async move {
+                presence_task(
+                    &mut raw_rx,
+                    config,
+                    target,
+                    adapter,
+                    baseline,
+                    scanner,
+                    trusted,
+                    presence_tx,
+                    snooze,
+                    recorder,
+                    event_tx,
+                    token,
+                )
+                .await
+            }
+        });
+        supervisor.spawn_with("inference", sample_rx, {
+            let (model, target) = (model_tx.subscribe(), target_tx.subscribe());
+            let (deps, recorder, event_tx) = (deps.clone(), recorder.clone(), event_tx.clone());
+            move |mut sample_rx, token| async move {
+                inference_task(
+                    model,
+                    &mut sample_rx,
+                    target,
+                    deps,
+                    recorder,
+                    event_tx,
+                    token,
+                )
+                .await
+            }
+        });
+        supervisor.spawn("baseline", {
+            let (baseline, deps) = (baseline.clone(), deps.clone());
+            let (config, target) = (config_tx.subscribe(), target_tx.subscribe());
+            move |token| {
+                baseline_task(
+                    baseline.clone(),
+                    config.clone(),
+                    target.clone(),
+                    deps.clone(),
+                    token,
+                )
+            }
+        });
+        supervisor.spawn("notify", {
+            let notifier: Arc<dyn Notifier> = Arc::new(DesktopNotifier::default());
+            let (event_tx, config) = (event_tx.clone(), config_tx.subscribe());
+            move |token| notify_task(event_tx.subscribe(), config.clone(), notifier.clone(), token)
+        });
+        let publisher = supervisor.child();
+        publish::start(&config.publish, &event_tx, target_tx.subscribe(), &publisher);
+        supervisor.spawn("snooze", {
+            let (snooze, event_tx) = (snooze.clone(), event_tx.clone());
+            move |token| snooze_task(snooze.clone(), event_tx.clone(), token)
+        });
+
+        info!("Runtime started, target {}", target);
+        let runtime = Arc::new(Self {
+            config_tx,
+            algo_tx,
+            model_tx,
+            target_tx,
+            presence_tx,
+            snooze,
+            deps,
+            adapters,
+            scanner,
+            detector,
+            baseline,
+            recorder,
+            event_tx,
+            supervisor,
+            publisher: tokio::sync::Mutex::new(publisher),
+        });
+
+        #[cfg(unix)]
+        {
+            let path = runtime
+                .config()
+                .ipc_socket
+                .unwrap_or_else(crate::ipc::default_socket_path);
+            match crate::ipc::server::serve(runtime.clone(), &path, runtime.supervisor.token()) {
+                Ok(task) => runtime.supervisor.track("ipc", task),
+                Err(e) => warn!("Failed to start IPC server on {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(runtime)
+    }
+
+    pub fn config(&self) -> RuntimeConfig {
+        self.config_tx.borrow().clone()
+    }
+
+    /// 校验并应用新设置：检测参数、锁屏策略、模型、暂停与发布设置立即生效，并写回配置文件。
+    /// 返回需要重启才能生效的字段
+    pub async fn apply_config(&self, mut config: RuntimeConfig) -> AppResult<Vec<&'static str>> {
+        config.validate()?;
+        let current = self.config();
+        config.path = current.path.clone();
+
+        if config.model_path != current.model_path {
+            let path = config.model_path.clone();
+            let model = tokio::task::spawn_blocking(move || load_model(path.as_deref()))
+                .await
+                .map_err(|e| AppError::ProcessingError { r: e.to_string() })??;
+            info!("Switched to model {}", model.version());
+            self.model_tx.send_replace(Arc::new(model));
+        }
+
+        config.save()?;
+        if config.snooze != current.snooze {
+            self.snooze.reconfigure(config.snooze.clone());
+        }
+        if config.publish != current.publish {
+            // 旧的出口关闭时保存各自的离线队列，新出口启动时读回
+            let mut publisher = self.publisher.lock().await;
+            publisher.shutdown(SHUTDOWN_GRACE).await;
+            *publisher = self.supervisor.child();
+            publish::start(
+                &config.publish,
+                &self.event_tx,
+                self.target_tx.subscribe(),
+                &publisher,
+            );
+            info!("Publish sinks restarted");
+        }
+        let restart = config.restart_required(&current);
+        if !restart.is_empty() {
+            info!("Settings saved, restart required for {:?}", restart);
+        }
+        self.algo_tx.send_replace(config.algo.clone());
+        self.config_tx.send_replace(config);
+        let _ = self.event_tx.send(RuntimeEvent::ConfigChanged);
+        Ok(restart)
+    }
+
+    /// 当前模型版本
+    pub fn model_version(&self) -> String {
+        self.model_tx.borrow().version().to_string()
+    }
+
+    /// 当前检测的目标设备指纹
+    pub fn target(&self) -> String {
+        self.target_tx.borrow().clone()
+    }
+
+    /// 切换目标设备，立即生效
+    pub fn set_target(&self, target: String) {
+        info!("Switching target to {}", target);
+        self.target_tx.send_replace(target.clone());
+        let _ = self.event_tx.send(RuntimeEvent::TargetChanged { target });
+    }
+
+    /// 目标设备的在场状态
+    pub fn presence_state(&self) -> PresenceState {
+        *self.presence_tx.borrow()
+    }
+
+    pub fn subscribe_presence(&self) -> watch::Receiver<PresenceState> {
+        self.presence_tx.subscribe()
+    }
+
+    /// 当前的手动暂停
+    pub fn snoozed(&self) -> Option<Snooze> {
+        self.snooze.current()
+    }
+
+    /// 暂停期间照常检测，只是不锁屏；`None` 表示恢复
+    pub fn set_snooze(&self, snooze: Option<Snooze>) {
+        info!("Snooze set to {:?}", snooze);
+        self.snooze.set(snooze);
+    }
+
+    /// 当前不锁屏的原因，包含免打扰时段与应用/全屏检测
+    pub async fn snooze_reason(&self) -> Option<SnoozeReason> {
+        self.snooze.reason().await
+    }
+
+    /// 某设备当前小时的阈值，手动阈值优先
+    pub fn thresholds(&self, device: &str) -> Thresholds {
+        self.baseline.thresholds(device, &self.config_tx.borrow().algo)
+    }
+
+    pub fn threshold_override(&self, device: &str) -> ThresholdOverride {
+        self.baseline.override_for(device)
+    }
+
+    /// 手动指定阈值，两项都为空时恢复自动学习
+    pub fn set_threshold_override(&self, device: &str, value: ThresholdOverride) -> AppResult<()> {
+        info!("Threshold override for {} set to {:?}", device, value);
+        self.baseline.set_override(device, value)?;
+        let _ = self.event_tx.send(RuntimeEvent::ConfigChanged);
+        Ok(())
+    }
+
+    pub async fn status(&self) -> RuntimeStatus {
+        let target = self.target();
+        RuntimeStatus {
+            thresholds: (!target.is_empty()).then(|| self.thresholds(&target)),
+            target,
+            snooze: self.snooze_reason().await,
+            adapter: self.adapters.state(),
+            model: self.model_version(),
+            presence: self.presence_state(),
+            devices: self.detector.status().await,
+        }
+    }
+
+    /// 人工标注样本，`id` 为空时标注目标设备最新的一条，返回样本 id
+    pub async fn label_sample(&self, id: Option<i32>, label: ModelResult) -> AppResult<i32> {
+        if !label.is_label() {
+            return Err(AppError::ProcessingError {
+                r: format!("{} is not a valid label", label),
+            });
+        }
+        let sample_srv: Arc<dyn ISampleService> = self.deps.resolve();
+        let id = match id {
+            Some(id) => id,
+            None => sample_srv
+                .list_samples(ListSamplesQuery {
+                    device: Some(self.target()),
+                    labeled: None,
+                    limit: Some(1),
+                })
+                .await?
+                .first()
+                .map(|s| s.id)
+                .ok_or(AppError::NotFound)?,
+        };
+        sample_srv
+            .label_sample(LabelSampleCommand {
+                id,
+                actual: label.into(),
+            })
+            .await?;
+        Ok(id)
+    }
+
+    pub fn deps(&self) -> Arc<Deps> {
+        self.deps.clone()
+    }
+
+    pub fn adapter_state(&self) -> AdapterState {
+        self.adapters.state()
+    }
+
+    pub fn adapters(&self) -> Arc<AdapterManager> {
+        self.adapters.clone()
+    }
+
+    pub fn scanner(&self) -> Arc<DeviceScanner> {
+        self.scanner.clone()
+    }
+
+    pub fn recorder(&self) -> Option<SessionRecorder> {
+        self.recorder.clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// 停止所有后台任务和适配器扫描，可重复调用
+    pub async fn shutdown(&self) {
+        if self.supervisor.is_cancelled() {
+            return;
+        }
+        info!("Runtime shutting down");
+        self.supervisor.shutdown(SHUTDOWN_GRACE).await;
+        self.publisher.lock().await.shutdown(SHUTDOWN_GRACE).await;
+        self.detector.shutdown().await;
+        self.scanner.stop().await;
+        info!("Runtime stopped");
+    }
+}
+
+/// 连接并迁移数据库，CLI 等不需要蓝牙的场景也可单独使用
+pub async fn connect(db_url: &str) -> AppResult<Arc<Deps>> {
+    let db = Database::connect(db_url)
+        .await
+        .map_err(|e| AppError::DbError { source: e })?;
+    migrate(&db).await?;
+    Ok(Arc::new(
+        Deps::builder()
+            .with_component_parameters::<DbProvider>(DbProviderParameters { conn: Arc::new(db) })
+            .build(),
+    ))
+}
+
+/// 配置中的目标优先，否则取最早加入的受信任设备
+async fn resolve_target(config: &RuntimeConfig, deps: &Deps) -> AppResult<String> {
+    if !config.target.is_empty() {
+        return Ok(config.target.clone());
+    }
+    let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+    match repo.list().await?.into_iter().next() {
+        Some(d) => Ok(d.fingerprint),
+        None => {
+            warn!("No target configured, add one with `blue-lock trust <fingerprint>`");
+            Ok(String::new())
+        }
+    }
+}
+
+pub fn load_model(path: Option<&Path>) -> AppResult<Model> {
+    let bytes = match path {
+        Some(p) => std::fs::read(p).map_err(|e| ModelError::ModelRead {
+            path: p.to_path_buf(),
+            source: e,
+        })?,
+        None => BUILTIN_MODEL.to_vec(),
+    };
+    Ok(Model::new(&bytes)?)
+}
+
+/// 窗口推理并保存样本
+async fn inference_task(
+    model: watch::Receiver<Arc<Model>>,
+    sample_rx: &mut mpsc::Receiver<Vec<f32>>,
+    target: watch::Receiver<String>,
+    deps: Arc<Deps>,
+    recorder: Option<SessionRecorder>,
+    event_tx: broadcast::Sender<RuntimeEvent>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    let sample_srv: Arc<dyn ISampleService> = deps.resolve();
+    loop {
+        let window = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            window = sample_rx.recv() => window.ok_or_else(|| AppError::ProcessingError {
+                r: "sample stream closed".to_string(),
+            })?,
+        };
+        let _ = event_tx.send(RuntimeEvent::Window {
+            data: window.clone(),
+        });
+        if let Some(r) = &recorder {
+            r.window(window.clone());
+        }
+
+        // 设置页切换模型后下一个窗口即用新模型
+        let model = model.borrow().clone();
+        let result = match model.inference(window.clone()) {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Inference failed: {}", e);
+                continue;
+            }
+        };
+        let _ = event_tx.send(RuntimeEvent::Prediction {
+            ts: now_millis(),
+            result,
+        });
+        if let Some(r) = &recorder {
+            r.prediction(result);
+        }
+
+        let device = target.borrow().clone();
+        let res = sample_srv
+            .create_sample(CreateSampleCommand {
+                device,
+                model: model.version().to_string(),
+                sample: window,
+                predict: result.into(),
+            })
+            .await;
+        if let Err(e) = res {
+            warn!("Failed to save sample: {}", e);
+        }
+    }
+}
+
+/// 锁屏/解锁的参数
+struct PresencePolicy {
+    timing: PresenceTiming,
+    auto_lock: bool,
+    adapter_lost: AdapterLostPolicy,
+    unlock: UnlockPolicy,
+}
+
+impl From<&RuntimeConfig> for PresencePolicy {
+    fn from(config: &RuntimeConfig) -> Self {
+        Self {
+            timing: PresenceTiming::from(&config.algo),
+            auto_lock: config.auto_lock,
+            adapter_lost: config.adapter_lost,
+            unlock: config.unlock,
+        }
+    }
+}
+
+/// 由在场状态机决定锁屏：进入 `Absent` 时锁屏，暂停期间跳过、暂停结束时仍离开则补锁；确认回来后在 `closer_window_secs` 内等待解锁条件，
+/// 满足时经设备验证解锁，否则保持锁定。切换目标后重新判断
+#[allow(clippy::too_many_arguments)]
+async fn presence_task(
+    raw_rx: &mut mpsc::Receiver<RssiEvent>,
+    mut config: watch::Receiver<RuntimeConfig>,
+    mut target: watch::Receiver<String>,
+    mut adapter: watch::Receiver<AdapterState>,
+    baseline: Arc<BaselineManager>,
+    scanner: Arc<DeviceScanner>,
+    trusted: Arc<dyn ITrustedDeviceRepo>,
+    presence_tx: watch::Sender<PresenceState>,
+    snooze: Arc<SnoozeManager>,
+    recorder: Option<SessionRecorder>,
+    event_tx: broadcast::Sender<RuntimeEvent>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    // 上次未正常退出或本任务重启前遗留的在场时段
+    if let Err(e) = baseline.end_present(None, now_millis()).await {
+        warn!("Failed to close presence spans: {}", e);
+    }
+    let mut policy = PresencePolicy::from(&*config.borrow_and_update());
+    let unlock_gate = |config: &RuntimeConfig| {
+        UnlockGate::from_config(config.unlock, config.proximity.clone(), scanner.clone())
+    };
+    let mut gate = unlock_gate(&config.borrow());
+    let mut machine = PresenceMachine::new(policy.timing, now_millis());
+    let mut evidence = ProximityEvidence::default();
+    let mut events = event_tx.subscribe();
+    // 真正锁过屏（非暂停跳过）才需要解锁
+    let mut screen_locked = false;
+    // 确认回来后等待解锁条件的截止时间
+    let mut unlock_deadline: Option<i64> = None;
+    // 因暂停跳过的锁屏，暂停结束时仍离开则补锁
+    let mut pending_lock: Option<TransitionReason> = None;
+    let mut lock_checked = 0;
+    let mut snooze_state = snooze.subscribe();
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    let thresholds =
+        |target: &str, config: &RuntimeConfig| baseline.thresholds(target, &config.algo);
+
+    loop {
+        // 是否重新判断待补的锁屏
+        let mut recheck = false;
+        let transition = tokio::select! {
+            event = raw_rx.recv() => match event {
+                Some(event) => {
+                    let (ts, rssi) = (event.ts, event.rssi as f32);
+                    let _ = event_tx.send(RuntimeEvent::Rssi(event.clone()));
+                    evidence.observe_rssi(ts, rssi);
+                    let t = thresholds(&target.borrow(), &config.borrow());
+                    let transition = machine.on_rssi(ts, rssi, &t);
+                    if machine.state() == PresenceState::Present {
+                        baseline.observe(&event, machine.last_good());
+                    }
+                    transition
+                }
+                None => {
+                    return Err(AppError::ProcessingError {
+                        r: "RSSI stream closed".to_string(),
+                    })
+                }
+            },
+            event = events.recv() => {
+                if let Ok(RuntimeEvent::Prediction { ts, result }) = event {
+                    evidence.observe_prediction(ts, result);
+                }
+                None
+            }
+            _ = token.cancelled() => break,
+            Ok(()) = target.changed() => {
+                if machine.state() == PresenceState::Present {
+                    end_present(&baseline, machine.last_good()).await;
+                }
+                machine.reset(now_millis());
+                evidence.clear();
+                screen_locked = false;
+                pending_lock = None;
+                unlock_deadline = None;
+                presence_tx.send_replace(machine.state());
+                continue;
+            }
+            Ok(()) = snooze_state.changed() => {
+                snooze_state.borrow_and_update();
+                recheck = true;
+                None
+            }
+            Ok(()) = config.changed() => {
+                policy = PresencePolicy::from(&*config.borrow_and_update());
+                gate = unlock_gate(&config.borrow());
+                machine.set_timing(policy.timing);
+                continue;
+            }
+            Ok(()) = adapter.changed() => {
+                let state = *adapter.borrow_and_update();
+                if state == AdapterState::Ready && policy.adapter_lost == AdapterLostPolicy::FailOpen {
+                    machine.hold(now_millis());
+                }
+                let _ = event_tx.send(RuntimeEvent::Adapter { state });
+                continue;
+            }
+            _ = tick.tick() => {
+                let now = now_millis();
+                let fail_open = *adapter.borrow() != AdapterState::Ready
+                    && policy.adapter_lost == AdapterLostPolicy::FailOpen;
+                // 没有目标时不做超时锁屏
+                if fail_open || target.borrow().is_empty() {
+                    machine.hold(now);
+                    continue;
+                }
+                // 免打扰时段、应用与全屏检测没有变化通知，定期重新判断
+                recheck = now - lock_checked >= SNOOZE_RECHECK_MS;
+                let t = thresholds(&target.borrow(), &config.borrow());
+                machine.on_tick(now, &t)
+            }
+        };
+
+        if let Some(transition) = transition {
+            presence_tx.send_replace(transition.to);
+            if transition.to == PresenceState::Present {
+                let device = target.borrow().clone();
+                if let Err(e) = baseline.begin_present(&device, transition.ts).await {
+                    warn!("Failed to record presence span: {}", e);
+                }
+            } else if transition.from == PresenceState::Present {
+                end_present(&baseline, machine.last_good()).await;
+            }
+            let _ = event_tx.send(RuntimeEvent::Transition {
+                ts: transition.ts,
+                from: transition.from,
+                to: transition.to,
+                reason: transition.reason,
+            });
+            if transition.to != PresenceState::Present {
+                unlock_deadline = None;
+            } else {
+                pending_lock = None;
+            }
+            let ts = transition.ts;
+            match (transition.from, transition.to) {
+                _ if transition.is_departure() => {
+                    let _ = event_tx.send(RuntimeEvent::Presence { ts, present: false });
+                    pending_lock = Some(transition.reason);
+                    lock_checked = 0;
+                    recheck = true;
+                }
+                (PresenceState::Returning, PresenceState::Present) => {
+                    let _ = event_tx.send(RuntimeEvent::Presence { ts, present: true });
+                    // 回到电脑前视同解锁会话
+                    snooze.end_until_unlock();
+                    if std::mem::take(&mut screen_locked) && policy.unlock != UnlockPolicy::LockOnly {
+                        unlock_deadline = Some(ts + gate.wait().as_millis() as i64);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if recheck && machine.state() == PresenceState::Absent {
+            if let Some(transition_reason) = pending_lock {
+                let first = lock_checked == 0;
+                lock_checked = now_millis();
+                match snooze.reason().await {
+                    Some(reason) if first => info!("Auto lock snoozed ({}), skip locking", reason),
+                    Some(reason) => debug!("Auto lock still snoozed ({})", reason),
+                    None => {
+                        pending_lock = None;
+                        let reason = LockReason::Absent {
+                            transition: transition_reason,
+                            adapter: match *adapter.borrow() {
+                                AdapterState::Ready => None,
+                                state => Some(state),
+                            },
+                        };
+                        info!("Locking: {}", reason);
+                        screen_locked = true;
+                        if policy.auto_lock {
+                            if let Err(e) = lock_system() {
+                                warn!("Failed to lock system: {}", e);
+                            }
+                        }
+                        if let Some(r) = &recorder {
+                            r.lock(true, reason.to_string());
+                        }
+                        let _ = event_tx.send(RuntimeEvent::Lock {
+                            ts: lock_checked,
+                            locked: true,
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+
+        let Some(deadline) = unlock_deadline else {
+            continue;
+        };
+        let now = now_millis();
+        let device = target.borrow().clone();
+        let t = thresholds(&device, &config.borrow());
+        // 条件满足前继续等待，超时或验证失败都保持锁定
+        let decision = match gate.ready(&evidence, now, &t) {
+            Err(reason) if now <= deadline => {
+                debug!("Waiting to unlock: {}", reason);
+                continue;
+            }
+            Err(reason) => UnlockDecision::Deny(reason),
+            // 只验证添加受信任设备时绑定的那台设备，不按名称指纹查找
+            Ok(()) => match trusted.get(&device).await {
+                Ok(Some(d)) if !d.address.is_empty() => {
+                    gate.decide(&d.address, &evidence, now, &t).await
+                }
+                Ok(_) => UnlockDecision::Deny("目标设备未绑定地址".to_string()),
+                Err(e) => UnlockDecision::Deny(format!("读取受信任设备失败：{}", e)),
+            },
+        };
+        unlock_deadline = None;
+        let reason = match decision {
+            UnlockDecision::Unlock(reason) => reason,
+            UnlockDecision::Deny(reason) => {
+                info!("Staying locked: {}", reason);
+                continue;
+            }
+        };
+        info!("Unlocking: {}", reason);
+        if policy.auto_lock {
+            if let Err(e) = unlock_system() {
+                warn!("Failed to unlock system: {}", e);
+            }
+        }
+        snooze.end_until_unlock();
+        if let Some(r) = &recorder {
+            r.lock(false, reason.to_string());
+        }
+        let _ = event_tx.send(RuntimeEvent::Lock {
+            ts: now,
+            locked: false,
+            reason,
+        });
+    }
+    if machine.state() == PresenceState::Present {
+        end_present(&baseline, machine.last_good()).await;
+    }
+    Ok(())
+}
+
+/// 在场时段到最后一次有效读数为止
+async fn end_present(baseline: &BaselineManager, last_good: i64) {
+    if let Err(e) = baseline.end_present(None, last_good).await {
+        warn!("Failed to close presence span: {}", e);
+    }
+}
+
+/// 启动时及每小时从历史重新学习受信任设备与目标的阈值，其间由 presence_task 用在场读数修正
+async fn baseline_task(
+    baseline: Arc<BaselineManager>,
+    config: watch::Receiver<RuntimeConfig>,
+    mut target: watch::Receiver<String>,
+    deps: Arc<Deps>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    let repo: Arc<dyn ITrustedDeviceRepo> = deps.resolve();
+    let mut tick = tokio::time::interval(BASELINE_REBUILD_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            _ = tick.tick() => {}
+            Ok(()) = target.changed() => {}
+        }
+        let mut devices: Vec<String> = match repo.list().await {
+            Ok(list) => list.into_iter().map(|d| d.fingerprint).collect(),
+            Err(e) => {
+                warn!("Failed to list trusted devices: {}", e);
+                Vec::new()
+            }
+        };
+        let current = target.borrow_and_update().clone();
+        if !current.is_empty() && !devices.contains(&current) {
+            devices.push(current);
+        }
+        let baseline_config = config.borrow().algo.baseline.clone();
+        if let Err(e) = baseline.rebuild(&devices, &baseline_config).await {
+            warn!("Failed to learn thresholds: {}", e);
+        }
+    }
+}
+
+/// 把后台任务的异常退出转为事件，供界面与通知提示
+async fn failure_task(
+    mut failures: broadcast::Receiver<TaskFailure>,
+    event_tx: broadcast::Sender<RuntimeEvent>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    loop {
+        let failure = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            failure = failures.recv() => match failure {
+                Ok(failure) => failure,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+        let _ = event_tx.send(RuntimeEvent::TaskFailed {
+            task: failure.task.to_string(),
+            error: failure.error,
+        });
+    }
+}
+
+/// 清理到期的暂停、跟踪会话解锁，并把暂停变化转发为事件
+async fn snooze_task(
+    snooze: Arc<SnoozeManager>,
+    event_tx: broadcast::Sender<RuntimeEvent>,
+    token: CancellationToken,
+) -> AppResult<()> {
+    let mut state = snooze.subscribe();
+    let mut tick = tokio::time::interval(Duration::from_secs(5));
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            _ = tick.tick() => {
+                let snooze = snooze.clone();
+                let _ = tokio::task::spawn_blocking(move || snooze.tick()).await;
+            }
+            changed = state.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                let snooze = state.borrow_and_update().snooze;
+                let _ = event_tx.send(RuntimeEvent::Snooze { snooze });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_save() {
+        let mut config = RuntimeConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.algo.window_size = 5;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidConfig { field, .. }) if field == "window_size"
+        ));
+        config.algo.window_size = Model::INPUT_LEN;
+        config.algo.timeout_secs = 1;
+        assert!(config.validate().is_err());
+        config.algo.timeout_secs = 30;
+        // 质询应答解锁必须配置质询参数
+        config.unlock = UnlockPolicy::Challenge;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidConfig { field, .. }) if field == "proximity.challenge"
+        ));
+
+        let path = std::env::temp_dir().join(format!("blue-lock-config-{}.json", now_millis()));
+        config.path = Some(path.clone());
+        config.unlock = UnlockPolicy::Connected;
+        config.save().unwrap();
+        let loaded = RuntimeConfig::load(Some(&path)).unwrap();
+        assert_eq!(loaded.algo.timeout_secs, 30);
+        assert_eq!(loaded.unlock, UnlockPolicy::Connected);
+        // 旧配置中的 proximity 按设备连接处理
+        let old: UnlockPolicy = serde_json::from_str(r#""proximity""#).unwrap();
+        assert_eq!(old, UnlockPolicy::Connected);
+        assert_eq!(loaded.path, Some(path.clone()));
+        assert!(loaded.restart_required(&config).is_empty());
+        // 暂停与发布设置即时生效，数据库等仍需重启
+        let mut changed = loaded.clone();
+        changed.snooze.inhibit_fullscreen = true;
+        changed.publish.max_queue = 10;
+        assert!(changed.restart_required(&loaded).is_empty());
+        changed.db_url = "sqlite:other.db".to_string();
+        assert_eq!(changed.restart_required(&loaded), vec!["db_url"]);
+        let _ = std::fs::remove_file(path);
+    }
+}
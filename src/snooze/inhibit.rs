@@ -0,0 +1,89 @@
+//! 系统状态探测：进程、全屏窗口、会话锁定
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::{fs, process::Command};
+
+    /// 返回第一个正在运行的进程名（匹配 `/proc/<pid>/comm`，忽略大小写）
+    pub fn running_app(apps: &[String]) -> Option<String> {
+        if apps.is_empty() {
+            return None;
+        }
+        fs::read_dir("/proc")
+            .ok()?
+            .flatten()
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .bytes()
+                    .all(|b| b.is_ascii_digit())
+            })
+            .filter_map(|e| fs::read_to_string(e.path().join("comm")).ok())
+            .find_map(|comm| {
+                let comm = comm.trim();
+                apps.iter().find(|a| a.eq_ignore_ascii_case(comm)).cloned()
+            })
+    }
+
+    /// 当前活动窗口是否全屏（X11，依赖 xprop）
+    pub fn fullscreen_active() -> bool {
+        let Some(window) = xprop(&["-root", "_NET_ACTIVE_WINDOW"]).and_then(|out| {
+            out.split_whitespace()
+                .last()
+                .filter(|id| id.starts_with("0x") && *id != "0x0")
+                .map(str::to_string)
+        }) else {
+            return false;
+        };
+        xprop(&["-id", &window, "_NET_WM_STATE"])
+            .is_some_and(|out| out.contains("_NET_WM_STATE_FULLSCREEN"))
+    }
+
+    fn xprop(args: &[&str]) -> Option<String> {
+        let output = Command::new("xprop").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// 通过 logind 读取当前会话是否锁定，无法判断时返回 `None`。
+    /// systemd 用户服务没有 `XDG_SESSION_ID`，此时取本用户的图形会话
+    pub fn session_locked() -> Option<bool> {
+        let session = std::env::var("XDG_SESSION_ID")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| {
+                let user = std::env::var("USER").ok()?;
+                loginctl(&["show-user", &user, "-p", "Display", "--value"])
+            })?;
+        match loginctl(&["show-session", &session, "-p", "LockedHint", "--value"])?.as_str() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn loginctl(args: &[&str]) -> Option<String> {
+        let output = Command::new("loginctl").args(args).output().ok()?;
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !value.is_empty()).then_some(value)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    pub fn running_app(_apps: &[String]) -> Option<String> {
+        None
+    }
+
+    pub fn fullscreen_active() -> bool {
+        false
+    }
+
+    pub fn session_locked() -> Option<bool> {
+        None
+    }
+}
+
+pub use imp::{fullscreen_active, running_app, session_locked};
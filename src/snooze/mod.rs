@@ -0,0 +1,421 @@
+//! 暂停自动锁屏
+//!
+//! 手动暂停（定时、直到下次解锁、无限期）持久化到状态文件，重启后继续生效；
+//! 免打扰时段与 Linux 下的应用/全屏检测按配置实时判断，配置可在运行中替换。
+
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+};
+
+use chrono::{Datelike as _, Local, Timelike as _};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::{
+    domain::entity::rssi_event::now_millis,
+    infrastructure::json_store::{default_state_path, JsonStore},
+};
+
+pub mod inhibit;
+
+/// 手动暂停
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Snooze {
+    /// 到指定时间（unix 毫秒）为止
+    Until { until: i64 },
+    /// 直到下一次解锁会话
+    UntilUnlock,
+    /// 直到手动恢复
+    Indefinite,
+}
+
+impl Snooze {
+    pub fn for_minutes(minutes: u64) -> Self {
+        Self::Until {
+            until: now_millis() + minutes as i64 * 60_000,
+        }
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self, Self::Until { until } if *until <= now)
+    }
+}
+
+/// 免打扰时段，`start`/`end` 为本地时间 `HH:MM`，可跨零点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietWindow {
+    pub start: String,
+    pub end: String,
+    /// 1 = 周一 … 7 = 周日，跨零点时按开始那天算；为空表示每天
+    #[serde(default)]
+    pub weekdays: Vec<u32>,
+}
+
+impl QuietWindow {
+    pub fn contains(&self, weekday: u32, minute: u32) -> bool {
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else {
+            return false;
+        };
+        let day_ok = |d: u32| self.weekdays.is_empty() || self.weekdays.contains(&d);
+        if start <= end {
+            day_ok(weekday) && (start..end).contains(&minute)
+        } else {
+            let yesterday = if weekday == 1 { 7 } else { weekday - 1 };
+            (minute >= start && day_ok(weekday)) || (minute < end && day_ok(yesterday))
+        }
+    }
+}
+
+fn parse_hhmm(s: &str) -> Option<u32> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnoozeConfig {
+    pub quiet_hours: Vec<QuietWindow>,
+    /// 这些进程运行时不锁屏（按进程名匹配，仅 Linux）
+    pub inhibit_apps: Vec<String>,
+    /// 有全屏窗口时不锁屏（仅 Linux X11）
+    pub inhibit_fullscreen: bool,
+    /// 暂停状态文件，缺省为 `$XDG_STATE_HOME/blue-lock/snooze.json`
+    pub state_path: Option<PathBuf>,
+}
+
+/// 持久化的暂停状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnoozeState {
+    pub snooze: Option<Snooze>,
+}
+
+/// 当前不锁屏的原因
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SnoozeReason {
+    Snoozed { snooze: Snooze },
+    QuietHours { window: QuietWindow },
+    App { name: String },
+    Fullscreen,
+}
+
+impl Display for SnoozeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Snoozed {
+                snooze: Snooze::Until { until },
+            } => {
+                let mins = (until - now_millis()).max(0) / 60_000 + 1;
+                write!(f, "已暂停，约 {} 分钟后恢复", mins)
+            }
+            Self::Snoozed {
+                snooze: Snooze::UntilUnlock,
+            } => write!(f, "已暂停，下次解锁后恢复"),
+            Self::Snoozed {
+                snooze: Snooze::Indefinite,
+            } => write!(f, "已暂停"),
+            Self::QuietHours { window } => {
+                write!(f, "免打扰时段 {}-{}", window.start, window.end)
+            }
+            Self::App { name } => write!(f, "{} 运行中", name),
+            Self::Fullscreen => write!(f, "全屏中"),
+        }
+    }
+}
+
+/// 不含应用/全屏检测的判断：手动暂停优先，其次免打扰时段
+pub fn evaluate(
+    state: &SnoozeState,
+    config: &SnoozeConfig,
+    now: i64,
+    weekday: u32,
+    minute: u32,
+) -> Option<SnoozeReason> {
+    if let Some(snooze) = state.snooze.filter(|s| !s.is_expired(now)) {
+        return Some(SnoozeReason::Snoozed { snooze });
+    }
+    config
+        .quiet_hours
+        .iter()
+        .find(|w| w.contains(weekday, minute))
+        .map(|w| SnoozeReason::QuietHours { window: w.clone() })
+}
+
+fn state_path(config: &SnoozeConfig) -> PathBuf {
+    config
+        .state_path
+        .clone()
+        .unwrap_or_else(|| default_state_path("snooze.json"))
+}
+
+pub struct SnoozeManager {
+    config: RwLock<SnoozeConfig>,
+    store: RwLock<JsonStore<SnoozeState>>,
+    state_tx: watch::Sender<SnoozeState>,
+    /// 上一次观察到的会话锁定状态
+    session_locked: Mutex<Option<bool>>,
+}
+
+impl SnoozeManager {
+    pub fn load(config: SnoozeConfig) -> Self {
+        let store = JsonStore::new(state_path(&config));
+        let mut state: SnoozeState = store.load();
+        if state.snooze.is_some_and(|s| s.is_expired(now_millis())) {
+            state.snooze = None;
+        }
+        if let Some(s) = &state.snooze {
+            info!("Restored snooze {:?}", s);
+        }
+        let (state_tx, _) = watch::channel(state);
+        Self {
+            config: RwLock::new(config),
+            store: RwLock::new(store),
+            state_tx,
+            session_locked: Mutex::new(None),
+        }
+    }
+
+    /// 替换配置，立即生效；状态文件位置变化时把当前暂停写到新位置
+    pub fn reconfigure(&self, config: SnoozeConfig) {
+        let path = state_path(&config);
+        {
+            let mut store = self.store.write().unwrap_or_else(|e| e.into_inner());
+            if store.path() != path {
+                let moved = JsonStore::new(path);
+                if let Err(e) = moved.save(&self.state_tx.borrow()) {
+                    warn!("Failed to persist snooze state: {}", e);
+                }
+                *store = moved;
+            }
+        }
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// 当前手动暂停（已过期的视为无）
+    pub fn current(&self) -> Option<Snooze> {
+        self.state_tx
+            .borrow()
+            .snooze
+            .filter(|s| !s.is_expired(now_millis()))
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<SnoozeState> {
+        self.state_tx.subscribe()
+    }
+
+    /// `None` 表示取消暂停
+    pub fn set(&self, snooze: Option<Snooze>) {
+        let state = SnoozeState { snooze };
+        let store = self.store.read().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = store.save(&state) {
+            warn!("Failed to persist snooze state: {}", e);
+        }
+        self.state_tx.send_replace(state);
+    }
+
+    /// 清理到期的定时暂停，并在会话解锁后结束“直到解锁”的暂停
+    pub fn tick(&self) {
+        let snooze = self.state_tx.borrow().snooze;
+        if snooze.is_some_and(|s| s.is_expired(now_millis())) {
+            info!("Snooze expired");
+            self.set(None);
+            return;
+        }
+
+        let locked = inhibit::session_locked();
+        let was_locked = std::mem::replace(
+            &mut *self
+                .session_locked
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+            locked,
+        );
+        if was_locked == Some(true) && locked == Some(false) && snooze == Some(Snooze::UntilUnlock)
+        {
+            info!("Session unlocked, snooze ended");
+            self.set(None);
+        }
+    }
+
+    /// 结束“直到解锁”的暂停：本程序解锁或目标确认回来时调用，
+    /// 不依赖只在 Linux 上可用的会话锁定探测
+    pub fn end_until_unlock(&self) {
+        if self.state_tx.borrow().snooze == Some(Snooze::UntilUnlock) {
+            info!("Target is back, snooze ended");
+            self.set(None);
+        }
+    }
+
+    /// 完整判断是否应跳过锁屏，包含应用与全屏检测
+    pub async fn reason(&self) -> Option<SnoozeReason> {
+        let now = Local::now();
+        let state = self.state_tx.borrow().clone();
+        let config = self
+            .config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(reason) = evaluate(
+            &state,
+            &config,
+            now_millis(),
+            now.weekday().number_from_monday(),
+            now.hour() * 60 + now.minute(),
+        ) {
+            return Some(reason);
+        }
+
+        let (apps, fullscreen) = (config.inhibit_apps, config.inhibit_fullscreen);
+        if apps.is_empty() && !fullscreen {
+            return None;
+        }
+        // 需要读 /proc 和调用 xprop，放到阻塞线程
+        tokio::task::spawn_blocking(move || {
+            if let Some(name) = inhibit::running_app(&apps) {
+                return Some(SnoozeReason::App { name });
+            }
+            (fullscreen && inhibit::fullscreen_active()).then_some(SnoozeReason::Fullscreen)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str, weekdays: Vec<u32>) -> QuietWindow {
+        QuietWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+            weekdays,
+        }
+    }
+
+    #[test]
+    fn test_quiet_window() {
+        let lunch = window("12:00", "13:30", vec![1, 2, 3, 4, 5]);
+        assert!(lunch.contains(1, 12 * 60));
+        assert!(!lunch.contains(1, 13 * 60 + 30));
+        assert!(!lunch.contains(6, 12 * 60 + 30));
+
+        // 跨零点：周五 22:00 开始到周六 07:00
+        let night = window("22:00", "07:00", vec![5]);
+        assert!(night.contains(5, 23 * 60));
+        assert!(night.contains(6, 6 * 60));
+        assert!(!night.contains(5, 6 * 60));
+        assert!(!night.contains(6, 23 * 60));
+
+        let monday_night = window("23:00", "01:00", vec![7]);
+        assert!(monday_night.contains(1, 30));
+
+        assert!(!window("25:00", "07:00", vec![]).contains(1, 0));
+    }
+
+    #[test]
+    fn test_evaluate_priority_and_expiry() {
+        let config = SnoozeConfig {
+            quiet_hours: vec![window("00:00", "23:59", vec![])],
+            ..Default::default()
+        };
+        let snoozed = SnoozeState {
+            snooze: Some(Snooze::Until { until: 1_000 }),
+        };
+        assert!(matches!(
+            evaluate(&snoozed, &config, 500, 1, 60),
+            Some(SnoozeReason::Snoozed { .. })
+        ));
+        // 到期后退回免打扰时段
+        assert!(matches!(
+            evaluate(&snoozed, &config, 1_000, 1, 60),
+            Some(SnoozeReason::QuietHours { .. })
+        ));
+        assert_eq!(
+            evaluate(&snoozed, &SnoozeConfig::default(), 1_000, 1, 60),
+            None
+        );
+        let until_unlock = SnoozeState {
+            snooze: Some(Snooze::UntilUnlock),
+        };
+        assert!(evaluate(&until_unlock, &SnoozeConfig::default(), i64::MAX, 1, 0).is_some());
+    }
+
+    #[test]
+    fn test_manager_persists() {
+        let path = std::env::temp_dir().join(format!("blue-lock-snooze-{}.json", now_millis()));
+        let config = SnoozeConfig {
+            state_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let manager = SnoozeManager::load(config.clone());
+        assert_eq!(manager.current(), None);
+        manager.set(Some(Snooze::UntilUnlock));
+
+        let restored = SnoozeManager::load(config.clone());
+        assert_eq!(restored.current(), Some(Snooze::UntilUnlock));
+
+        // 过期的定时暂停重启后不再生效
+        restored.set(Some(Snooze::Until { until: 1 }));
+        assert_eq!(SnoozeManager::load(config).current(), None);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_applies_live() {
+        let dir = std::env::temp_dir().join(format!("blue-lock-snooze-live-{}", now_millis()));
+        let config = SnoozeConfig {
+            state_path: Some(dir.join("a.json")),
+            ..Default::default()
+        };
+        let manager = SnoozeManager::load(config);
+        assert_eq!(manager.reason().await, None);
+        manager.set(Some(Snooze::UntilUnlock));
+
+        // 新的免打扰时段立即生效（两段合起来覆盖全天），暂停状态跟随到新文件
+        manager.reconfigure(SnoozeConfig {
+            quiet_hours: vec![
+                window("00:00", "23:59", vec![]),
+                window("23:59", "00:00", vec![]),
+            ],
+            state_path: Some(dir.join("b.json")),
+            ..Default::default()
+        });
+        let restored = SnoozeManager::load(SnoozeConfig {
+            state_path: Some(dir.join("b.json")),
+            ..Default::default()
+        });
+        assert_eq!(restored.current(), Some(Snooze::UntilUnlock));
+        manager.set(None);
+        assert!(matches!(
+            manager.reason().await,
+            Some(SnoozeReason::QuietHours { .. })
+        ));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_until_unlock_ends_on_return() {
+        let path =
+            std::env::temp_dir().join(format!("blue-lock-snooze-unlock-{}.json", now_millis()));
+        let manager = SnoozeManager::load(SnoozeConfig {
+            state_path: Some(path.clone()),
+            ..Default::default()
+        });
+        let until = Snooze::Until { until: i64::MAX };
+        manager.set(Some(until));
+        manager.end_until_unlock();
+        assert_eq!(manager.current(), Some(until));
+
+        manager.set(Some(Snooze::UntilUnlock));
+        manager.end_until_unlock();
+        assert_eq!(manager.current(), None);
+        let _ = std::fs::remove_file(path);
+    }
+}
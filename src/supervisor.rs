@@ -0,0 +1,302 @@
+//! 后台任务的生命周期管理
+//!
+//! 所有常驻任务都由 `Supervisor` 启动并持有 `JoinHandle`，共享同一个取消令牌。
+//! 可重启任务出错或 panic 后按指数退避重新启动并广播 `TaskFailure`，关闭时先取消、再等待退出，
+//! 超时的任务直接中止。
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{broadcast, OwnedMutexGuard},
+    task::JoinHandle,
+    time::Instant,
+};
+use tokio_util::{sync::CancellationToken, task::AbortOnDropHandle};
+use tracing::{error, info, warn};
+
+use crate::errors::AppResult;
+
+/// 重启退避
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 可重启任务的一次异常退出，任务随后会按退避重启
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskFailure {
+    pub task: &'static str,
+    pub error: String,
+}
+
+pub struct Supervisor {
+    token: CancellationToken,
+    backoff: Backoff,
+    tasks: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+    failures: broadcast::Sender<TaskFailure>,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new(Backoff::default())
+    }
+}
+
+impl Supervisor {
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            backoff,
+            tasks: Mutex::new(Vec::new()),
+            failures: broadcast::channel(16).0,
+        }
+    }
+
+    /// 子级监督者：随本级一起取消，也可单独关闭后重建，用于随设置重启的一组任务
+    pub fn child(&self) -> Self {
+        Self {
+            token: self.token.child_token(),
+            backoff: self.backoff,
+            tasks: Mutex::new(Vec::new()),
+            failures: self.failures.clone(),
+        }
+    }
+
+    /// 订阅本级及子级任务的异常退出
+    pub fn subscribe_failures(&self) -> broadcast::Receiver<TaskFailure> {
+        self.failures.subscribe()
+    }
+
+    /// 关闭时会被取消的令牌
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 启动可重启任务：返回 `Ok` 视为正常结束，出错或 panic 时退避后用 `make` 重新创建
+    pub fn spawn<F, Fut>(&self, name: &'static str, mut make: F)
+    where
+        F: FnMut(CancellationToken) -> Fut + Send + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        let token = self.token.clone();
+        let backoff = self.backoff;
+        let failures = self.failures.clone();
+        let handle = tokio::spawn(async move {
+            let mut delay = backoff.initial;
+            loop {
+                let started = Instant::now();
+                // 监督任务被中止时连带中止当前实例
+                let run = AbortOnDropHandle::new(tokio::spawn(make(token.clone())));
+                let error = match run.await {
+                    Ok(Ok(())) => break,
+                    Ok(Err(e)) => e.to_string(),
+                    Err(e) if e.is_cancelled() => break,
+                    Err(e) => format!("panicked: {}", e),
+                };
+                if token.is_cancelled() {
+                    break;
+                }
+                error!("Task {} exited: {}", name, error);
+                let _ = failures.send(TaskFailure { task: name, error });
+                // 稳定运行过一段时间后，退避从头计算
+                if started.elapsed() >= backoff.max {
+                    delay = backoff.initial;
+                }
+                info!("Restarting task {} in {:?}", name, delay);
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = token.cancelled() => break,
+                }
+                delay = (delay * 2).min(backoff.max);
+            }
+        });
+        self.track(name, handle);
+    }
+
+    /// 启动独占 `resource`（如 channel 接收端）的可重启任务：每个实例启动时取得资源，
+    /// 退出或 panic 时归还，下一个实例接着使用
+    pub fn spawn_with<T, F, Fut>(&self, name: &'static str, resource: T, make: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(OwnedMutexGuard<T>, CancellationToken) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        let resource = Arc::new(tokio::sync::Mutex::new(resource));
+        self.spawn(name, move |token| {
+            let (resource, make) = (resource.clone(), make.clone());
+            async move { make(resource.lock_owned().await, token).await }
+        });
+    }
+
+    /// 启动不可重启的任务，任务需自行响应取消
+    pub fn spawn_once<F, Fut>(&self, name: &'static str, make: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(make(self.token.clone()));
+        self.track(name, handle);
+    }
+
+    /// 托管已启动的任务
+    pub fn track(&self, name: &'static str, handle: JoinHandle<()>) {
+        self.tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, handle));
+    }
+
+    /// 取消所有任务并在 `grace` 内等待退出，超时的任务直接中止
+    pub async fn shutdown(&self, grace: Duration) {
+        self.token.cancel();
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        let deadline = tokio::time::Instant::now() + grace;
+        for (name, mut handle) in tasks {
+            if tokio::time::timeout_at(deadline, &mut handle)
+                .await
+                .is_err()
+            {
+                warn!("Task {} did not stop in time, aborting", name);
+                handle.abort();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::errors::AppError;
+
+    use super::*;
+
+    fn fast() -> Supervisor {
+        Supervisor::new(Backoff {
+            initial: Duration::from_millis(5),
+            max: Duration::from_millis(20),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_until_ok() {
+        let supervisor = fast();
+        let mut failures = supervisor.subscribe_failures();
+        let started = Instant::now();
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        supervisor.spawn("flaky", {
+            let runs = runs.clone();
+            move |_| {
+                let mut runs = runs.lock().unwrap();
+                runs.push(started.elapsed());
+                let n = runs.len();
+                async move {
+                    match n {
+                        1 => Err(AppError::ProcessingError { r: "boom".into() }),
+                        2 => panic!("boom"),
+                        _ => Ok(()),
+                    }
+                }
+            }
+        });
+        assert_eq!(failures.recv().await.unwrap().task, "flaky");
+        assert!(failures.recv().await.unwrap().error.contains("panicked"));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        // 退避从 5ms 开始翻倍，正常结束后不再重启
+        assert_eq!(
+            *runs.lock().unwrap(),
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(5),
+                Duration::from_millis(15)
+            ]
+        );
+        supervisor.shutdown(Duration::from_secs(1)).await;
+        assert!(failures.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_keeps_resource() {
+        let supervisor = fast();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u32>();
+        let (seen_tx, mut seen) = tokio::sync::mpsc::unbounded_channel();
+        supervisor.spawn_with("consumer", rx, move |mut rx, token| async move {
+            loop {
+                let n = tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    Some(n) = rx.recv() => n,
+                };
+                if n == 0 {
+                    panic!("boom");
+                }
+                let _ = seen_tx.send(n);
+            }
+        });
+        tx.send(1).unwrap();
+        tx.send(0).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(seen.recv().await, Some(1));
+        // panic 后重启的实例接着读同一个接收端
+        tokio::time::advance(Duration::from_millis(5)).await;
+        assert_eq!(seen.recv().await, Some(2));
+        supervisor.shutdown(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_cancels_and_aborts() {
+        let supervisor = fast();
+        let stopped = Arc::new(AtomicUsize::new(0));
+        supervisor.spawn("cooperative", {
+            let stopped = stopped.clone();
+            move |token| {
+                let stopped = stopped.clone();
+                async move {
+                    token.cancelled().await;
+                    stopped.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            }
+        });
+        // 不响应取消的任务在超时后被中止
+        supervisor.spawn_once("stubborn", |_| std::future::pending());
+
+        let started = Instant::now();
+        supervisor.shutdown(Duration::from_millis(100)).await;
+        assert_eq!(stopped.load(Ordering::SeqCst), 1);
+        assert_eq!(started.elapsed(), Duration::from_millis(100));
+        assert!(supervisor.is_cancelled());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_child_shutdown() {
+        let supervisor = fast();
+        let child = supervisor.child();
+        child.spawn_once("child", |token| async move { token.cancelled().await });
+        child.shutdown(Duration::from_millis(100)).await;
+        assert!(child.is_cancelled());
+        assert!(!supervisor.is_cancelled());
+
+        // 关闭本级时子级一并取消
+        let child = supervisor.child();
+        supervisor.shutdown(Duration::from_millis(100)).await;
+        assert!(child.is_cancelled());
+    }
+}
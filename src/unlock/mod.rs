@@ -0,0 +1,385 @@
+//! 靠近解锁的安全检查
+//!
+//! 仅凭蓝牙在场就解锁并不安全：拿到手机的人，或中继手机广播的攻击者都能解锁。
+//! 确认回来后还需同时满足：最近出现连续的 `MovingCloser` 预测、RSSI 足够强且足够新，
+//! 并通过设备验证（配套应用的 GATT 质询应答，或至少设备已配对并保持连接）。
+//! 任一条件不满足或无法判断时保持锁定，由用户手动解锁，即退回仅锁屏。
+
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    ble::{baseline::Thresholds, scanner::DeviceScanner},
+    domain::value_objects::ModelResult,
+    errors::AppResult,
+    runtime::LockReason,
+};
+
+pub mod verifier;
+
+use verifier::{ChallengeConfig, ChallengeVerifier, ConnectedVerifier};
+
+/// 只保留这么久的预测
+const PREDICTION_HISTORY_MS: i64 = 5 * 60_000;
+
+/// 目标回到附近时是否解锁
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockPolicy {
+    /// 只锁屏，从不自动解锁
+    #[default]
+    LockOnly,
+    /// 靠近且设备已配对并保持连接时解锁，无法抵御拿到手机的人
+    #[serde(alias = "proximity")]
+    Connected,
+    /// 靠近且配套应用通过 GATT 质询应答时解锁
+    Challenge,
+}
+
+/// 靠近解锁需满足的条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProximityConfig {
+    /// 检查最近多少秒内的模型预测
+    pub closer_window_secs: u64,
+    /// 其中需连续出现的 `MovingCloser` 次数
+    pub min_closer: usize,
+    /// RSSI 至少高于在场阈值多少 dB
+    pub margin_db: f32,
+    /// 最近一次读数距今不超过多少毫秒
+    pub max_rssi_age_ms: i64,
+    /// `UnlockPolicy::Challenge` 时必填
+    pub challenge: Option<ChallengeConfig>,
+}
+
+impl Default for ProximityConfig {
+    fn default() -> Self {
+        Self {
+            closer_window_secs: 30,
+            min_closer: 2,
+            margin_db: 5.0,
+            max_rssi_age_ms: 2_000,
+            challenge: None,
+        }
+    }
+}
+
+/// 确认设备确实在用户手中
+#[async_trait::async_trait]
+pub trait DeviceVerifier: Send + Sync {
+    /// `address` 为受信任设备绑定的地址或外设 ID。通过返回 `Ok(true)`；
+    /// 应答不符、未连接或找不到该设备返回 `Ok(false)`
+    async fn verify(&self, address: &str) -> AppResult<bool>;
+}
+
+/// 解锁前收集的预测与读数，同一次广播只计一次
+#[derive(Debug, Default)]
+pub struct ProximityEvidence {
+    /// (预测时间, 当时最近一次广播的时间, 结果)
+    predictions: VecDeque<(i64, Option<i64>, ModelResult)>,
+    last_rssi: Option<(i64, f32)>,
+}
+
+impl ProximityEvidence {
+    pub fn observe_prediction(&mut self, ts: i64, result: ModelResult) {
+        let advert = self.last_rssi.map(|(ts, _)| ts);
+        // 没有新广播时的重复预测不算再次靠近
+        if advert.is_some()
+            && self
+                .predictions
+                .back()
+                .is_some_and(|(_, last, _)| *last == advert)
+        {
+            return;
+        }
+        self.predictions.push_back((ts, advert, result));
+        while let Some((first, _, _)) = self.predictions.front() {
+            if ts - first <= PREDICTION_HISTORY_MS {
+                break;
+            }
+            self.predictions.pop_front();
+        }
+    }
+
+    /// 时间戳不晚于上一次的读数视为重复投递，不刷新
+    pub fn observe_rssi(&mut self, ts: i64, rssi: f32) {
+        if self.last_rssi.is_some_and(|(last, _)| ts <= last) {
+            return;
+        }
+        self.last_rssi = Some((ts, rssi));
+    }
+
+    /// 切换目标后清空
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 最近 `window` 内连续 `MovingCloser` 的最长次数
+    fn closer_run(&self, now: i64, window: Duration) -> usize {
+        let from = now - window.as_millis() as i64;
+        let (mut run, mut longest) = (0, 0);
+        for (_, _, result) in self.predictions.iter().filter(|(ts, _, _)| *ts >= from) {
+            run = if *result == ModelResult::MovingCloser {
+                run + 1
+            } else {
+                0
+            };
+            longest = longest.max(run);
+        }
+        longest
+    }
+
+    /// 检查设备验证以外的条件，不满足时返回原因
+    pub fn check(
+        &self,
+        now: i64,
+        thresholds: &Thresholds,
+        config: &ProximityConfig,
+    ) -> Result<(), String> {
+        let window = Duration::from_secs(config.closer_window_secs);
+        let run = self.closer_run(now, window);
+        if run < config.min_closer.max(1) {
+            return Err(format!(
+                "最近 {} 秒内没有连续 {} 次靠近",
+                window.as_secs(),
+                config.min_closer.max(1)
+            ));
+        }
+        let Some((ts, rssi)) = self.last_rssi else {
+            return Err("没有信号读数".to_string());
+        };
+        if now - ts > config.max_rssi_age_ms {
+            return Err(format!("最近一次读数已过去 {} 毫秒", now - ts));
+        }
+        let required = thresholds.present + config.margin_db;
+        if rssi < required {
+            return Err(format!("信号 {:.0} dBm 低于 {:.0} dBm", rssi, required));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnlockDecision {
+    Unlock(LockReason),
+    /// 保持锁定的原因
+    Deny(String),
+}
+
+/// 按策略组合各项检查，得出是否解锁
+pub struct UnlockGate {
+    policy: UnlockPolicy,
+    config: ProximityConfig,
+    verifier: Option<Arc<dyn DeviceVerifier>>,
+}
+
+impl UnlockGate {
+    pub fn new(
+        policy: UnlockPolicy,
+        config: ProximityConfig,
+        verifier: Option<Arc<dyn DeviceVerifier>>,
+    ) -> Self {
+        Self {
+            policy,
+            config,
+            verifier,
+        }
+    }
+
+    /// 按策略创建验证方式；`Challenge` 缺少质询参数时没有验证方式，不会解锁
+    pub fn from_config(
+        policy: UnlockPolicy,
+        config: ProximityConfig,
+        scanner: Arc<DeviceScanner>,
+    ) -> Self {
+        let verifier: Option<Arc<dyn DeviceVerifier>> = match (policy, &config.challenge) {
+            (UnlockPolicy::LockOnly, _) => None,
+            (UnlockPolicy::Connected, _) => Some(Arc::new(ConnectedVerifier::new(scanner))),
+            (UnlockPolicy::Challenge, Some(c)) => {
+                Some(Arc::new(ChallengeVerifier::new(scanner, c.clone())))
+            }
+            (UnlockPolicy::Challenge, None) => {
+                warn!("Unlock policy is challenge but no challenge is configured, lock only");
+                None
+            }
+        };
+        Self::new(policy, config, verifier)
+    }
+
+    /// 确认回来后最多等待多久
+    pub fn wait(&self) -> Duration {
+        Duration::from_secs(self.config.closer_window_secs)
+    }
+
+    /// 是否可以做出决定：没有验证方式时立即可以（结果为保持锁定），否则需先满足信号条件
+    pub fn ready(
+        &self,
+        evidence: &ProximityEvidence,
+        now: i64,
+        thresholds: &Thresholds,
+    ) -> Result<(), String> {
+        if self.verifier.is_none() {
+            return Ok(());
+        }
+        evidence.check(now, thresholds, &self.config)
+    }
+
+    /// `address` 为目标设备绑定的地址或外设 ID，见 `TrustedDevice::address`
+    pub async fn decide(
+        &self,
+        address: &str,
+        evidence: &ProximityEvidence,
+        now: i64,
+        thresholds: &Thresholds,
+    ) -> UnlockDecision {
+        let Some(verifier) = self.verifier.as_ref() else {
+            return UnlockDecision::Deny(match self.policy {
+                UnlockPolicy::LockOnly => "策略为只锁屏".to_string(),
+                _ => "未配置设备验证".to_string(),
+            });
+        };
+        if let Err(reason) = evidence.check(now, thresholds, &self.config) {
+            return UnlockDecision::Deny(reason);
+        }
+        let method = match self.policy {
+            UnlockPolicy::Challenge => "质询应答",
+            _ => "设备连接",
+        };
+        match verifier.verify(address).await {
+            Ok(true) => UnlockDecision::Unlock(LockReason::Verified {
+                policy: self.policy,
+            }),
+            Ok(false) => UnlockDecision::Deny(format!("{}验证未通过", method)),
+            Err(e) => UnlockDecision::Deny(format!("{}验证失败：{}", method, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// 固定结果并记录调用次数
+    struct FakeVerifier {
+        result: bool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl DeviceVerifier for FakeVerifier {
+        async fn verify(&self, _address: &str) -> AppResult<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.result)
+        }
+    }
+
+    fn gate(policy: UnlockPolicy, result: bool) -> (UnlockGate, Arc<FakeVerifier>) {
+        let verifier = Arc::new(FakeVerifier {
+            result,
+            calls: AtomicUsize::new(0),
+        });
+        let gate = UnlockGate::new(policy, ProximityConfig::default(), Some(verifier.clone()));
+        (gate, verifier)
+    }
+
+    /// 阈值 -70，需 -65 以上
+    fn approaching(now: i64, rssi: f32) -> ProximityEvidence {
+        let mut evidence = ProximityEvidence::default();
+        evidence.observe_prediction(now - 20_000, ModelResult::Stationary);
+        evidence.observe_prediction(now - 10_000, ModelResult::MovingCloser);
+        evidence.observe_prediction(now - 5_000, ModelResult::MovingCloser);
+        evidence.observe_rssi(now - 500, rssi);
+        evidence
+    }
+
+    #[tokio::test]
+    async fn test_unlock_requires_all_checks() {
+        let now = 1_000_000;
+        let t = Thresholds::fixed(-70.0);
+        let (g, verifier) = gate(UnlockPolicy::Challenge, true);
+        assert!(matches!(
+            g.decide("abc", &approaching(now, -60.0), now, &t).await,
+            UnlockDecision::Unlock(_)
+        ));
+
+        // 信号不够强
+        let decision = g.decide("abc", &approaching(now, -68.0), now, &t).await;
+        assert!(matches!(decision, UnlockDecision::Deny(_)));
+        // 读数太旧
+        let decision = g
+            .decide("abc", &approaching(now, -60.0), now + 5_000, &t)
+            .await;
+        assert!(matches!(decision, UnlockDecision::Deny(_)));
+        // 之前的连续靠近仍在窗口内
+        let mut evidence = approaching(now, -60.0);
+        evidence.observe_prediction(now - 1_000, ModelResult::Stationary);
+        evidence.observe_prediction(now - 500, ModelResult::MovingCloser);
+        assert!(matches!(
+            g.decide("abc", &evidence, now, &t).await,
+            UnlockDecision::Unlock(_)
+        ));
+        // 靠近被打断，没有连续两次
+        let mut evidence = ProximityEvidence::default();
+        evidence.observe_prediction(now - 2_000, ModelResult::MovingCloser);
+        evidence.observe_prediction(now - 1_000, ModelResult::Stationary);
+        evidence.observe_prediction(now - 500, ModelResult::MovingCloser);
+        evidence.observe_rssi(now, -50.0);
+        assert!(matches!(
+            g.decide("abc", &evidence, now, &t).await,
+            UnlockDecision::Deny(_)
+        ));
+        // 信号条件不满足时不做设备验证
+        assert_eq!(verifier.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_advertisements_count_once() {
+        let now = 1_000_000;
+        let t = Thresholds::fixed(-70.0);
+        let (g, verifier) = gate(UnlockPolicy::Challenge, true);
+
+        // 同一次广播重复投递：读数不刷新，对应的第二次预测也不计入
+        let mut evidence = ProximityEvidence::default();
+        evidence.observe_rssi(now - 5_000, -60.0);
+        evidence.observe_prediction(now - 4_000, ModelResult::MovingCloser);
+        evidence.observe_rssi(now - 5_000, -60.0);
+        evidence.observe_prediction(now - 500, ModelResult::MovingCloser);
+        assert!(matches!(
+            g.decide("AA:BB:CC:DD:EE:FF", &evidence, now, &t).await,
+            UnlockDecision::Deny(_)
+        ));
+        assert_eq!(verifier.calls.load(Ordering::SeqCst), 0);
+
+        // 收到新的广播后才算第二次靠近
+        evidence.observe_rssi(now - 300, -60.0);
+        evidence.observe_prediction(now - 200, ModelResult::MovingCloser);
+        assert!(matches!(
+            g.decide("AA:BB:CC:DD:EE:FF", &evidence, now, &t).await,
+            UnlockDecision::Unlock(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_lock_only() {
+        let now = 1_000_000;
+        let t = Thresholds::fixed(-70.0);
+        let evidence = approaching(now, -50.0);
+
+        let (g, _) = gate(UnlockPolicy::Connected, false);
+        assert!(matches!(
+            g.decide("abc", &evidence, now, &t).await,
+            UnlockDecision::Deny(_)
+        ));
+
+        // 没有验证方式时一律不解锁
+        let g = UnlockGate::new(UnlockPolicy::Challenge, ProximityConfig::default(), None);
+        assert!(matches!(
+            g.decide("abc", &evidence, now, &t).await,
+            UnlockDecision::Deny(_)
+        ));
+    }
+}
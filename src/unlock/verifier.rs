@@ -0,0 +1,127 @@
+use std::{sync::Arc, time::Duration};
+
+use hmac::{Hmac, Mac};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    ble::scanner::DeviceScanner,
+    errors::{AppResult, BleError},
+};
+
+use super::DeviceVerifier;
+
+const NONCE_LEN: usize = 16;
+
+/// 配套应用提供的质询特征：本方写入随机数，应用以共享密钥计算 HMAC-SHA256 后供本方读取
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeConfig {
+    pub service: Uuid,
+    pub characteristic: Uuid,
+    /// 与配套应用共享的密钥，至少 16 个字符
+    pub secret: String,
+    /// 连接、写入与读回的总时限，限制中继转发的余地
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_timeout_ms() -> u64 {
+    3_000
+}
+
+/// 配套应用对质询的应答
+pub fn challenge_response(secret: &[u8], nonce: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(nonce);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 常量时间比较应答
+pub fn verify_response(secret: &[u8], nonce: &[u8], response: &[u8]) -> bool {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(nonce);
+    mac.verify_slice(response).is_ok()
+}
+
+/// 目标设备必须与本机保持连接。btleplug 无法跨平台查询配对状态，
+/// 以系统已建立的连接近似：未配对的设备通常不会保持连接
+pub struct ConnectedVerifier {
+    scanner: Arc<DeviceScanner>,
+}
+
+impl ConnectedVerifier {
+    pub fn new(scanner: Arc<DeviceScanner>) -> Self {
+        Self { scanner }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceVerifier for ConnectedVerifier {
+    async fn verify(&self, address: &str) -> AppResult<bool> {
+        match self.scanner.is_connected(address).await {
+            Ok(connected) => Ok(connected),
+            Err(BleError::DeviceNotFound { .. }) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// 通过配套应用的 GATT 质询应答验证
+pub struct ChallengeVerifier {
+    scanner: Arc<DeviceScanner>,
+    config: ChallengeConfig,
+}
+
+impl ChallengeVerifier {
+    pub fn new(scanner: Arc<DeviceScanner>, config: ChallengeConfig) -> Self {
+        Self { scanner, config }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeviceVerifier for ChallengeVerifier {
+    async fn verify(&self, address: &str) -> AppResult<bool> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let response = self
+            .scanner
+            .challenge(
+                address,
+                self.config.service,
+                self.config.characteristic,
+                &nonce,
+                Duration::from_millis(self.config.timeout_ms),
+            )
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(BleError::DeviceNotFound { .. }) => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(verify_response(
+            self.config.secret.as_bytes(),
+            &nonce,
+            &response,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_response() {
+        let secret = b"0123456789abcdef0123";
+        let nonce = [7u8; NONCE_LEN];
+        let response = challenge_response(secret, &nonce);
+        assert_eq!(response.len(), 32);
+        assert!(verify_response(secret, &nonce, &response));
+        // 重放旧应答或密钥不符都不通过
+        assert!(!verify_response(secret, &[8u8; NONCE_LEN], &response));
+        assert!(!verify_response(b"another secret!!", &nonce, &response));
+        assert!(!verify_response(secret, &nonce, &response[..16]));
+    }
+}
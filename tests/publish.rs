@@ -0,0 +1,283 @@
+//! webhook 与 MQTT 出口的集成测试，分别对接本地的模拟 HTTP 服务和 MQTT broker
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::BytesMut;
+use dioxus_demo::{
+    publish::{
+        mqtt::{MqttConfig, MqttSink},
+        queue::OfflineQueue,
+        run_sink,
+        webhook::{WebhookConfig, WebhookSink},
+        EventMapper, EventSink, Message, RetryConfig,
+    },
+    runtime::RuntimeEvent,
+    supervisor::Supervisor,
+};
+use rumqttc::{ConnAck, ConnectReturnCode, Packet, PubAck, QoS};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_util::sync::CancellationToken;
+
+type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+fn presence(ts: i64, present: bool) -> Message {
+    EventMapper
+        .map(&RuntimeEvent::Presence { ts, present }, "desk-phone")
+        .unwrap()
+}
+
+fn fast_retry(max_attempts: u32) -> RetryConfig {
+    RetryConfig {
+        max_attempts,
+        initial_backoff_ms: 10,
+        max_backoff_ms: 50,
+    }
+}
+
+/// 依次用 `statuses` 回应请求，之后都回 200；记录路径和请求体
+async fn mock_http(listener: TcpListener, statuses: Vec<u16>) -> Requests {
+    let requests = Requests::default();
+    let recorded = requests.clone();
+    tokio::spawn(async move {
+        let mut statuses = statuses.into_iter();
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let Some((path, body)) = read_request(&mut stream).await else {
+                continue;
+            };
+            recorded.lock().unwrap().push((path, body));
+            let status = statuses.next().unwrap_or(200);
+            let response = format!(
+                "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    requests
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let path = head.split_whitespace().nth(1)?.to_string();
+    let len = head
+        .lines()
+        .find_map(|l| {
+            let (name, value) = l.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())?
+        })
+        .unwrap_or(0);
+    while buf.len() < header_end + len {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Some((
+        path,
+        String::from_utf8_lossy(&buf[header_end..]).to_string(),
+    ))
+}
+
+async fn wait_for<F: Fn() -> bool>(check: F) {
+    for _ in 0..100 {
+        if check() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("timed out waiting for condition");
+}
+
+#[tokio::test]
+async fn test_webhook_retries_with_template() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = mock_http(listener, vec![500]).await;
+
+    let sink: Arc<dyn EventSink> = Arc::new(
+        WebhookSink::new(WebhookConfig {
+            url: format!("http://127.0.0.1:{}/hook/{{{{target}}}}", port),
+            body: Some(r#"{"on":{{present}},"kind":"{{kind}}"}"#.to_string()),
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let (tx, mut rx) = mpsc::channel(8);
+    let token = CancellationToken::new();
+    let task = tokio::spawn({
+        let (sink, queue, token) = (sink, OfflineQueue::new(None, 10), token.clone());
+        async move { run_sink(sink, &mut rx, queue, fast_retry(3), token).await }
+    });
+
+    tx.send(presence(1, true)).await.unwrap();
+    wait_for(|| requests.lock().unwrap().len() == 2).await;
+    token.cancel();
+    task.await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[1].0, "/hook/desk-phone");
+    assert_eq!(requests[1].1, r#"{"on":true,"kind":"presence"}"#);
+}
+
+#[tokio::test]
+async fn test_webhook_offline_queue_survives_restart() {
+    // 先占一个端口再释放，模拟服务未启动
+    let port = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let queue_path = std::env::temp_dir().join(format!(
+        "blue-lock-publish-{}-{}.json",
+        std::process::id(),
+        port
+    ));
+    let config = WebhookConfig {
+        url: format!("http://127.0.0.1:{}/events", port),
+        ..Default::default()
+    };
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let token = CancellationToken::new();
+    let task = tokio::spawn({
+        let (sink, queue, token) = (
+            Arc::new(WebhookSink::new(config.clone()).unwrap()),
+            OfflineQueue::new(Some(queue_path.clone()), 10),
+            token.clone(),
+        );
+        async move { run_sink(sink, &mut rx, queue, fast_retry(2), token).await }
+    });
+    tx.send(presence(1, false)).await.unwrap();
+    tx.send(presence(2, true)).await.unwrap();
+    wait_for(|| OfflineQueue::new(Some(queue_path.clone()), 10).len() == 2).await;
+    token.cancel();
+    task.await.unwrap();
+
+    // 服务恢复后，重新启动的出口按顺序补发
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    let requests = mock_http(listener, vec![]).await;
+    let (_tx, mut rx) = mpsc::channel(8);
+    let token = CancellationToken::new();
+    let task = tokio::spawn({
+        let (sink, queue, token) = (
+            Arc::new(WebhookSink::new(config).unwrap()),
+            OfflineQueue::new(Some(queue_path.clone()), 10),
+            token.clone(),
+        );
+        async move { run_sink(sink, &mut rx, queue, fast_retry(2), token).await }
+    });
+    wait_for(|| requests.lock().unwrap().len() == 2).await;
+    token.cancel();
+    task.await.unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[0].1.contains(r#""present":false"#));
+    assert!(requests[1].1.contains(r#""present":true"#));
+    assert!(OfflineQueue::new(Some(queue_path.clone()), 10).is_empty());
+    let _ = std::fs::remove_file(queue_path);
+}
+
+/// 只实现连接、发布与心跳的最小 broker，记录收到的主题和内容
+async fn mock_broker(listener: TcpListener) -> Requests {
+    let published = Requests::default();
+    let recorded = published.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let mut buf = BytesMut::new();
+                loop {
+                    let packet = match Packet::read(&mut buf, 1024 * 1024) {
+                        Ok(packet) => packet,
+                        Err(_) => {
+                            if stream.read_buf(&mut buf).await.unwrap_or(0) == 0 {
+                                return;
+                            }
+                            continue;
+                        }
+                    };
+                    let reply = match packet {
+                        Packet::Connect(_) => Some(Packet::ConnAck(ConnAck::new(
+                            ConnectReturnCode::Success,
+                            false,
+                        ))),
+                        Packet::Publish(p) => {
+                            recorded.lock().unwrap().push((
+                                p.topic.clone(),
+                                String::from_utf8_lossy(&p.payload).to_string(),
+                            ));
+                            (p.qos == QoS::AtLeastOnce).then(|| Packet::PubAck(PubAck::new(p.pkid)))
+                        }
+                        Packet::PingReq => Some(Packet::PingResp),
+                        _ => None,
+                    };
+                    if let Some(reply) = reply {
+                        let mut out = BytesMut::new();
+                        reply.write(&mut out).unwrap();
+                        if stream.write_all(&out).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+    });
+    published
+}
+
+#[tokio::test]
+async fn test_mqtt_publishes_templated_topic() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let published = mock_broker(listener).await;
+
+    let supervisor = Supervisor::default();
+    let sink = MqttSink::start(
+        MqttConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            topic: "desk/{{target}}/{{kind}}".to_string(),
+            payload: Some(r#"{"present":{{present}}}"#.to_string()),
+            ..Default::default()
+        },
+        &supervisor,
+    );
+
+    // 连上之前发布失败，由出口任务放进离线队列
+    let message = presence(1, true);
+    let mut sent = false;
+    for _ in 0..100 {
+        if sink.publish(&message).await.is_ok() {
+            sent = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(sent, "mqtt sink never connected");
+    wait_for(|| !published.lock().unwrap().is_empty()).await;
+    supervisor.shutdown(Duration::from_secs(1)).await;
+
+    let published = published.lock().unwrap();
+    assert_eq!(published[0].0, "desk/desk-phone/presence");
+    assert_eq!(published[0].1, r#"{"present":true}"#);
+}